@@ -1121,6 +1121,8 @@ impl Clone for BorrowRef<'_> {
 /// A wrapper type for an immutably borrowed value from a `RefCell<T>`.
 ///
 /// See the [module-level documentation](index.html) for more.
+#[cfg_attr(not(stage0),
+           must_not_suspend = "holding a Ref across a `yield` can cause `BorrowMutError`s")]
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct Ref<'b, T: ?Sized + 'b> {
     value: &'b T,
@@ -1350,6 +1352,8 @@ impl<'b> BorrowRefMut<'b> {
 /// A wrapper type for a mutably borrowed value from a `RefCell<T>`.
 ///
 /// See the [module-level documentation](index.html) for more.
+#[cfg_attr(not(stage0),
+           must_not_suspend = "holding a RefMut across a `yield` can cause `BorrowError`s")]
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct RefMut<'b, T: ?Sized + 'b> {
     value: &'b mut T,
@@ -129,6 +129,7 @@
 #![feature(reverse_bits)]
 #![feature(non_exhaustive)]
 #![feature(structural_match)]
+#![cfg_attr(not(stage0), feature(must_not_suspend))]
 
 #[prelude_import]
 #[allow(unused)]
@@ -480,8 +480,10 @@ define_dep_nodes!( <'tcx>
     // things read/modify that MIR.
     [] MirConstQualif(DefId),
     [] MirBuilt(DefId),
+    [] UnconditionalCalls(DefId),
     [] MirConst(DefId),
     [] MirValidated(DefId),
+    [] UnoptimizedMir(DefId),
     [] MirOptimized(DefId),
     [] MirShim { instance_def: InstanceDef<'tcx> },
 
@@ -35,7 +35,12 @@ impl_stable_hash_for!(struct mir::LocalDecl<'tcx> {
 impl_stable_hash_for!(struct mir::UpvarDecl { debug_name, var_hir_id, by_ref, mutability });
 impl_stable_hash_for!(struct mir::BasicBlockData<'tcx> { statements, terminator, is_cleanup });
 impl_stable_hash_for!(struct mir::UnsafetyViolation { source_info, description, details, kind });
-impl_stable_hash_for!(struct mir::UnsafetyCheckResult { violations, unsafe_blocks });
+impl_stable_hash_for!(struct mir::UnsafetyCheckResult {
+    violations,
+    unsafe_blocks,
+    unsafe_operations
+});
+impl_stable_hash_for!(struct mir::UnconditionalCall<'tcx> { callee, substs, span, formats });
 
 impl_stable_hash_for!(enum mir::BorrowKind {
     Shared,
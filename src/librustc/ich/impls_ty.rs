@@ -449,6 +449,7 @@ impl_stable_hash_for!(
         GeneratorResumedAfterPanic,
         ReferencedConstant,
         InfiniteLoop,
+        StepLimitReached,
         ReadUndefBytes(offset),
         InvalidDiscriminant(val),
         Panic { msg, file, line, col },
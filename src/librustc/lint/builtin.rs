@@ -32,6 +32,12 @@ declare_lint! {
     "constant evaluation detected erroneous expression"
 }
 
+declare_lint! {
+    pub UNCONDITIONAL_PANIC,
+    Warn,
+    "calls that always panic for the constant arguments they are passed"
+}
+
 declare_lint! {
     pub UNUSED_IMPORTS,
     Warn,
@@ -74,6 +80,12 @@ declare_lint! {
     "detect unused, unexported items"
 }
 
+declare_lint! {
+    pub UNUSED_WORKSPACE_ITEMS,
+    Warn,
+    "detect public items that no crate of the workspace uses, with -Z workspace-item-usage"
+}
+
 declare_lint! {
     pub UNREACHABLE_CODE,
     Warn,
@@ -129,6 +141,30 @@ declare_lint! {
     "detects trivial casts of numeric types which could be removed"
 }
 
+declare_lint! {
+    pub TRUNCATING_CASTS,
+    Allow,
+    "detects `as` casts to a smaller integer type, which drop the upper bits"
+}
+
+declare_lint! {
+    pub SIGN_CHANGING_CASTS,
+    Allow,
+    "detects `as` casts between signed and unsigned integer types that can change the value"
+}
+
+declare_lint! {
+    pub FLOAT_TO_INT_CASTS,
+    Allow,
+    "detects `as` casts from floats to integers, which round and clamp the value"
+}
+
+declare_lint! {
+    pub LOSSLESS_CASTS,
+    Allow,
+    "detects `as` casts between integer types that can't change the value, which `From` can do"
+}
+
 declare_lint! {
     pub PRIVATE_IN_PUBLIC,
     Warn,
@@ -324,6 +360,30 @@ declare_lint! {
     "warn about doc test in private item"
 }
 
+declare_lint! {
+    pub INVALID_HTML_TAGS,
+    Allow,
+    "detects unclosed or unopened HTML tags in documentation"
+}
+
+declare_lint! {
+    pub BARE_URLS,
+    Warn,
+    "detects URLs in documentation that are not turned into links"
+}
+
+declare_lint! {
+    pub UNCLOSED_CODE_BLOCKS,
+    Warn,
+    "detects code blocks in documentation whose fence is never closed"
+}
+
+declare_lint! {
+    pub BROKEN_FOOTNOTE_REFERENCES,
+    Warn,
+    "detects references to footnotes that documentation does not define"
+}
+
 declare_lint! {
     pub WHERE_CLAUSES_OBJECT_SAFETY,
     Warn,
@@ -356,6 +416,18 @@ declare_lint! {
     "outlives requirements can be inferred"
 }
 
+declare_lint! {
+    pub MUST_NOT_SUSPEND,
+    Warn,
+    "values of a `#[must_not_suspend]` type held across a `yield`"
+}
+
+declare_lint! {
+    pub UNDOCUMENTED_UNSAFE_BLOCKS,
+    Allow,
+    "`unsafe` blocks without a `// SAFETY:` comment explaining why they are sound"
+}
+
 /// Some lints that are buffered from `libsyntax`. See `syntax::early_buffered_lints`.
 pub mod parser {
     declare_lint! {
@@ -382,6 +454,7 @@ impl LintPass for HardwiredLints {
             UNUSED_VARIABLES,
             UNUSED_ASSIGNMENTS,
             DEAD_CODE,
+            UNUSED_WORKSPACE_ITEMS,
             UNREACHABLE_CODE,
             UNREACHABLE_PATTERNS,
             UNUSED_MACROS,
@@ -391,10 +464,15 @@ impl LintPass for HardwiredLints {
             UNKNOWN_CRATE_TYPES,
             TRIVIAL_CASTS,
             TRIVIAL_NUMERIC_CASTS,
+            TRUNCATING_CASTS,
+            SIGN_CHANGING_CASTS,
+            FLOAT_TO_INT_CASTS,
+            LOSSLESS_CASTS,
             PRIVATE_IN_PUBLIC,
             PUB_USE_OF_PRIVATE_EXTERN_CRATE,
             INVALID_TYPE_PARAM_DEFAULT,
             CONST_ERR,
+            UNCONDITIONAL_PANIC,
             RENAMED_AND_REMOVED_LINTS,
             SAFE_EXTERN_STATICS,
             SAFE_PACKED_BORROWS,
@@ -422,10 +500,16 @@ impl LintPass for HardwiredLints {
             INTRA_DOC_LINK_RESOLUTION_FAILURE,
             MISSING_DOC_CODE_EXAMPLES,
             PRIVATE_DOC_TESTS,
+            INVALID_HTML_TAGS,
+            BARE_URLS,
+            UNCLOSED_CODE_BLOCKS,
+            BROKEN_FOOTNOTE_REFERENCES,
             WHERE_CLAUSES_OBJECT_SAFETY,
             PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
             MACRO_USE_EXTERN_CRATE,
             MACRO_EXPANDED_MACRO_EXPORTS_ACCESSED_BY_ABSOLUTE_PATHS,
+            MUST_NOT_SUSPEND,
+            UNDOCUMENTED_UNSAFE_BLOCKS,
             parser::QUESTION_MARK_MACRO_SEP,
         )
     }
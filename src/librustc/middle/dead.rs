@@ -20,10 +20,16 @@ use hir::itemlikevisit::ItemLikeVisitor;
 use hir::def::Def;
 use hir::CodegenFnAttrFlags;
 use hir::def_id::{DefId, LOCAL_CRATE};
+use hir::map::definitions::DefPathData;
 use lint;
 use middle::privacy;
+use session::config;
 use ty::{self, TyCtxt};
-use util::nodemap::FxHashSet;
+use util::nodemap::{FxHashMap, FxHashSet};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 
 use syntax::{ast, source_map};
 use syntax::attr;
@@ -55,10 +61,13 @@ struct MarkSymbolVisitor<'a, 'tcx: 'a> {
     in_pat: bool,
     inherited_pub_visibility: bool,
     ignore_variant_stack: Vec<DefId>,
+    // Every item referenced from a live item, including those of other crates.
+    used_def_ids: FxHashSet<DefId>,
 }
 
 impl<'a, 'tcx> MarkSymbolVisitor<'a, 'tcx> {
     fn check_def_id(&mut self, def_id: DefId) {
+        self.used_def_ids.insert(def_id);
         if let Some(node_id) = self.tcx.hir.as_local_node_id(def_id) {
             if should_explore(self.tcx, node_id) {
                 self.worklist.push(node_id);
@@ -68,6 +77,7 @@ impl<'a, 'tcx> MarkSymbolVisitor<'a, 'tcx> {
     }
 
     fn insert_def_id(&mut self, def_id: DefId) {
+        self.used_def_ids.insert(def_id);
         if let Some(node_id) = self.tcx.hir.as_local_node_id(def_id) {
             debug_assert!(!should_explore(self.tcx, node_id));
             self.live_symbols.insert(node_id);
@@ -421,7 +431,7 @@ fn create_and_seed_worklist<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
 fn find_live<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                        access_levels: &privacy::AccessLevels,
                        krate: &hir::Crate)
-                       -> FxHashSet<ast::NodeId> {
+                       -> (FxHashSet<ast::NodeId>, FxHashSet<DefId>) {
     let worklist = create_and_seed_worklist(tcx, access_levels, krate);
     let mut symbol_visitor = MarkSymbolVisitor {
         worklist,
@@ -432,9 +442,10 @@ fn find_live<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         in_pat: false,
         inherited_pub_visibility: false,
         ignore_variant_stack: vec![],
+        used_def_ids: Default::default(),
     };
     symbol_visitor.mark_live_symbols();
-    symbol_visitor.live_symbols
+    (symbol_visitor.live_symbols, symbol_visitor.used_def_ids)
 }
 
 fn get_struct_ctor_id(item: &hir::Item) -> Option<ast::NodeId> {
@@ -648,10 +659,215 @@ impl<'a, 'tcx> Visitor<'tcx> for DeadVisitor<'a, 'tcx> {
 pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
     let access_levels = &tcx.privacy_access_levels(LOCAL_CRATE);
     let krate = tcx.hir.krate();
-    let live_symbols = find_live(tcx, access_levels, krate);
+    let (live_symbols, used_def_ids) = find_live(tcx, access_levels, krate);
     let mut visitor = DeadVisitor {
         tcx,
         live_symbols,
     };
     intravisit::walk_crate(&mut visitor, krate);
+
+    if let Some(ref dir) = tcx.sess.opts.debugging_opts.workspace_item_usage {
+        let dir = Path::new(dir);
+        record_item_usage(tcx, dir, access_levels, used_def_ids);
+        let is_executable = tcx.sess.crate_types.borrow().iter().any(|&crate_type| {
+            crate_type == config::CrateType::Executable
+        });
+        if is_executable && !tcx.sess.opts.test {
+            report_unused_workspace_items(tcx, dir);
+        }
+    }
+}
+
+// With `-Z workspace-item-usage=DIR`, every crate writes a file to `DIR` listing the public
+// items it exports and the items of other crates it uses, one per line:
+//
+//     crate <crate> <disambiguator>
+//     export <crate> <def path> <item path> <location>
+//     use <crate> <def path>
+//
+// with tab-separated fields. Building an executable (but not a test harness) then reports the
+// exported items of the crates it links that no crate recorded so far uses, which are the
+// public items that are dead in the whole workspace as long as the executable is built last.
+// Files left behind by other builds of the linked crates are ignored, and nothing is reported
+// unless every crate linked from outside the sysroot has recorded its items in this build.
+
+fn record_item_usage<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                               dir: &Path,
+                               access_levels: &privacy::AccessLevels,
+                               mut used_def_ids: FxHashSet<DefId>) {
+    // Implementing a trait, calling one of its methods or using a constructor uses the
+    // item they belong to.
+    used_def_ids.extend(tcx.hir.krate().trait_impls.keys().cloned());
+    let parents = used_def_ids.iter().filter_map(|&def_id| {
+        if let DefPathData::StructCtor = tcx.def_key(def_id).disambiguated_data.data {
+            return tcx.parent_def_id(def_id);
+        }
+        match tcx.describe_def(def_id) {
+            Some(Def::Method(_)) |
+            Some(Def::AssociatedConst(_)) |
+            Some(Def::AssociatedTy(_)) => tcx.trait_of_item(def_id),
+            _ => None,
+        }
+    }).collect::<Vec<_>>();
+    used_def_ids.extend(parents);
+
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let mut lines = vec![];
+    let is_library = !tcx.sess.opts.test && tcx.sess.crate_types.borrow().iter().any(|&ty| {
+        ty == config::CrateType::Rlib || ty == config::CrateType::Dylib
+    });
+    if is_library {
+        for (&id, _) in access_levels.map.iter() {
+            let def_id = tcx.hir.local_def_id(id);
+            if access_levels.is_exported(id) && is_api_item(tcx, id) &&
+                !used_def_ids.contains(&def_id)
+            {
+                lines.push(format!(
+                    "export\t{}\t{}\t{}\t{}",
+                    crate_name,
+                    tcx.def_path(def_id).to_string_no_crate(),
+                    tcx.absolute_item_path_str(def_id),
+                    tcx.sess.source_map().span_to_string(tcx.def_span(def_id)),
+                ));
+            }
+        }
+    }
+    for def_id in used_def_ids.iter().filter(|def_id| !def_id.is_local()) {
+        lines.push(format!(
+            "use\t{}\t{}",
+            tcx.crate_name(def_id.krate),
+            tcx.def_path(*def_id).to_string_no_crate(),
+        ));
+    }
+    lines.sort();
+    lines.insert(0, format!("crate\t{}\t{}", crate_name, local_crate_disambiguator(tcx)));
+
+    let path = dir.join(format!("{}-{}.items", crate_name, local_crate_disambiguator(tcx)));
+    let result = fs::create_dir_all(dir).and_then(|_| {
+        let mut contents = lines.join("\n");
+        contents.push('\n');
+        fs::write(&path, contents)
+    });
+    if let Err(err) = result {
+        tcx.sess.err(&format!("failed to write item usage to {}: {}", path.display(), err));
+    }
+}
+
+/// Whether `id` is an item that other crates can use by name, and that isn't kept alive
+/// for other reasons, like a `#[lang]` item or an item with `#[allow(dead_code)]`.
+fn is_api_item<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, id: ast::NodeId) -> bool {
+    let attrs = match tcx.hir.find(id) {
+        Some(Node::Item(item)) => match item.node {
+            hir::ItemKind::Fn(..) |
+            hir::ItemKind::Const(..) |
+            hir::ItemKind::Static(..) |
+            hir::ItemKind::Struct(..) |
+            hir::ItemKind::Enum(..) |
+            hir::ItemKind::Union(..) |
+            hir::ItemKind::Trait(..) |
+            hir::ItemKind::Ty(..) => &item.attrs,
+            _ => return false,
+        },
+        Some(Node::ImplItem(impl_item)) => {
+            // Items of trait impls are used through the trait.
+            let impl_def_id = tcx.hir.get_parent_did(id);
+            if tcx.impl_trait_ref(impl_def_id).is_some() {
+                return false;
+            }
+            &impl_item.attrs
+        }
+        _ => return false,
+    };
+    !has_allow_dead_code_or_lang_attr(tcx, id, attrs)
+}
+
+fn local_crate_disambiguator(tcx: TyCtxt) -> String {
+    tcx.sess.local_crate_disambiguator().to_fingerprint().to_hex()
+}
+
+fn report_unused_workspace_items<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, dir: &Path) {
+    // The crates linked into this executable, by name, with their disambiguators.
+    let mut linked = FxHashMap::default();
+    linked.insert(tcx.crate_name(LOCAL_CRATE).to_string(), local_crate_disambiguator(tcx));
+    for &cnum in tcx.crates().iter() {
+        linked.insert(tcx.crate_name(cnum).to_string(),
+                      tcx.crate_disambiguator(cnum).to_fingerprint().to_hex());
+    }
+
+    let mut recorded = FxHashSet::default();
+    let mut exports = BTreeMap::new();
+    let mut uses = FxHashSet::default();
+    let result = fs::read_dir(dir).and_then(|entries| {
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "items") {
+                continue;
+            }
+            let contents = fs::read_to_string(&path)?;
+            let mut lines = contents.lines();
+            let is_linked = match lines.next().map(|line| line.split('\t').collect::<Vec<_>>()) {
+                Some(ref header) if header.len() == 3 && header[0] == "crate" => {
+                    match linked.get(header[1]) {
+                        // Another build of a linked crate, which may be out of date.
+                        Some(disambiguator) if *disambiguator != header[2] => continue,
+                        Some(_) => {
+                            recorded.insert(header[1].to_string());
+                            true
+                        }
+                        // Other crates of the workspace can still use the linked ones.
+                        None => false,
+                    }
+                }
+                _ => continue,
+            };
+            for line in lines {
+                let fields = line.split('\t').collect::<Vec<_>>();
+                match fields[..] {
+                    ["export", krate, def_path, item_path, location] if is_linked => {
+                        exports.insert((krate.to_string(), item_path.to_string()),
+                                       (def_path.to_string(), location.to_string()));
+                    }
+                    ["use", krate, def_path] => {
+                        uses.insert((krate.to_string(), def_path.to_string()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    });
+    if let Err(err) = result {
+        tcx.sess.err(&format!("failed to read item usage from {}: {}", dir.display(), err));
+        return;
+    }
+
+    let sysroot = tcx.sess.sysroot();
+    let unrecorded = tcx.crates().iter().find(|&&cnum| {
+        let source = tcx.used_crate_source(cnum);
+        let in_sysroot = source.rlib.iter().chain(&source.rmeta).chain(&source.dylib)
+            .any(|(path, _)| path.starts_with(sysroot));
+        !in_sysroot && !recorded.contains(&*tcx.crate_name(cnum).as_str())
+    });
+    if let Some(&cnum) = unrecorded {
+        tcx.struct_lint_node(
+            lint::builtin::UNUSED_WORKSPACE_ITEMS,
+            ast::CRATE_NODE_ID,
+            "not reporting unused workspace items",
+        ).note(&format!(
+            "crate `{}` has not recorded its items in {}",
+            tcx.crate_name(cnum),
+            dir.display(),
+        )).emit();
+        return;
+    }
+
+    for ((krate, item_path), (def_path, location)) in exports {
+        if !uses.contains(&(krate.clone(), def_path)) {
+            tcx.struct_lint_node(
+                lint::builtin::UNUSED_WORKSPACE_ITEMS,
+                ast::CRATE_NODE_ID,
+                &format!("public item `{}` is never used", item_path),
+            ).note(&format!("`{}` is defined at {}", item_path, location)).emit();
+        }
+    }
 }
@@ -340,6 +340,8 @@ pub enum EvalErrorKind<'tcx, O> {
     GeneratorResumedAfterReturn,
     GeneratorResumedAfterPanic,
     InfiniteLoop,
+    /// Evaluation executed more steps than `-Z const-eval-step-limit` allows
+    StepLimitReached,
 }
 
 pub type EvalResult<'tcx, T = ()> = Result<T, EvalError<'tcx>>;
@@ -472,6 +474,8 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
             GeneratorResumedAfterPanic => "generator resumed after panicking",
             InfiniteLoop =>
                 "duplicate interpreter state observed here, const evaluation will never terminate",
+            StepLimitReached =>
+                "reached the configured maximum number of evaluation steps",
         }
     }
 }
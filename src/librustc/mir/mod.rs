@@ -2791,6 +2791,23 @@ pub struct UnsafetyCheckResult {
     /// unsafe blocks in this function, along with whether they are used. This is
     /// used for the "unused_unsafe" lint.
     pub unsafe_blocks: Lrc<[(ast::NodeId, bool)]>,
+    /// Operations in this function that are allowed by an `unsafe` block, along with the
+    /// block, or by the function being unsafe, in which case there is no block. This is
+    /// used for the unsafe inventory.
+    pub unsafe_operations: Lrc<[(Option<ast::NodeId>, UnsafetyViolation)]>,
+}
+
+/// A function that a function calls on every path on which it returns, as computed for the
+/// unconditional recursion lint.
+#[derive(Clone, Debug)]
+pub struct UnconditionalCall<'tcx> {
+    /// The function called, which may be a trait method that still has to be resolved.
+    pub callee: DefId,
+    pub substs: &'tcx Substs<'tcx>,
+    pub span: Span,
+    /// Whether the function is not called directly, but turned into a function pointer that
+    /// `format_args!` calls to format a value.
+    pub formats: bool,
 }
 
 /// The layout of generator state
@@ -1268,6 +1268,14 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "print the result of the monomorphization collection pass"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
         "set the MIR optimization level (0-3, default: 1)"),
+    const_eval_step_limit: usize = (10_000_000, parse_uint, [TRACKED],
+        "the number of terminators constant evaluation may execute before it is aborted \
+         (0 disables the limit, default: 10000000)"),
+    trace_const_eval: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "print every step taken while evaluating the named const or static item \
+         (e.g. `foo::BAR`), with the values written to locals"),
+    trace_const_eval_limit: usize = (1000, parse_uint, [UNTRACKED],
+        "the number of steps printed by `-Z trace-const-eval` (0 prints all steps, default: 1000)"),
     mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "emit noalias metadata for mutable references (default: yes on LLVM >= 6)"),
     arg_align_attributes: bool = (false, parse_bool, [TRACKED],
@@ -1326,6 +1334,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "emit diagnostics rather than buffering (breaks NLL error downgrading, sorting)."),
     polonius: bool = (false, parse_bool, [UNTRACKED],
         "enable polonius-based borrow-checker"),
+    polonius_in_tree: bool = (false, parse_bool, [UNTRACKED],
+        "enable polonius-based borrow-checker, using the in-tree solver instead of \
+         polonius-engine"),
     codegen_time_graph: bool = (false, parse_bool, [UNTRACKED],
         "generate a graphical HTML report of time spent in codegen and LLVM"),
     thinlto: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1379,6 +1390,13 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "output a json file with profiler results"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emits a section containing stack size metadata"),
+    workspace_item_usage: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "record the public items of the crate and the items it uses from other crates \
+         in the given directory; when building an executable, report the public items \
+         of the crates it links that no crate recorded there uses"),
+    unsafe_inventory: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "write the `unsafe` blocks, unsafe functions and unsafe impls of the crate, \
+         with the unsafe operations in each, as JSON to the given file"),
     plt: Option<bool> = (None, parse_opt_bool, [TRACKED],
           "whether to use the PLT when calling into shared libraries;
           only has effect for PIC code on systems with ELF binaries
@@ -3126,6 +3144,14 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.print_mono_items = Some(String::from("abc"));
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.trace_const_eval = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.trace_const_eval_limit = 10;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.workspace_item_usage = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.unsafe_inventory = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir = Some(String::from("abc"));
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_dir = String::from("abc");
@@ -3182,6 +3208,10 @@ mod tests {
         opts.debugging_opts.mir_opt_level = 3;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+        opts = reference.clone();
+        opts.debugging_opts.const_eval_step_limit = 100;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
         opts = reference.clone();
         opts.debugging_opts.relro_level = Some(RelroLevel::Full);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
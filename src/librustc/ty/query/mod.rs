@@ -234,6 +234,11 @@ define_queries! { <'tcx>
         /// unreachable code.
         [] fn mir_built: MirBuilt(DefId) -> &'tcx Steal<mir::Mir<'tcx>>,
 
+        /// The functions that a function calls on every path on which it returns, for the
+        /// unconditional recursion lint. Computed from the MIR right after it's built.
+        [] fn unconditional_calls: UnconditionalCalls(DefId)
+            -> Lrc<Vec<mir::UnconditionalCall<'tcx>>>,
+
         /// Fetch the MIR for a given def-id up till the point where it is
         /// ready for const evaluation.
         ///
@@ -242,6 +247,11 @@ define_queries! { <'tcx>
 
         [] fn mir_validated: MirValidated(DefId) -> &'tcx Steal<mir::Mir<'tcx>>,
 
+        /// A copy of the MIR of a function as borrowck sees it, with its regions erased, for
+        /// the lints that look into the functions called without their calls being inlined.
+        /// Computed before `optimized_mir` steals the `mir_validated` result.
+        [] fn unoptimized_mir: UnoptimizedMir(DefId) -> &'tcx mir::Mir<'tcx>,
+
         /// MIR after our optimization passes have run. This is MIR that is ready
         /// for codegen. This is also the only query that can fetch non-local MIR, at present.
         [] fn optimized_mir: MirOptimized(DefId) -> &'tcx mir::Mir<'tcx>,
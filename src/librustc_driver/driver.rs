@@ -1352,6 +1352,18 @@ where
 
             time(sess, "lint checking", || lint::check_crate(tcx));
 
+            time(sess, "panic reachability checking", || mir::deny_panics_check_crate(tcx));
+
+            time(sess, "unconditional panic checking", || {
+                mir::unconditional_panic_check_crate(tcx)
+            });
+
+            if let Some(ref path) = sess.opts.debugging_opts.unsafe_inventory {
+                time(sess, "unsafe inventory", || {
+                    mir::transform::check_unsafety::write_unsafe_inventory(tcx, path)
+                });
+            }
+
             return Ok(f(tcx, analysis, rx, tcx.sess.compile_status()));
         },
     )
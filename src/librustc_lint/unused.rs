@@ -10,9 +10,9 @@
 
 use rustc::hir::def::Def;
 use rustc::hir::def_id::DefId;
-use rustc::ty;
+use rustc::ty::{self, Ty};
 use rustc::ty::adjustment;
-use lint::{LateContext, EarlyContext, LintContext, LintArray};
+use lint::{LateContext, EarlyContext, LintContext, LintArray, Lint};
 use lint::{LintPass, EarlyLintPass, LateLintPass};
 
 use syntax::ast;
@@ -39,17 +39,34 @@ declare_lint! {
     "unused result of an expression in a statement"
 }
 
+declare_lint! {
+    pub LET_UNDERSCORE_MUST_USE,
+    Allow,
+    "result of a type flagged as #[must_use] discarded with `let _ = ...`"
+}
+
+declare_lint! {
+    pub UNUSED_NESTED_MUST_USE,
+    Allow,
+    "unused `Option` or `Box`, or dropped closure result, of a type flagged as #[must_use]"
+}
+
 #[derive(Copy, Clone)]
 pub struct UnusedResults;
 
 impl LintPass for UnusedResults {
     fn get_lints(&self) -> LintArray {
-        lint_array!(UNUSED_MUST_USE, UNUSED_RESULTS)
+        lint_array!(
+            UNUSED_MUST_USE,
+            UNUSED_RESULTS,
+            LET_UNDERSCORE_MUST_USE,
+            UNUSED_NESTED_MUST_USE
+        )
     }
 }
 
 impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedResults {
-    fn check_stmt(&mut self, cx: &LateContext, s: &hir::Stmt) {
+    fn check_stmt(&mut self, cx: &LateContext<'a, 'tcx>, s: &hir::Stmt) {
         let expr = match s.node {
             hir::StmtKind::Semi(ref expr, _) => &**expr,
             _ => return,
@@ -60,39 +77,13 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedResults {
         }
 
         let t = cx.tables.expr_ty(&expr);
+        let module = cx.tcx.hir.get_module_parent(expr.id);
         let type_permits_lack_of_use = if t.is_unit()
-            || cx.tcx.is_ty_uninhabited_from(cx.tcx.hir.get_module_parent(expr.id), t) {
+            || cx.tcx.is_ty_uninhabited_from(module, t) {
             true
         } else {
-            match t.sty {
-                ty::Adt(def, _) => check_must_use(cx, def.did, s.span, "", ""),
-                ty::Opaque(def, _) => {
-                    let mut must_use = false;
-                    for (predicate, _) in &cx.tcx.predicates_of(def).predicates {
-                        if let ty::Predicate::Trait(ref poly_trait_predicate) = predicate {
-                            let trait_ref = poly_trait_predicate.skip_binder().trait_ref;
-                            if check_must_use(cx, trait_ref.def_id, s.span, "implementer of ", "") {
-                                must_use = true;
-                                break;
-                            }
-                        }
-                    }
-                    must_use
-                }
-                ty::Dynamic(binder, _) => {
-                    let mut must_use = false;
-                    for predicate in binder.skip_binder().iter() {
-                        if let ty::ExistentialPredicate::Trait(ref trait_ref) = predicate {
-                            if check_must_use(cx, trait_ref.def_id, s.span, "", " trait object") {
-                                must_use = true;
-                                break;
-                            }
-                        }
-                    }
-                    must_use
-                }
-                _ => false,
-            }
+            check_must_use_ty(cx, UNUSED_MUST_USE, UNUSED_NESTED_MUST_USE, t, module, s.span,
+                              "", "")
         };
 
         let mut fn_warned = false;
@@ -119,7 +110,7 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedResults {
         };
         if let Some(def) = maybe_def {
             let def_id = def.def_id();
-            fn_warned = check_must_use(cx, def_id, s.span, "return value of ", "");
+            fn_warned = check_must_use(cx, UNUSED_MUST_USE, def_id, s.span, "return value of ", "");
         } else if type_permits_lack_of_use {
             // We don't warn about unused unit or uninhabited types.
             // (See https://github.com/rust-lang/rust/issues/43806 for details.)
@@ -172,30 +163,144 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedResults {
         if !(type_permits_lack_of_use || fn_warned || op_warned) {
             cx.span_lint(UNUSED_RESULTS, s.span, "unused result");
         }
+    }
+
+    fn check_expr(&mut self, cx: &LateContext<'a, 'tcx>, expr: &hir::Expr) {
+        let args = match expr.node {
+            hir::ExprKind::Call(_, ref args) | hir::ExprKind::MethodCall(_, _, ref args) => args,
+            _ => return,
+        };
+        let call_ty = cx.tables.expr_ty(expr);
+        for arg in args {
+            let body_id = match arg.node {
+                hir::ExprKind::Closure(_, _, body_id, _, _) => body_id,
+                _ => continue,
+            };
+            let (def_id, substs) = match cx.tables.expr_ty(arg).sty {
+                ty::Closure(def_id, substs) => (def_id, substs),
+                _ => continue,
+            };
+            let sig = substs.closure_sig(def_id, cx.tcx);
+            let output = cx.tcx.erase_late_bound_regions(&sig.output());
+            // If the result of the call mentions the closure's return type, as the adapters of
+            // `Iterator` and `Option` do, the caller may still get to see the returned value.
+            if call_ty.walk().any(|ty| ty == output) {
+                continue;
+            }
+            let body = &cx.tcx.hir.body(body_id).value;
+            let module = cx.tcx.hir.get_module_parent(body.id);
+            check_must_use_ty(cx, UNUSED_NESTED_MUST_USE, UNUSED_NESTED_MUST_USE, output, module,
+                              body.span, "", " returned from a closure");
+        }
+    }
+
+    fn check_local(&mut self, cx: &LateContext<'a, 'tcx>, local: &hir::Local) {
+        let init = match (&local.pat.node, &local.init) {
+            (hir::PatKind::Wild, Some(init)) => init,
+            _ => return,
+        };
+        let module = cx.tcx.hir.get_module_parent(local.id);
+        check_must_use_ty(
+            cx,
+            LET_UNDERSCORE_MUST_USE,
+            LET_UNDERSCORE_MUST_USE,
+            cx.tables.expr_ty(init),
+            module,
+            local.span,
+            "",
+            "",
+        );
+    }
+}
 
-        fn check_must_use(
-            cx: &LateContext,
-            def_id: DefId,
-            sp: Span,
-            descr_pre_path: &str,
-            descr_post_path: &str,
-        ) -> bool {
-            for attr in cx.tcx.get_attrs(def_id).iter() {
-                if attr.check_name("must_use") {
-                    let msg = format!("unused {}`{}`{} that must be used",
-                        descr_pre_path, cx.tcx.item_path_str(def_id), descr_post_path);
-                    let mut err = cx.struct_span_lint(UNUSED_MUST_USE, sp, &msg);
-                    // check for #[must_use = "..."]
-                    if let Some(note) = attr.value_str() {
-                        err.note(&note.as_str());
+/// Reports the first `#[must_use]` type found in `ty` under `lint`, and returns whether one was
+/// found. The search looks through tuples and arrays, and through `Option` and `Box`, whose
+/// contents are reported under `nested_lint` instead. Other types are not looked into: a map's
+/// `insert` returns the value it replaced, which is fine to drop whatever it is.
+fn check_must_use_ty<'a, 'tcx>(
+    cx: &LateContext<'a, 'tcx>,
+    lint: &'static Lint,
+    nested_lint: &'static Lint,
+    ty: Ty<'tcx>,
+    module: DefId,
+    sp: Span,
+    descr_pre: &str,
+    descr_post: &str,
+) -> bool {
+    if ty.is_unit() || cx.tcx.is_ty_uninhabited_from(module, ty) {
+        return false;
+    }
+    match ty.sty {
+        ty::Adt(def, substs) => {
+            if check_must_use(cx, lint, def.did, sp, descr_pre, descr_post) {
+                return true;
+            }
+            if !def.is_box() && cx.tcx.absolute_item_path_str(def.did) != "core::option::Option" {
+                return false;
+            }
+            let descr_post = format!(" in `{}`{}", cx.tcx.item_path_str(def.did), descr_post);
+            let ty = substs.type_at(0);
+            check_must_use_ty(cx, nested_lint, nested_lint, ty, module, sp, descr_pre, &descr_post)
+        }
+        ty::Opaque(def, _) => {
+            for (predicate, _) in &cx.tcx.predicates_of(def).predicates {
+                if let ty::Predicate::Trait(ref poly_trait_predicate) = predicate {
+                    let trait_ref = poly_trait_predicate.skip_binder().trait_ref;
+                    let descr_pre = &format!("{}implementer of ", descr_pre);
+                    if check_must_use(cx, lint, trait_ref.def_id, sp, descr_pre, descr_post) {
+                        return true;
                     }
-                    err.emit();
-                    return true;
                 }
             }
             false
         }
+        ty::Dynamic(binder, _) => {
+            for predicate in binder.skip_binder().iter() {
+                if let ty::ExistentialPredicate::Trait(ref trait_ref) = predicate {
+                    let descr_post = &format!(" trait object{}", descr_post);
+                    if check_must_use(cx, lint, trait_ref.def_id, sp, descr_pre, descr_post) {
+                        return true;
+                    }
+                }
+            }
+            false
+        }
+        ty::Tuple(tys) => {
+            tys.iter().enumerate().any(|(i, &ty)| {
+                let descr_post = &format!(" in tuple element {}{}", i, descr_post);
+                check_must_use_ty(cx, lint, nested_lint, ty, module, sp, descr_pre, descr_post)
+            })
+        }
+        ty::Array(ty, _) => {
+            let descr_post = &format!(" in array{}", descr_post);
+            check_must_use_ty(cx, lint, nested_lint, ty, module, sp, descr_pre, descr_post)
+        }
+        _ => false,
+    }
+}
+
+fn check_must_use(
+    cx: &LateContext,
+    lint: &'static Lint,
+    def_id: DefId,
+    sp: Span,
+    descr_pre_path: &str,
+    descr_post_path: &str,
+) -> bool {
+    for attr in cx.tcx.get_attrs(def_id).iter() {
+        if attr.check_name("must_use") {
+            let msg = format!("unused {}`{}`{} that must be used",
+                descr_pre_path, cx.tcx.item_path_str(def_id), descr_post_path);
+            let mut err = cx.struct_span_lint(lint, sp, &msg);
+            // check for #[must_use = "..."]
+            if let Some(note) = attr.value_str() {
+                err.note(&note.as_str());
+            }
+            err.emit();
+            return true;
+        }
     }
+    false
 }
 
 declare_lint! {
@@ -14,6 +14,7 @@ use borrow_check::prefixes::IsPrefixOf;
 use borrow_check::WriteKind;
 use rustc::hir;
 use rustc::hir::def_id::DefId;
+use rustc::hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc::middle::region::ScopeTree;
 use rustc::mir::{
     self, AggregateKind, BindingForm, BorrowKind, ClearCrossCrate, Constant,
@@ -21,6 +22,7 @@ use rustc::mir::{
     Place, PlaceProjection, ProjectionElem, Rvalue, Statement, StatementKind,
     TerminatorKind, VarBindingForm,
 };
+use rustc::traits;
 use rustc::ty::{self, DefIdTree};
 use rustc::util::ppaux::with_highlight_region_for_bound_region;
 use rustc_data_structures::fx::FxHashSet;
@@ -176,6 +178,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 };
             }
 
+            self.add_move_suggestions(&move_site_vec, &mut err);
+
             use_spans.var_span_label(
                 &mut err,
                 format!("{} occurs due to use{}", desired_action.as_noun(), use_spans.describe()),
@@ -513,12 +517,174 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             );
         }
 
+        self.suggest_temporary_for_argument(issued_span, span, &mut err);
+
         self.explain_why_borrow_contains_point(context, issued_borrow, None)
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, first_borrow_desc);
 
         err.buffer(&mut self.errors_buffer);
     }
 
+    /// Suggests ways to avoid the moves in `move_site_vec`: iterating over a reference to the
+    /// head of a `for` loop, and cloning moved values whose type implements `Clone`.
+    fn add_move_suggestions(&self, move_site_vec: &[MoveSite], err: &mut DiagnosticBuilder<'_>) {
+        let mut suggested = FxHashSet::default();
+        for move_site in move_site_vec {
+            let move_out = self.move_data.moves[move_site.moi];
+            let moved_place = &self.move_data.move_paths[move_out.path].place;
+            let move_spans = self.move_spans(moved_place, move_out.source);
+            if move_spans.for_closure() {
+                continue;
+            }
+            let move_span = move_spans.args_or_use();
+            let snippet = match self.infcx.tcx.sess.source_map().span_to_snippet(move_span) {
+                Ok(snippet) => snippet,
+                Err(_) => continue,
+            };
+            if !suggested.insert(move_span) {
+                continue;
+            }
+
+            // The suggestions only make sense where the moved place is written out as is.
+            if self.describe_place(moved_place).as_ref() != Some(&snippet) {
+                continue;
+            }
+
+            if self.is_for_loop_head(move_out.source, move_span) {
+                // The moved place is exactly the loop head, so borrowing it is what keeps it
+                // usable after the loop.
+                err.span_suggestion_with_applicability(
+                    move_span,
+                    "consider iterating over a reference to avoid moving the value into the loop",
+                    format!("&{}", snippet),
+                    Applicability::MachineApplicable,
+                );
+            } else if self.implements_clone(moved_place, move_span) {
+                // Cloning can be expensive, and changes what the code does if the value is
+                // mutated afterwards, so this is only a suggestion.
+                err.span_suggestion_with_applicability(
+                    move_span,
+                    "consider cloning the value if the performance cost is acceptable",
+                    format!("{}.clone()", snippet),
+                    Applicability::MaybeIncorrect,
+                );
+            }
+        }
+    }
+
+    /// Whether `span`, moved at `location`, is the head of a `for` loop, which the desugaring
+    /// of the loop passes to `IntoIterator::into_iter` with a call that has the same span.
+    fn is_for_loop_head(&self, location: Location, span: Span) -> bool {
+        let terminator = self.mir[location.block].terminator();
+        match terminator.kind {
+            TerminatorKind::Call { .. } if terminator.source_info.span == span => {}
+            _ => return false,
+        }
+        let tcx = self.infcx.tcx;
+        let body_id = match tcx.hir.as_local_node_id(self.mir_def_id)
+            .and_then(|node_id| tcx.hir.maybe_body_owned_by(node_id))
+        {
+            Some(body_id) => body_id,
+            None => return false,
+        };
+        let mut finder = ForLoopHeadFinder { span, found: false };
+        finder.visit_body(tcx.hir.body(body_id));
+        finder.found
+    }
+
+    fn implements_clone(&self, place: &Place<'tcx>, span: Span) -> bool {
+        let tcx = self.infcx.tcx;
+        let ty = tcx.erase_regions(&place.ty(self.mir, tcx).to_ty(tcx));
+        if ty.is_closure() || ty.is_generator() {
+            return false;
+        }
+        match tcx.lang_items().clone_trait() {
+            Some(clone_trait) => {
+                traits::type_known_to_meet_bound(self.infcx, self.param_env, ty, clone_trait, span)
+            }
+            None => false,
+        }
+    }
+
+    /// Suggests evaluating an argument of a method call into a temporary before the call, when
+    /// the argument borrows something that conflicts with the borrow of the receiver at
+    /// `issued_span`, as in `self.items.push(self.next_id())`.
+    fn suggest_temporary_for_argument(
+        &self,
+        issued_span: Span,
+        borrow_span: Span,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let tcx = self.infcx.tcx;
+        let body_id = match tcx.hir.as_local_node_id(self.mir_def_id)
+            .and_then(|node_id| tcx.hir.maybe_body_owned_by(node_id))
+        {
+            Some(body_id) => body_id,
+            None => return,
+        };
+        let mut finder = ArgumentFinder {
+            issued_span,
+            borrow_span,
+            stmt_span: None,
+            found: None,
+        };
+        finder.visit_body(tcx.hir.body(body_id));
+        let (stmt_span, arg) = match finder.found {
+            Some(found) => found,
+            None => return,
+        };
+
+        let source_map = tcx.sess.source_map();
+        let arg_snippet = match source_map.span_to_snippet(arg.span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        let loc = source_map.lookup_char_pos(stmt_span.lo());
+        let indent: String = match loc.file.get_line(loc.line - 1) {
+            Some(line) => line.chars().take(loc.col.0).collect(),
+            None => return,
+        };
+        if !indent.chars().all(char::is_whitespace) {
+            return;
+        }
+        // The temporary only helps if the argument does not itself hold on to the borrow.
+        let arg_ty = tcx.typeck_tables_of(self.mir_def_id).expr_ty(arg);
+        let may_hold_borrow = arg_ty.walk().any(|ty| match ty.sty {
+            ty::Adt(_, substs) => substs.regions().next().is_some(),
+            ty::Ref(..) | ty::RawPtr(..) | ty::Dynamic(..) | ty::Closure(..) |
+            ty::Generator(..) | ty::Param(..) | ty::Projection(..) | ty::Opaque(..) => true,
+            _ => false,
+        });
+        if may_hold_borrow {
+            return;
+        }
+        // Name the temporary after the method computing it, looking through methods that
+        // only unwrap the result, as in `v.pop().unwrap()`.
+        let mut method_call = arg;
+        let name = loop {
+            match method_call.node {
+                hir::ExprKind::MethodCall(ref segment, _, ref args) => {
+                    let name = segment.ident.as_str();
+                    let unwraps = name == "unwrap" || name == "expect";
+                    match args[0].node {
+                        hir::ExprKind::MethodCall(..) if unwraps => method_call = &args[0],
+                        _ => break name.to_string(),
+                    }
+                }
+                _ => return,
+            }
+        };
+
+        err.multipart_suggestion_with_applicability(
+            "introduce a temporary to evaluate the argument before the first borrow",
+            vec![
+                (stmt_span.shrink_to_lo(), format!("let {} = {};\n{}", name, arg_snippet, indent)),
+                (arg.span, name),
+            ],
+            Applicability::MaybeIncorrect,
+        );
+    }
+
     /// Reports StorageDeadOrDrop of `place` conflicts with `borrow`.
     ///
     /// This means that some data referenced by `borrow` needs to live
@@ -2451,3 +2617,70 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         self.borrow_spans(span, borrow.reserve_location)
     }
 }
+
+/// Looks for the call to `IntoIterator::into_iter` that a `for` loop with its head at `span`
+/// is desugared to.
+struct ForLoopHeadFinder {
+    span: Span,
+    found: bool,
+}
+
+impl<'hir> Visitor<'hir> for ForLoopHeadFinder {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'hir> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_expr(&mut self, expr: &'hir hir::Expr) {
+        if let hir::ExprKind::Match(ref discr, _, hir::MatchSource::ForLoopDesugar) = expr.node {
+            if let hir::ExprKind::Call(_, ref args) = discr.node {
+                if discr.span == self.span && args.iter().any(|arg| arg.span == self.span) {
+                    self.found = true;
+                }
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
+
+/// Finds the innermost method call whose receiver contains `issued_span` and which has an
+/// argument, itself a method call, that contains `borrow_span`, along with the statement containing
+/// that method call.
+struct ArgumentFinder<'hir> {
+    issued_span: Span,
+    borrow_span: Span,
+    stmt_span: Option<Span>,
+    found: Option<(Span, &'hir hir::Expr)>,
+}
+
+impl<'hir> Visitor<'hir> for ArgumentFinder<'hir> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'hir> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_stmt(&mut self, stmt: &'hir hir::Stmt) {
+        let outer_stmt_span = ::std::mem::replace(&mut self.stmt_span, Some(stmt.span));
+        intravisit::walk_stmt(self, stmt);
+        self.stmt_span = outer_stmt_span;
+    }
+
+    fn visit_expr(&mut self, expr: &'hir hir::Expr) {
+        if let hir::ExprKind::MethodCall(_, _, ref args) = expr.node {
+            if let Some((receiver, args)) = args.split_first() {
+                let arg = args.iter().find(|arg| {
+                    arg.span.contains(self.borrow_span) && match arg.node {
+                        hir::ExprKind::MethodCall(..) => true,
+                        _ => false,
+                    }
+                });
+                if let (Some(arg), Some(stmt_span)) = (arg, self.stmt_span) {
+                    if receiver.span.contains(self.issued_span) &&
+                        !receiver.span.contains(self.borrow_span)
+                    {
+                        self.found = Some((stmt_span, arg));
+                    }
+                }
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
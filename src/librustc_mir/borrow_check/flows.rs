@@ -14,12 +14,11 @@
 //! but is not as ugly as it is right now.
 
 use rustc::mir::{BasicBlock, Location};
-use rustc::ty::RegionVid;
 use rustc_data_structures::bit_set::BitIter;
 
 use borrow_check::location::LocationIndex;
 
-use polonius_engine::Output;
+use borrow_check::nll::polonius::PoloniusOutput;
 
 use dataflow::move_paths::indexes::BorrowIndex;
 use dataflow::move_paths::HasMoveData;
@@ -38,7 +37,7 @@ crate struct Flows<'b, 'gcx: 'tcx, 'tcx: 'b> {
     pub ever_inits: FlowAtLocation<EverInitializedPlaces<'b, 'gcx, 'tcx>>,
 
     /// Polonius Output
-    pub polonius_output: Option<Rc<Output<RegionVid, BorrowIndex, LocationIndex>>>,
+    pub polonius_output: Option<Rc<PoloniusOutput>>,
 }
 
 impl<'b, 'gcx, 'tcx> Flows<'b, 'gcx, 'tcx> {
@@ -46,7 +45,7 @@ impl<'b, 'gcx, 'tcx> Flows<'b, 'gcx, 'tcx> {
         borrows: FlowAtLocation<Borrows<'b, 'gcx, 'tcx>>,
         uninits: FlowAtLocation<MaybeUninitializedPlaces<'b, 'gcx, 'tcx>>,
         ever_inits: FlowAtLocation<EverInitializedPlaces<'b, 'gcx, 'tcx>>,
-        polonius_output: Option<Rc<Output<RegionVid, BorrowIndex, LocationIndex>>>,
+        polonius_output: Option<Rc<PoloniusOutput>>,
     ) -> Self {
         Flows {
             borrows,
@@ -11,6 +11,7 @@
 use core::unicode::property::Pattern_White_Space;
 use std::fmt::{self, Display};
 
+use rustc::hir;
 use rustc::mir::*;
 use rustc::ty;
 use rustc_errors::{DiagnosticBuilder,Applicability};
@@ -403,9 +404,55 @@ impl<'a, 'gcx, 'tcx> MirBorrowckCtxt<'a, 'gcx, 'tcx> {
                 self.add_move_error_suggestions(err, &binds_to);
                 self.add_move_error_details(err, &binds_to);
             }
-            // No binding. Nothing to suggest.
-            GroupedMoveError::OtherIllegalMove { .. } => (),
+            GroupedMoveError::OtherIllegalMove { ref original_path, .. } => {
+                self.add_as_ref_suggestion(err, original_path, span, &snippet);
+            }
+        }
+    }
+
+    /// Suggests `as_ref` or `as_mut` when an `Option` or a `Result` is moved out of a
+    /// reference, like when calling `unwrap` on a field of `&self`.
+    fn add_as_ref_suggestion(
+        &self,
+        err: &mut DiagnosticBuilder<'a>,
+        original_path: &Place<'tcx>,
+        span: Span,
+        snippet: &str,
+    ) {
+        let tcx = self.infcx.tcx;
+        // Only fields are suggested, as `*r.as_ref()` would call `as_ref` on the reference.
+        match *original_path {
+            Place::Projection(box Projection { elem: ProjectionElem::Field(..), .. }) => {}
+            _ => return,
+        }
+        if self.describe_place(original_path).as_ref().map(|s| &s[..]) != Some(snippet) {
+            return;
         }
+        let type_name = match original_path.ty(self.mir, tcx).to_ty(tcx).sty {
+            ty::Adt(def, _) => match &tcx.item_path_str(def.did)[..] {
+                "std::option::Option" | "core::option::Option" => "Option",
+                "std::result::Result" | "core::result::Result" => "Result",
+                _ => return,
+            },
+            _ => return,
+        };
+        let mut method = "as_ref";
+        for prefix in self.prefixes(original_path, PrefixSet::All) {
+            if let Place::Projection(box Projection { ref base, elem: ProjectionElem::Deref }) =
+                *prefix
+            {
+                if let ty::Ref(_, _, hir::MutMutable) = base.ty(self.mir, tcx).to_ty(tcx).sty {
+                    method = "as_mut";
+                }
+                break;
+            }
+        }
+        err.span_suggestion_with_applicability(
+            span,
+            &format!("consider borrowing the `{}`'s content", type_name),
+            format!("{}.{}()", snippet, method),
+            Applicability::MaybeIncorrect,
+        );
     }
 
     fn add_move_error_suggestions(
@@ -39,6 +39,7 @@ impl AllFactsExt for AllFacts {
     fn enabled(tcx: TyCtxt<'_, '_, '_>) -> bool {
         tcx.sess.opts.debugging_opts.nll_facts
             || tcx.sess.opts.debugging_opts.polonius
+            || tcx.sess.opts.debugging_opts.polonius_in_tree
     }
 
     fn write_to_dir(
@@ -9,12 +9,11 @@
 // except according to those terms.
 
 use borrow_check::borrow_set::BorrowSet;
-use borrow_check::location::{LocationIndex, LocationTable};
+use borrow_check::location::LocationTable;
 use borrow_check::nll::facts::AllFactsExt;
 use borrow_check::nll::type_check::{MirTypeckResults, MirTypeckRegionConstraints};
 use borrow_check::nll::type_check::liveness::liveness_map::NllLivenessMap;
 use borrow_check::nll::region_infer::values::RegionValueElements;
-use dataflow::indexes::BorrowIndex;
 use dataflow::move_paths::MoveData;
 use dataflow::FlowAtLocation;
 use dataflow::MaybeInitializedPlaces;
@@ -40,6 +39,7 @@ mod constraint_generation;
 pub mod explain_borrow;
 mod facts;
 mod invalidation;
+crate mod polonius;
 crate mod region_infer;
 mod renumber;
 crate mod type_check;
@@ -48,6 +48,7 @@ mod universal_regions;
 mod constraints;
 
 use self::facts::AllFacts;
+use self::polonius::PoloniusOutput;
 use self::region_infer::RegionInferenceContext;
 use self::universal_regions::UniversalRegions;
 
@@ -91,7 +92,7 @@ pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
     errors_buffer: &mut Vec<Diagnostic>,
 ) -> (
     RegionInferenceContext<'tcx>,
-    Option<Rc<Output<RegionVid, BorrowIndex, LocationIndex>>>,
+    Option<Rc<PoloniusOutput>>,
     Option<ClosureRegionRequirements<'gcx>>,
 ) {
     let mut all_facts = if AllFacts::enabled(infcx.tcx) {
@@ -182,16 +183,18 @@ pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
             all_facts.write_to_dir(dir_path, location_table).unwrap();
         }
 
-        if infcx.tcx.sess.opts.debugging_opts.polonius {
+        if infcx.tcx.sess.opts.debugging_opts.polonius_in_tree {
+            Some(Rc::new(PoloniusOutput::InTree(polonius::compute(&all_facts))))
+        } else if infcx.tcx.sess.opts.debugging_opts.polonius {
             let algorithm = env::var("POLONIUS_ALGORITHM")
                 .unwrap_or_else(|_| String::from("DatafrogOpt"));
             let algorithm = Algorithm::from_str(&algorithm).unwrap();
             debug!("compute_regions: using polonius algorithm {:?}", algorithm);
-            Some(Rc::new(Output::compute(
+            Some(Rc::new(PoloniusOutput::Engine(Output::compute(
                 &all_facts,
                 algorithm,
                 false,
-            )))
+            ))))
         } else {
             None
         }
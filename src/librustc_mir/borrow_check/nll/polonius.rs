@@ -0,0 +1,171 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An in-tree solver for the Polonius rules, used with `-Z polonius-in-tree`.
+//!
+//! Polonius computes which loans are live at each point from the facts gathered in `facts.rs`,
+//! rather than from the region values computed by region inference. Regions only flow into
+//! each other at the points where they are related, and a loan only stays live at a point if
+//! a region that is live there still requires it. A borrow that is returned from the function
+//! on one path, for example, is thus not live on the paths where it is not returned.
+//!
+//! The rules, in Datalog notation, are those of the "naive" variant of the analysis:
+//!
+//! ```text
+//! subset(R1, R2, P) :- outlives(R1, R2, P).
+//! subset(R1, R3, P) :- subset(R1, R2, P), subset(R2, R3, P).
+//! subset(R1, R2, Q) :- subset(R1, R2, P), cfg_edge(P, Q),
+//!                      region_live_at(R1, Q), region_live_at(R2, Q).
+//!
+//! requires(R, L, P) :- borrow_region(R, L, P).
+//! requires(R2, L, P) :- requires(R1, L, P), subset(R1, R2, P).
+//! requires(R, L, Q) :- requires(R, L, P), !killed(L, P), cfg_edge(P, Q),
+//!                      region_live_at(R, Q).
+//!
+//! borrow_live_at(L, P) :- requires(R, L, P), region_live_at(R, P).
+//!
+//! errors(L, P) :- invalidates(P, L), borrow_live_at(L, P).
+//! ```
+//!
+//! Universal regions are live at every point, as the caller may use them after we return.
+//! Each relation is computed to a fixed point with a worklist of the tuples that have not
+//! been propagated yet.
+
+use borrow_check::location::LocationIndex;
+use borrow_check::nll::facts::AllFacts;
+use dataflow::indexes::BorrowIndex;
+use polonius_engine::Output;
+use rustc::ty::RegionVid;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+
+/// The loans that are invalidated while they are live, at each point where this happens, as
+/// computed by `polonius-engine` or by the in-tree solver.
+crate enum PoloniusOutput {
+    Engine(Output<RegionVid, BorrowIndex, LocationIndex>),
+    InTree(FxHashMap<LocationIndex, Vec<BorrowIndex>>),
+}
+
+impl PoloniusOutput {
+    crate fn errors_at(&self, location: LocationIndex) -> &[BorrowIndex] {
+        match *self {
+            PoloniusOutput::Engine(ref output) => output.errors_at(location),
+            PoloniusOutput::InTree(ref errors) => {
+                errors.get(&location).map(|loans| &loans[..]).unwrap_or(&[])
+            }
+        }
+    }
+}
+
+/// For each point, the regions that each region flows into there.
+type Subset = FxHashMap<LocationIndex, FxHashMap<RegionVid, FxHashSet<RegionVid>>>;
+
+struct Facts<'a> {
+    all_facts: &'a AllFacts,
+    successors: FxHashMap<LocationIndex, Vec<LocationIndex>>,
+    region_live_at: FxHashSet<(RegionVid, LocationIndex)>,
+    killed: FxHashSet<(BorrowIndex, LocationIndex)>,
+}
+
+/// Computes the `errors` relation from `all_facts`.
+crate fn compute(all_facts: &AllFacts) -> FxHashMap<LocationIndex, Vec<BorrowIndex>> {
+    let mut successors: FxHashMap<LocationIndex, Vec<LocationIndex>> = FxHashMap::default();
+    let mut points = FxHashSet::default();
+    for &(p, q) in &all_facts.cfg_edge {
+        successors.entry(p).or_default().push(q);
+        points.insert(p);
+        points.insert(q);
+    }
+    let mut region_live_at: FxHashSet<_> = all_facts.region_live_at.iter().cloned().collect();
+    for &r in &all_facts.universal_region {
+        region_live_at.extend(points.iter().map(|&p| (r, p)));
+    }
+    let facts = Facts {
+        all_facts,
+        successors,
+        region_live_at,
+        killed: all_facts.killed.iter().cloned().collect(),
+    };
+
+    let subset = facts.compute_subset();
+    let borrow_live_at = facts.compute_borrow_live_at(&subset);
+
+    let mut errors: FxHashMap<LocationIndex, Vec<BorrowIndex>> = FxHashMap::default();
+    for &(p, l) in &all_facts.invalidates {
+        if borrow_live_at.contains(&(l, p)) {
+            errors.entry(p).or_default().push(l);
+        }
+    }
+    for loans in errors.values_mut() {
+        loans.sort();
+        loans.dedup();
+    }
+    debug!("polonius::compute: {} points with errors", errors.len());
+    errors
+}
+
+impl<'a> Facts<'a> {
+    fn successors(&self, p: LocationIndex) -> &[LocationIndex] {
+        self.successors.get(&p).map(|qs| &qs[..]).unwrap_or(&[])
+    }
+
+    fn compute_subset(&self) -> Subset {
+        let mut subset = Subset::default();
+        // The inverse of `subset`, for the transitive closure.
+        let mut supersets = Subset::default();
+        let mut worklist = self.all_facts.outlives.clone();
+        while let Some((r1, r2, p)) = worklist.pop() {
+            if !subset.entry(p).or_default().entry(r1).or_default().insert(r2) {
+                continue;
+            }
+            supersets.entry(p).or_default().entry(r2).or_default().insert(r1);
+
+            if let Some(r3s) = subset[&p].get(&r2) {
+                worklist.extend(r3s.iter().map(|&r3| (r1, r3, p)));
+            }
+            if let Some(r0s) = supersets[&p].get(&r1) {
+                worklist.extend(r0s.iter().map(|&r0| (r0, r2, p)));
+            }
+            for &q in self.successors(p) {
+                if self.region_live_at.contains(&(r1, q)) &&
+                    self.region_live_at.contains(&(r2, q))
+                {
+                    worklist.push((r1, r2, q));
+                }
+            }
+        }
+        subset
+    }
+
+    fn compute_borrow_live_at(&self, subset: &Subset) -> FxHashSet<(BorrowIndex, LocationIndex)> {
+        let mut requires = FxHashSet::default();
+        let mut borrow_live_at = FxHashSet::default();
+        let mut worklist = self.all_facts.borrow_region.clone();
+        while let Some((r, l, p)) = worklist.pop() {
+            if !requires.insert((r, l, p)) {
+                continue;
+            }
+            if self.region_live_at.contains(&(r, p)) {
+                borrow_live_at.insert((l, p));
+            }
+
+            if let Some(r2s) = subset.get(&p).and_then(|regions| regions.get(&r)) {
+                worklist.extend(r2s.iter().map(|&r2| (r2, l, p)));
+            }
+            if !self.killed.contains(&(l, p)) {
+                for &q in self.successors(p) {
+                    if self.region_live_at.contains(&(r, q)) {
+                        worklist.push((r, l, q));
+                    }
+                }
+            }
+        }
+        borrow_live_at
+    }
+}
@@ -82,6 +82,13 @@ pub fn mir_build<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> Mir<'t
         _ => span_bug!(tcx.hir.span(id), "can't build MIR for {:?}", def_id),
     };
 
+    // Tests can replace the body of a function with hand-written MIR.
+    if let Some(mir) = mir_util::mir_from_file_attr(tcx, def_id) {
+        mir_util::dump_mir(tcx, None, "mir_map", &0,
+                           MirSource::item(def_id), &mir, |_, _| Ok(()) );
+        return mir;
+    }
+
     tcx.infer_ctxt().enter(|infcx| {
         let cx = Cx::new(&infcx, id);
         let mut mir = if cx.tables().tainted_by_errors {
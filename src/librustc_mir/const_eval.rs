@@ -193,6 +193,13 @@ fn eval_body_using_ecx<'mir, 'tcx>(
     let prom = cid.promoted.map_or(String::new(), |p| format!("::promoted[{:?}]", p));
     trace!("eval_body_using_ecx: pushing stack frame for global: {}{}", name, prom);
     assert!(mir.arg_count == 0);
+    if let Some(ref item) = tcx.sess.opts.debugging_opts.trace_const_eval {
+        let def_id = cid.instance.def_id();
+        if *item == name || *item == tcx.absolute_item_path_str(def_id) {
+            eprintln!("trace: evaluating `{}{}`", name, prom);
+            ecx.start_step_trace(tcx.sess.opts.debugging_opts.trace_const_eval_limit);
+        }
+    }
     ecx.push_stack_frame(
         cid.instance,
         mir.span,
@@ -212,12 +219,51 @@ fn eval_body_using_ecx<'mir, 'tcx>(
     } else {
         Mutability::Immutable
     };
-    ecx.memory.intern_static(ret.ptr.to_ptr()?.alloc_id, mutability)?;
+    let ret_alloc = ret.ptr.to_ptr()?.alloc_id;
+    check_heap_allocs(ecx, ret_alloc, is_static.is_some(), mutability, param_env, mir.span)?;
+    ecx.memory.intern_static(ret_alloc, mutability)?;
 
     debug!("eval_body_using_ecx done: {:?}", *ret);
     Ok(ret)
 }
 
+/// Check that every heap allocation still alive after evaluation is part of the final value,
+/// and that the final value is allowed to contain heap memory at all.
+///
+/// Only immutable statics may keep heap memory: their value is never dropped or mutated, so
+/// the memory can be interned like any other part of the static. A constant is copied to
+/// every use site, where dropping it would try to free the interned memory. The mutability of
+/// the static only depends on its own type, and a `Box` is `Freeze` whatever it points to, so
+/// the type of every heap allocation is checked for interior mutability as well.
+fn check_heap_allocs<'mir, 'tcx>(
+    ecx: &CompileTimeEvalContext<'_, 'mir, 'tcx>,
+    ret_alloc: AllocId,
+    is_static: bool,
+    mutability: Mutability,
+    param_env: ty::ParamEnv<'tcx>,
+    span: Span,
+) -> EvalResult<'tcx> {
+    let heap_allocs = ecx.memory.machine_allocs(ConstEvalMemoryKind::Heap);
+    if heap_allocs.is_empty() {
+        return Ok(());
+    }
+    let reachable = ecx.memory.reachable_allocs(ret_alloc);
+    if heap_allocs.iter().any(|alloc_id| !reachable.contains(alloc_id)) {
+        return Err(ConstEvalError::HeapLeak.into());
+    }
+    if !is_static || mutability == Mutability::Mutable {
+        return Err(ConstEvalError::HeapInFinalValue.into());
+    }
+    let tcx = ecx.tcx.tcx;
+    let interior_mutable = heap_allocs.iter().any(|alloc_id| {
+        ecx.machine.heap_types.get(alloc_id).map_or(true, |ty| !ty.is_freeze(tcx, param_env, span))
+    });
+    if interior_mutable {
+        return Err(ConstEvalError::HeapInFinalValue.into());
+    }
+    Ok(())
+}
+
 impl<'tcx> Into<EvalError<'tcx>> for ConstEvalError {
     fn into(self) -> EvalError<'tcx> {
         EvalErrorKind::MachineError(self.to_string()).into()
@@ -227,6 +273,10 @@ impl<'tcx> Into<EvalError<'tcx>> for ConstEvalError {
 #[derive(Clone, Debug)]
 enum ConstEvalError {
     NeedsRfc(String),
+    /// A heap allocation was neither freed nor part of the final value.
+    HeapLeak,
+    /// The final value refers to heap memory, but is not an immutable static.
+    HeapInFinalValue,
 }
 
 impl fmt::Display for ConstEvalError {
@@ -240,6 +290,16 @@ impl fmt::Display for ConstEvalError {
                     msg
                 )
             }
+            HeapLeak => {
+                write!(f, "heap allocation was not freed before the end of constant evaluation")
+            }
+            HeapInFinalValue => {
+                write!(
+                    f,
+                    "heap memory can only be part of the final value of an immutable static \
+                     without interior mutability"
+                )
+            }
         }
     }
 }
@@ -249,6 +309,8 @@ impl Error for ConstEvalError {
         use self::ConstEvalError::*;
         match *self {
             NeedsRfc(_) => "this feature needs an rfc before being allowed inside constants",
+            HeapLeak => "heap allocation was not freed before the end of constant evaluation",
+            HeapInFinalValue => "heap memory in the final value of a constant",
         }
     }
 
@@ -267,6 +329,15 @@ pub struct CompileTimeInterpreter<'a, 'mir, 'tcx: 'a+'mir> {
 
     /// Extra state to detect loops.
     pub(super) loop_detector: snapshot::InfiniteLoopDetector<'a, 'mir, 'tcx>,
+
+    /// The number of terminators executed so far, checked against
+    /// `-Z const-eval-step-limit` so that loops which never repeat a state
+    /// still get reported instead of hanging the compiler.
+    pub(super) steps_taken: usize,
+
+    /// The type of the value each heap allocation was made for by `box`, to check the heap
+    /// memory kept in the final value for interior mutability.
+    pub(super) heap_types: FxHashMap<AllocId, ty::Ty<'tcx>>,
 }
 
 impl<'a, 'mir, 'tcx> CompileTimeInterpreter<'a, 'mir, 'tcx> {
@@ -274,6 +345,25 @@ impl<'a, 'mir, 'tcx> CompileTimeInterpreter<'a, 'mir, 'tcx> {
         CompileTimeInterpreter {
             loop_detector: Default::default(),
             steps_since_detector_enabled: -STEPS_UNTIL_DETECTOR_ENABLED,
+            steps_taken: 0,
+            heap_types: FxHashMap::default(),
+        }
+    }
+}
+
+/// The memory kinds CTFE uses in addition to the builtin ones.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum ConstEvalMemoryKind {
+    /// Memory allocated by `box` under `#![feature(const_heap)]`. It has to be freed
+    /// again before evaluation ends, unless it is part of the final value.
+    Heap,
+}
+
+impl interpret::MayLeak for ConstEvalMemoryKind {
+    #[inline(always)]
+    fn may_leak(self) -> bool {
+        match self {
+            ConstEvalMemoryKind::Heap => false,
         }
     }
 }
@@ -342,27 +432,19 @@ impl<K: Hash + Eq, V> interpret::AllocMap<K, V> for FxHashMap<K, V> {
 type CompileTimeEvalContext<'a, 'mir, 'tcx> =
     EvalContext<'a, 'mir, 'tcx, CompileTimeInterpreter<'a, 'mir, 'tcx>>;
 
-impl interpret::MayLeak for ! {
-    #[inline(always)]
-    fn may_leak(self) -> bool {
-        // `self` is uninhabited
-        self
-    }
-}
-
 impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
     for CompileTimeInterpreter<'a, 'mir, 'tcx>
 {
-    type MemoryKinds = !;
+    type MemoryKinds = ConstEvalMemoryKind;
     type PointerTag = ();
 
     type FrameExtra = ();
     type MemoryExtra = ();
     type AllocExtra = ();
 
-    type MemoryMap = FxHashMap<AllocId, (MemoryKind<!>, Allocation)>;
+    type MemoryMap = FxHashMap<AllocId, (MemoryKind<ConstEvalMemoryKind>, Allocation)>;
 
-    const STATIC_KIND: Option<!> = None; // no copying of statics allowed
+    const STATIC_KIND: Option<ConstEvalMemoryKind> = None; // no copying of statics allowed
 
     #[inline(always)]
     fn enforce_validity(_ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> bool {
@@ -377,6 +459,20 @@ impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
         ret: Option<mir::BasicBlock>,
     ) -> EvalResult<'tcx, Option<&'mir mir::Mir<'tcx>>> {
         debug!("eval_fn_call: {:?}", instance);
+        if ecx.tcx.features().const_heap {
+            // `box_free` is how drop glue releases the memory of a `Box`.
+            if Some(instance.def_id()) == ecx.tcx.lang_items().box_free_fn() {
+                let ptr = ecx.read_immediate(args[0])?.to_scalar_ptr()?.to_ptr()?;
+                ecx.memory.deallocate(ptr, None, MemoryKind::Machine(ConstEvalMemoryKind::Heap))?;
+                ecx.goto_block(ret)?;
+                return Ok(None);
+            }
+            // Run drop glue so that boxes get freed. Any `Drop` impl it calls is still
+            // rejected below, as those are not const fns.
+            if let ty::InstanceDef::DropGlue(..) = instance.def {
+                return Ok(Some(ecx.load_mir(instance.def)?));
+            }
+        }
         // Execution might have wandered off into other crates, so we cannot to a stability-
         // sensitive check here.  But we can at least rule out functions that are not const
         // at all.
@@ -453,15 +549,28 @@ impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
     }
 
     fn box_alloc(
-        _ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
-        _dest: PlaceTy<'tcx>,
+        ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        dest: PlaceTy<'tcx>,
     ) -> EvalResult<'tcx> {
-        Err(
-            ConstEvalError::NeedsRfc("heap allocations via `box` keyword".to_string()).into(),
-        )
+        if !ecx.tcx.features().const_heap {
+            return Err(
+                ConstEvalError::NeedsRfc("heap allocations via `box` keyword".to_string()).into(),
+            );
+        }
+        trace!("box_alloc: {:?}", dest.layout.ty);
+        let layout = ecx.layout_of(dest.layout.ty.boxed_ty())?;
+        let place = ecx.allocate(layout, MemoryKind::Machine(ConstEvalMemoryKind::Heap))?;
+        ecx.machine.heap_types.insert(place.ptr.to_ptr()?.alloc_id, layout.ty);
+        ecx.write_scalar(place.ptr, dest)
     }
 
     fn before_terminator(ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>) -> EvalResult<'tcx> {
+        ecx.machine.steps_taken += 1;
+        let step_limit = ecx.tcx.sess.opts.debugging_opts.const_eval_step_limit;
+        if step_limit != 0 && ecx.machine.steps_taken > step_limit {
+            return err!(StepLimitReached);
+        }
+
         {
             let steps = &mut ecx.machine.steps_since_detector_enabled;
 
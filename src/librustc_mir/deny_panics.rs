@@ -0,0 +1,279 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Checks that functions marked `#[deny_panics]` cannot reach a panic.
+//!
+//! Starting from each marked function, the calls and drops in its MIR are followed through
+//! the call graph, and every panic site found on the way is reported together with the chain
+//! of calls leading to it. A panic site is an `Assert` terminator (for example a bounds or
+//! overflow check), a call to one of the panic lang items, or a call to a function whose MIR
+//! is not available, like a trait object method or a function pointer. Foreign functions are
+//! panic sites too, unless they are marked `#[deny_panics]`, which asserts that they don't
+//! panic. Cleanup blocks are not searched, as they only run while already panicking.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc::hir::def_id::DefId;
+use rustc::mir::TerminatorKind;
+use rustc::ty::{Instance, InstanceDef, ParamEnv, Ty, TyCtxt, TyKind, TypeFoldable};
+use rustc::ty::subst::Substs;
+use monomorphize::resolve_drop_in_place;
+use rustc_errors::DiagnosticBuilder;
+use std::collections::VecDeque;
+use syntax_pos::Span;
+
+/// The number of functions searched for panics from each `#[deny_panics]` function.
+/// Calls to functions beyond that are reported as if they could panic.
+const SEARCH_LIMIT: usize = 1000;
+
+/// The number of panic sites reported for each `#[deny_panics]` function.
+const REPORT_LIMIT: usize = 10;
+
+pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    for def_id in tcx.body_owners() {
+        if !tcx.has_attr(def_id, "deny_panics") {
+            continue;
+        }
+        match tcx.type_of(def_id).sty {
+            TyKind::FnDef(..) => check_fn(tcx, def_id),
+            _ => {
+                let sp = tcx.sess.source_map().def_span(tcx.def_span(def_id));
+                tcx.sess.span_err(sp, "`#[deny_panics]` can only be applied to functions");
+            }
+        }
+    }
+}
+
+struct PanicSite<'tcx> {
+    /// The function containing the panic site.
+    instance: Instance<'tcx>,
+    span: Span,
+    /// Why the site can panic, completing a sentence that starts with the function's path.
+    description: String,
+}
+
+enum Callee<'tcx> {
+    /// A function that cannot panic, or that is checked on its own.
+    PanicFree,
+    /// A function whose body is searched for panics.
+    Body(Instance<'tcx>),
+    /// A function that panics or that is not known not to, with the reason.
+    Panics(String),
+}
+
+struct PanicSearch<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    param_env: ParamEnv<'tcx>,
+    root: DefId,
+    /// The caller of each function found, with the span of its first call.
+    callers: FxHashMap<Instance<'tcx>, (Instance<'tcx>, Span)>,
+    queue: VecDeque<Instance<'tcx>>,
+    sites: Vec<PanicSite<'tcx>>,
+}
+
+fn check_fn<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) {
+    let substs = tcx.erase_regions(&Substs::identity_for_item(tcx, def_id));
+    let root = Instance::new(def_id, substs);
+    let mut search = PanicSearch {
+        tcx,
+        param_env: tcx.param_env(def_id),
+        root: def_id,
+        callers: FxHashMap::default(),
+        queue: VecDeque::new(),
+        sites: vec![],
+    };
+    let mut visited = FxHashSet::default();
+    visited.insert(root);
+    search.queue.push_back(root);
+    while let Some(instance) = search.queue.pop_front() {
+        if search.sites.len() > REPORT_LIMIT {
+            break;
+        }
+        search.search_body(instance, &mut visited);
+    }
+
+    let fn_sp = tcx.sess.source_map().def_span(tcx.def_span(def_id));
+    let msg = format!("`#[deny_panics]` function `{}` can panic", tcx.item_path_str(def_id));
+    for (i, site) in search.sites.iter().enumerate().take(REPORT_LIMIT) {
+        let mut err = tcx.sess.struct_span_err(fn_sp, &msg);
+        for (caller, callee, span) in search.call_chain(site.instance) {
+            let note = format!("`{}` calls `{}`",
+                               tcx.item_path_str(caller.def_id()),
+                               tcx.item_path_str(callee.def_id()));
+            note_at(&mut err, caller, span, &note);
+        }
+        let note = format!("`{}` {}", tcx.item_path_str(site.instance.def_id()), site.description);
+        note_at(&mut err, site.instance, site.span, &note);
+        if i + 1 == REPORT_LIMIT && search.sites.len() > REPORT_LIMIT {
+            err.note("further panic sites in this function are not reported");
+        }
+        err.emit();
+    }
+}
+
+/// Adds `note` pointing at `span` if it is in the body of a local function.
+fn note_at(err: &mut DiagnosticBuilder,
+           instance: Instance,
+           span: Span,
+           note: &str) {
+    match instance.def {
+        InstanceDef::Item(def_id) if def_id.is_local() => {
+            err.span_note(span, note);
+        }
+        _ => {
+            err.note(note);
+        }
+    }
+}
+
+impl<'a, 'tcx> PanicSearch<'a, 'tcx> {
+    fn search_body(&mut self,
+                   instance: Instance<'tcx>,
+                   visited: &mut FxHashSet<Instance<'tcx>>) {
+        let tcx = self.tcx;
+        let mir = tcx.instance_mir(instance.def);
+        for data in mir.basic_blocks().iter() {
+            if data.is_cleanup {
+                continue;
+            }
+            let terminator = data.terminator();
+            let span = terminator.source_info.span;
+            let callee = match terminator.kind {
+                TerminatorKind::Assert { ref msg, .. } => {
+                    Callee::Panics(format!("can panic: {}", msg.description()))
+                }
+                TerminatorKind::Call { ref func, .. } => {
+                    let func_ty = self.monomorphize(instance, func.ty(mir, tcx));
+                    self.classify_call(func_ty)
+                }
+                TerminatorKind::Drop { ref location, .. } |
+                TerminatorKind::DropAndReplace { ref location, .. } => {
+                    let ty = location.ty(mir, tcx).to_ty(tcx);
+                    let ty = self.monomorphize(instance, ty);
+                    self.classify_drop(ty)
+                }
+                _ => Callee::PanicFree,
+            };
+            match callee {
+                Callee::PanicFree => {}
+                Callee::Body(callee) => {
+                    if visited.contains(&callee) {
+                        continue;
+                    }
+                    if visited.len() >= SEARCH_LIMIT {
+                        // Not searching the callee could hide its panics, so it counts as one.
+                        self.sites.push(PanicSite {
+                            instance,
+                            span,
+                            description: format!("calls `{}`, which was not searched: call \
+                                                  graph too large",
+                                                 tcx.item_path_str(callee.def_id())),
+                        });
+                        continue;
+                    }
+                    visited.insert(callee);
+                    self.callers.insert(callee, (instance, span));
+                    self.queue.push_back(callee);
+                }
+                Callee::Panics(description) => {
+                    self.sites.push(PanicSite { instance, span, description });
+                }
+            }
+        }
+    }
+
+    fn monomorphize(&self, instance: Instance<'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
+        self.tcx.subst_and_normalize_erasing_regions(instance.substs, self.param_env, &ty)
+    }
+
+    fn classify_call(&self, func_ty: Ty<'tcx>) -> Callee<'tcx> {
+        let tcx = self.tcx;
+        let (def_id, substs) = match func_ty.sty {
+            TyKind::FnDef(def_id, substs) => (def_id, substs),
+            _ => {
+                return Callee::Panics(
+                    "calls a function pointer, which is not known to be panic-free".to_string());
+            }
+        };
+        let lang_items = tcx.lang_items();
+        let panic_fns = [
+            lang_items.panic_fn(),
+            lang_items.panic_bounds_check_fn(),
+            lang_items.begin_panic_fn(),
+            lang_items.panic_impl(),
+        ];
+        if panic_fns.contains(&Some(def_id)) {
+            return Callee::Panics(format!("calls `{}`, which panics", tcx.item_path_str(def_id)));
+        }
+        let not_panic_free = |def_id| {
+            Callee::Panics(format!("calls `{}`, which is not known to be panic-free",
+                                   tcx.item_path_str(def_id)))
+        };
+        let instance = match Instance::resolve(tcx, self.param_env, def_id, substs) {
+            Some(instance) => instance,
+            None => return not_panic_free(def_id),
+        };
+        match instance.def {
+            InstanceDef::Intrinsic(_) => Callee::PanicFree,
+            InstanceDef::Virtual(def_id, _) => {
+                Callee::Panics(format!("calls `{}` through a trait object, which is not \
+                                        known to be panic-free",
+                                       tcx.item_path_str(def_id)))
+            }
+            InstanceDef::Item(def_id) => {
+                if tcx.is_foreign_item(def_id) {
+                    // Foreign functions have no body to search, so they are only trusted not
+                    // to panic when they are marked `#[deny_panics]` themselves.
+                    if tcx.has_attr(def_id, "deny_panics") {
+                        Callee::PanicFree
+                    } else {
+                        Callee::Panics(format!("calls the foreign function `{}`, which is not \
+                                                known to be panic-free",
+                                               tcx.item_path_str(def_id)))
+                    }
+                } else if def_id != self.root && tcx.has_attr(def_id, "deny_panics") {
+                    // Reported when that function is checked.
+                    Callee::PanicFree
+                } else if tcx.is_mir_available(def_id) {
+                    Callee::Body(instance)
+                } else {
+                    not_panic_free(def_id)
+                }
+            }
+            _ => Callee::Body(instance),
+        }
+    }
+
+    fn classify_drop(&self, ty: Ty<'tcx>) -> Callee<'tcx> {
+        if !ty.needs_drop(self.tcx, self.param_env) {
+            Callee::PanicFree
+        } else if ty.needs_subst() {
+            // The drop glue of generic types is only known once they are substituted.
+            Callee::Panics(format!("drops a value of type `{}`, which is not known to be \
+                                    panic-free",
+                                   ty))
+        } else {
+            Callee::Body(resolve_drop_in_place(self.tcx, ty))
+        }
+    }
+
+    /// The calls leading from the `#[deny_panics]` function to `instance`, as
+    /// `(caller, callee, span)`.
+    fn call_chain(&self,
+                  mut instance: Instance<'tcx>)
+                  -> Vec<(Instance<'tcx>, Instance<'tcx>, Span)> {
+        let mut chain = vec![];
+        while let Some(&(caller, span)) = self.callers.get(&instance) {
+            chain.push((caller, instance, span));
+            instance = caller;
+        }
+        chain.reverse();
+        chain
+    }
+}
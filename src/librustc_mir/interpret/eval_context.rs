@@ -34,6 +34,7 @@ use super::{
     Immediate, Operand, MemPlace, MPlaceTy, Place, PlaceTy, ScalarMaybeUndef,
     Memory, Machine
 };
+use super::trace::StepTrace;
 
 pub struct EvalContext<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'a, 'mir, 'tcx>> {
     /// Stores the `Machine` instance.
@@ -53,6 +54,9 @@ pub struct EvalContext<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'a, 'mir, 'tcx>> {
 
     /// A cache for deduplicating vtables
     pub(super) vtables: FxHashMap<(Ty<'tcx>, ty::PolyExistentialTraitRef<'tcx>), AllocId>,
+
+    /// Set while the executed steps are printed, see `start_step_trace`.
+    pub(super) step_trace: Option<StepTrace>,
 }
 
 /// A stack frame.
@@ -185,6 +189,7 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tc
             memory: Memory::new(tcx),
             stack: Vec::new(),
             vtables: FxHashMap::default(),
+            step_trace: None,
         }
     }
 
@@ -593,6 +593,13 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
     pub fn alloc_map(&self) -> &M::MemoryMap {
         &self.alloc_map
     }
+
+    /// Return the ids of all live allocations of the given machine-specific kind.
+    pub fn machine_allocs(&self, kind: M::MemoryKinds) -> Vec<AllocId> {
+        self.alloc_map.filter_map_collect(|&id, &(alloc_kind, _)| {
+            if alloc_kind == MemoryKind::Machine(kind) { Some(id) } else { None }
+        })
+    }
 }
 
 /// Byte Accessors
@@ -629,12 +636,9 @@ where
             alloc_id,
             mutability
         );
-        // remove allocation
-        let (kind, mut alloc) = self.alloc_map.remove(&alloc_id).unwrap();
-        match kind {
-            MemoryKind::Machine(_) => bug!("Static cannot refer to machine memory"),
-            MemoryKind::Stack | MemoryKind::Vtable => {},
-        }
+        // remove allocation; machine memory (like the CTFE heap) has been checked by the
+        // machine to be fine to keep around at this point
+        let (_kind, mut alloc) = self.alloc_map.remove(&alloc_id).unwrap();
         // ensure llvm knows not to put this into immutable memory
         alloc.mutability = mutability;
         let alloc = self.tcx.intern_const_alloc(alloc);
@@ -659,6 +663,23 @@ where
         }
         Ok(())
     }
+
+    /// Return the set of allocations reachable from `root` by following relocations,
+    /// including `root` itself. Allocations that are not local to this instance are
+    /// included, but not followed: they cannot point back into local memory.
+    pub fn reachable_allocs(&self, root: AllocId) -> FxHashSet<AllocId> {
+        let mut reachable = FxHashSet::default();
+        let mut todo = vec![root];
+        while let Some(id) = todo.pop() {
+            if !reachable.insert(id) {
+                continue;
+            }
+            if let Ok(&(_, ref alloc)) = self.alloc_map.get_or(id, || Err(())) {
+                todo.extend(alloc.relocations.values().map(|&(_, target)| target));
+            }
+        }
+        reachable
+    }
 }
 
 /// Reading and writing
@@ -20,6 +20,7 @@ mod operator;
 pub(crate) mod snapshot; // for const_eval
 mod step;
 mod terminator;
+mod trace;
 mod traits;
 mod validity;
 mod intrinsics;
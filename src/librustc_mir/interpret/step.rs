@@ -68,7 +68,11 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
 
         if let Some(stmt) = basic_block.statements.get(stmt_id) {
             assert_eq!(old_frames, self.cur_frame());
+            let traced = self.trace_statement(stmt);
             self.statement(stmt)?;
+            if let Some(traced) = traced {
+                self.trace_step_effects(traced);
+            }
             return Ok(true);
         }
 
@@ -76,7 +80,11 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
 
         let terminator = basic_block.terminator();
         assert_eq!(old_frames, self.cur_frame());
+        let traced = self.trace_terminator(terminator);
         self.terminator(terminator)?;
+        if let Some(traced) = traced {
+            self.trace_step_effects(traced);
+        }
         Ok(true)
     }
 
@@ -0,0 +1,180 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Human readable tracing of the steps taken by the interpreter.
+//!
+//! Unlike the `trace!` logging spread through this module, which needs a compiler built with
+//! debug assertions and covers every evaluation, a step trace is started for a single
+//! evaluation (see `-Z trace-const-eval`) and printed to stderr.
+
+use rustc::mir;
+use rustc::ty;
+
+use super::{EvalContext, Machine, Immediate, LocalValue, OpTy, PlaceTy};
+
+/// The state of a step trace started with `EvalContext::start_step_trace`.
+#[derive(Clone, Debug)]
+pub(super) struct StepTrace {
+    /// The number of steps printed so far.
+    steps: usize,
+    /// The number of steps after which we stop printing, or 0 to print every step.
+    limit: usize,
+}
+
+/// What needs to be printed after a traced step was executed.
+pub(super) struct TracedStep<'tcx, Tag> {
+    /// The frame the step was executed in.
+    frame: usize,
+    instance: ty::Instance<'tcx>,
+    /// The local written by the statement, if any.
+    assigned: Option<mir::Local>,
+    /// The return place of the frame, if the step is a `Return` terminator.
+    return_place: Option<PlaceTy<'tcx, Tag>>,
+}
+
+/// The local whose value changes when `place` is written to. Writes through a reference
+/// do not change the value of a local, so they are not reported.
+fn assigned_local(place: &mir::Place<'_>) -> Option<mir::Local> {
+    match *place {
+        mir::Place::Local(local) => Some(local),
+        mir::Place::Projection(ref proj) => match proj.elem {
+            mir::ProjectionElem::Deref => None,
+            _ => assigned_local(&proj.base),
+        },
+        mir::Place::Promoted(..) | mir::Place::Static(..) => None,
+    }
+}
+
+impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
+    /// Print every step executed from now on to stderr, together with the frames being entered
+    /// and left and the values written to locals. At most `limit` steps are printed, or all of
+    /// them if `limit` is 0.
+    pub fn start_step_trace(&mut self, limit: usize) {
+        self.step_trace = Some(StepTrace { steps: 0, limit });
+    }
+
+    /// Count the step about to be executed and decide whether it gets printed.
+    fn trace_next_step(&mut self) -> bool {
+        let trace = match self.step_trace {
+            Some(ref mut trace) => trace,
+            None => return false,
+        };
+        if trace.limit != 0 && trace.steps >= trace.limit {
+            if trace.steps == trace.limit {
+                let plural = if trace.limit == 1 { "" } else { "s" };
+                eprintln!("trace: stopped printing after {} step{}", trace.limit, plural);
+                trace.steps += 1;
+            }
+            return false;
+        }
+        trace.steps += 1;
+        true
+    }
+
+    fn trace_indent(&self, frame: usize) -> String {
+        "  ".repeat(frame + 1)
+    }
+
+    /// Print the statement that is about to be executed, if the step is traced.
+    pub(super) fn trace_statement(
+        &mut self,
+        stmt: &mir::Statement<'tcx>,
+    ) -> Option<TracedStep<'tcx, M::PointerTag>> {
+        if !self.trace_next_step() {
+            return None;
+        }
+        let frame = self.cur_frame();
+        eprintln!(
+            "{}{:?}[{}]: {:?}",
+            self.trace_indent(frame), self.frame().block, self.frame().stmt, stmt,
+        );
+        let assigned = match stmt.kind {
+            mir::StatementKind::Assign(ref place, _) |
+            mir::StatementKind::SetDiscriminant { ref place, .. } => assigned_local(place),
+            _ => None,
+        };
+        Some(TracedStep { frame, instance: self.frame().instance, assigned, return_place: None })
+    }
+
+    /// Print the terminator that is about to be executed, if the step is traced.
+    pub(super) fn trace_terminator(
+        &mut self,
+        terminator: &mir::Terminator<'tcx>,
+    ) -> Option<TracedStep<'tcx, M::PointerTag>> {
+        if !self.trace_next_step() {
+            return None;
+        }
+        let frame = self.cur_frame();
+        eprintln!(
+            "{}{:?}: {:?}",
+            self.trace_indent(frame), self.frame().block, terminator.kind,
+        );
+        let return_place = match terminator.kind {
+            mir::TerminatorKind::Return => self.frame().return_place,
+            _ => None,
+        };
+        Some(TracedStep { frame, instance: self.frame().instance, assigned: None, return_place })
+    }
+
+    /// Print the effects of a traced step: the value of the local it assigned to, the
+    /// arguments of a frame it pushed, or the value returned by a frame it popped.
+    pub(super) fn trace_step_effects(&self, step: TracedStep<'tcx, M::PointerTag>) {
+        let indent = self.trace_indent(step.frame);
+        if let Some(local) = step.assigned {
+            eprintln!("{}  {:?} = {}", indent, local, self.trace_local(step.frame, local));
+        }
+        if self.stack.len() > step.frame + 1 {
+            let callee = self.cur_frame();
+            let callee_indent = self.trace_indent(callee);
+            eprintln!("{}entering `{}`", callee_indent, self.frame().instance);
+            for arg in self.frame().mir.args_iter() {
+                eprintln!("{}  {:?} = {}", callee_indent, arg, self.trace_local(callee, arg));
+            }
+        } else if self.stack.len() <= step.frame {
+            let value = match step.return_place {
+                Some(place) => self.trace_place(place),
+                None => "<no value>".to_string(),
+            };
+            eprintln!("{}returning {} from `{}`", indent, value, step.instance);
+        }
+    }
+
+    fn trace_local(&self, frame: usize, local: mir::Local) -> String {
+        if local == mir::RETURN_PLACE {
+            return match self.stack[frame].return_place {
+                Some(place) => self.trace_place(place),
+                None => "<no return place>".to_string(),
+            };
+        }
+        if let LocalValue::Dead = self.stack[frame].locals[local] {
+            return "<dead>".to_string();
+        }
+        match self.access_local(&self.stack[frame], local, None) {
+            Ok(op) => self.trace_value(op),
+            Err(err) => format!("<{}>", err),
+        }
+    }
+
+    fn trace_place(&self, place: PlaceTy<'tcx, M::PointerTag>) -> String {
+        match self.place_to_op(place) {
+            Ok(op) => self.trace_value(op),
+            Err(err) => format!("<{}>", err),
+        }
+    }
+
+    fn trace_value(&self, op: OpTy<'tcx, M::PointerTag>) -> String {
+        match self.try_read_immediate(op) {
+            Ok(Ok(Immediate::Scalar(val))) => format!("{}: {}", val, op.layout.ty),
+            Ok(Ok(Immediate::ScalarPair(a, b))) => format!("({}, {}): {}", a, b, op.layout.ty),
+            Ok(Err(mplace)) => format!("<{:?} in memory>: {}", mplace.ptr, op.layout.ty),
+            Err(err) => format!("<{}>", err),
+        }
+    }
+}
@@ -77,6 +77,7 @@ mod diagnostics;
 mod borrow_check;
 mod build;
 mod dataflow;
+mod deny_panics;
 mod hair;
 mod lints;
 mod shim;
@@ -87,12 +88,15 @@ pub mod monomorphize;
 pub mod const_eval;
 
 pub use hair::pattern::check_crate as matchck_crate;
+pub use deny_panics::check_crate as deny_panics_check_crate;
+pub use transform::const_prop::check_unconditional_panics as unconditional_panic_check_crate;
 use rustc::ty::query::Providers;
 
 pub fn provide(providers: &mut Providers) {
     borrow_check::provide(providers);
     shim::provide(providers);
     transform::provide(providers);
+    lints::provide(providers);
     monomorphize::partitioning::provide(providers);
     providers.const_eval = const_eval::const_eval_provider;
     providers.const_eval_raw = const_eval::const_eval_raw_provider;
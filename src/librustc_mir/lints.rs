@@ -9,13 +9,20 @@
 // except according to those terms.
 
 use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::sync::Lrc;
 use rustc::hir::def_id::DefId;
 use rustc::hir::intravisit::FnKind;
 use rustc::hir::map::blocks::FnLikeNode;
+use rustc::lint::Level;
 use rustc::lint::builtin::UNCONDITIONAL_RECURSION;
-use rustc::mir::{self, Mir, TerminatorKind};
-use rustc::ty::{AssociatedItem, AssociatedItemContainer, Instance, TyCtxt, TyKind};
+use rustc::mir::{self, BasicBlock, CastKind, Mir, Rvalue, StatementKind, TerminatorKind};
+use rustc::mir::UnconditionalCall;
+use rustc::ty::{AssociatedItem, AssociatedItemContainer, Instance, InstanceDef, ParamEnv};
+use rustc::ty::{TyCtxt, TyKind};
+use rustc::ty::query::Providers;
 use rustc::ty::subst::Substs;
+use syntax_pos::Span;
 
 pub fn check(tcx: TyCtxt<'a, 'tcx, 'tcx>,
              mir: &Mir<'tcx>,
@@ -154,3 +161,242 @@ fn check_fn_for_unconditional_recursion(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         db.emit();
     }
 }
+
+pub fn provide(providers: &mut Providers) {
+    *providers = Providers {
+        unconditional_calls,
+        ..*providers
+    };
+}
+
+fn unconditional_calls<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                 def_id: DefId)
+                                 -> Lrc<Vec<UnconditionalCall<'tcx>>> {
+    if def_id.is_local() {
+        // NB: This `borrow()` is guaranteed to be valid (i.e., the value
+        // cannot yet be stolen), because `mir_const()`, which steals
+        // from `mir_built()`, forces this query to execute before
+        // performing the steal.
+        Lrc::new(find_unconditional_calls(tcx, &tcx.mir_built(def_id).borrow()))
+    } else {
+        Lrc::new(find_unconditional_calls(tcx, tcx.optimized_mir(def_id)))
+    }
+}
+
+/// Returns the functions that `mir` calls on every path to a return, given that it can return
+/// at all. Turning the `fmt` method of a formatting trait into a function pointer counts as a
+/// call, because that is how `format_args!` calls it.
+fn find_unconditional_calls<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                      mir: &Mir<'tcx>)
+                                      -> Vec<UnconditionalCall<'tcx>> {
+    if !returns_without_calling(mir, &[]) {
+        return vec![];
+    }
+
+    let mut calls = vec![];
+    for (bb, data) in mir.basic_blocks().iter_enumerated() {
+        for statement in &data.statements {
+            if let StatementKind::Assign(
+                _,
+                box Rvalue::Cast(CastKind::ReifyFnPointer, ref operand, _),
+            ) = statement.kind {
+                if let TyKind::FnDef(callee, substs) = operand.ty(mir, tcx).sty {
+                    if is_fmt_method(tcx, callee) {
+                        calls.push((bb, UnconditionalCall {
+                            callee,
+                            substs: tcx.erase_regions(&substs),
+                            span: statement.source_info.span,
+                            formats: true,
+                        }));
+                    }
+                }
+            }
+        }
+        let terminator = data.terminator();
+        if let TerminatorKind::Call { ref func, .. } = terminator.kind {
+            if let TyKind::FnDef(callee, substs) = func.ty(mir, tcx).sty {
+                calls.push((bb, UnconditionalCall {
+                    callee,
+                    substs: tcx.erase_regions(&substs),
+                    span: terminator.source_info.span,
+                    formats: false,
+                }));
+            }
+        }
+    }
+
+    let same_callee = |a: &UnconditionalCall<'tcx>, b: &UnconditionalCall<'tcx>| {
+        a.callee == b.callee && a.substs == b.substs
+    };
+    let mut unconditional_calls: Vec<UnconditionalCall<'tcx>> = vec![];
+    for (_, call) in &calls {
+        if unconditional_calls.iter().any(|known| same_callee(known, call)) {
+            continue;
+        }
+        let call_blocks = calls.iter()
+            .filter(|(_, other)| same_callee(other, call))
+            .map(|&(bb, _)| bb)
+            .collect::<Vec<_>>();
+        if !returns_without_calling(mir, &call_blocks) {
+            unconditional_calls.push(call.clone());
+        }
+    }
+    unconditional_calls
+}
+
+/// Whether `def_id` is the `fmt` method of one of the formatting traits, like `Display`.
+fn is_fmt_method(tcx: TyCtxt<'_, '_, '_>, def_id: DefId) -> bool {
+    match tcx.trait_of_item(def_id) {
+        Some(trait_def_id) => {
+            tcx.item_name(def_id) == "fmt" &&
+                tcx.absolute_item_path_str(trait_def_id).starts_with("core::fmt::")
+        }
+        None => false,
+    }
+}
+
+/// Checks for functions that cannot return without recursing through other functions: two
+/// functions that always call each other, or a trait method that always calls another method
+/// on the same `Self` type which calls it back, like a `Display` impl formatting `self` with
+/// `write!` or `to_string`. Functions that always call themselves directly are reported by
+/// `check`.
+///
+/// This uses the MIR of the functions called right after it's built, so it has to run before
+/// the MIR of `def_id` is stolen.
+pub fn check_calls<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) {
+    let node_id = tcx.hir.as_local_node_id(def_id).unwrap();
+    if FnLikeNode::from_node(tcx.hir.get(node_id)).is_none() ||
+        tcx.is_closure(def_id) ||
+        tcx.lint_level_at_node(UNCONDITIONAL_RECURSION, node_id).0 == Level::Allow
+    {
+        return;
+    }
+
+    let param_env = tcx.param_env(def_id);
+    let substs = tcx.erase_regions(&Substs::identity_for_item(tcx, def_id));
+    let root = Instance::new(def_id, substs);
+    let mut visited = FxHashSet::default();
+    let mut path = vec![];
+    if !find_cycle(tcx, param_env, root, root, &mut visited, &mut path) {
+        return;
+    }
+    // The cycle is reported at the first of its functions in this crate only.
+    if path.iter().any(|step| step.caller.def_id().is_local() && step.caller.def_id() < def_id) {
+        return;
+    }
+
+    let sp = tcx.sess.source_map().def_span(tcx.hir.span(node_id));
+    let mut db = tcx.struct_span_lint_node(UNCONDITIONAL_RECURSION,
+                                           node_id,
+                                           sp,
+                                           "function cannot return without recursing");
+    db.span_label(sp, "cannot return without recursing");
+    for step in &path {
+        // The calls made in other crates are left out, as there is nothing to do about them.
+        if !step.caller.def_id().is_local() {
+            continue;
+        }
+        let msg = format!("`{}` always {} `{}`",
+                          tcx.item_path_str(step.caller.def_id()),
+                          if step.formats { "formats a value with" } else { "calls" },
+                          tcx.item_path_str(step.callee));
+        db.span_note(step.span.source_callsite(), &msg);
+    }
+    db.help("a `loop` may express intention better if this is on purpose");
+    db.emit();
+}
+
+/// The number of functions searched for a path back to each checked function.
+const RECURSION_SEARCH_LIMIT: usize = 256;
+
+/// A call on a path of unconditional calls.
+struct RecursionStep<'tcx> {
+    caller: Instance<'tcx>,
+    /// The function called, as it is named at the call site.
+    callee: DefId,
+    span: Span,
+    formats: bool,
+}
+
+/// Searches for a path of unconditional calls from `instance` back to `root`. If one is found,
+/// `path` contains its calls.
+fn find_cycle<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                        param_env: ParamEnv<'tcx>,
+                        root: Instance<'tcx>,
+                        instance: Instance<'tcx>,
+                        visited: &mut FxHashSet<Instance<'tcx>>,
+                        path: &mut Vec<RecursionStep<'tcx>>)
+                        -> bool {
+    for call in tcx.unconditional_calls(instance.def_id()).iter() {
+        let substs = tcx.subst_and_normalize_erasing_regions(instance.substs,
+                                                             param_env,
+                                                             &call.substs);
+        let callee = match Instance::resolve(tcx, param_env, call.callee, substs) {
+            Some(callee) => callee,
+            None => continue,
+        };
+        if callee == instance {
+            // Calls of a function to itself are not a cycle through other functions.
+            continue;
+        }
+        if callee != root && !is_searchable(tcx, callee) {
+            continue;
+        }
+        path.push(RecursionStep {
+            caller: instance,
+            callee: call.callee,
+            span: call.span,
+            formats: call.formats,
+        });
+        if callee == root {
+            return true;
+        }
+        if visited.len() < RECURSION_SEARCH_LIMIT && visited.insert(callee) &&
+            find_cycle(tcx, param_env, root, callee, visited, path)
+        {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Whether the calls made by `instance` are searched for a path back to the checked function.
+fn is_searchable<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, instance: Instance<'tcx>) -> bool {
+    let def_id = instance.def_id();
+    match instance.def {
+        InstanceDef::Item(_) if tcx.is_mir_available(def_id) && !tcx.is_closure(def_id) => {}
+        _ => return false,
+    }
+    match tcx.hir.as_local_node_id(def_id) {
+        // Tuple struct constructors have MIR, but no body to build it from.
+        Some(node_id) => tcx.hir.maybe_body_owned_by(node_id).is_some(),
+        // Functions of other crates can only call back into this one through their type
+        // parameters.
+        None => instance.substs.types().any(|ty| {
+            ty.walk().any(|ty| match ty.sty {
+                TyKind::Adt(def, _) => def.did.is_local(),
+                TyKind::Closure(def_id, _) | TyKind::FnDef(def_id, _) => def_id.is_local(),
+                _ => false,
+            })
+        }),
+    }
+}
+
+/// Whether `mir` can reach a return without going through one of the `calls` blocks.
+fn returns_without_calling(mir: &Mir<'_>, calls: &[BasicBlock]) -> bool {
+    let basic_blocks = mir.basic_blocks();
+    let mut queue = vec![mir::START_BLOCK];
+    let mut visited = BitSet::new_empty(basic_blocks.len());
+    while let Some(bb) = queue.pop() {
+        if !visited.insert(bb) || calls.contains(&bb) {
+            continue;
+        }
+        let terminator = basic_blocks[bb].terminator();
+        match terminator.kind {
+            TerminatorKind::Abort | TerminatorKind::Return => return true,
+            _ => queue.extend(terminator.successors().cloned()),
+        }
+    }
+    false
+}
@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc_data_structures::sync::Lrc;
 
@@ -16,13 +16,19 @@ use rustc::ty::query::Providers;
 use rustc::ty::{self, TyCtxt};
 use rustc::hir;
 use rustc::hir::Node;
-use rustc::hir::def_id::DefId;
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
+use rustc::lint::Level;
 use rustc::lint::builtin::{SAFE_EXTERN_STATICS, SAFE_PACKED_BORROWS, UNUSED_UNSAFE};
+use rustc::lint::builtin::UNDOCUMENTED_UNSAFE_BLOCKS;
 use rustc::mir::*;
 use rustc::mir::visit::{PlaceContext, Visitor, MutatingUseContext};
 
+use rustc_serialize::json::as_pretty_json;
 use syntax::ast;
 use syntax::symbol::Symbol;
+use syntax_pos::Span;
+
+use std::fs;
 
 use util;
 
@@ -36,6 +42,7 @@ pub struct UnsafetyChecker<'a, 'tcx: 'a> {
     param_env: ty::ParamEnv<'tcx>,
     used_unsafe: FxHashSet<ast::NodeId>,
     inherited_blocks: Vec<(ast::NodeId, bool)>,
+    unsafe_operations: Vec<(Option<ast::NodeId>, UnsafetyViolation)>,
 }
 
 impl<'a, 'gcx, 'tcx> UnsafetyChecker<'a, 'tcx> {
@@ -59,6 +66,7 @@ impl<'a, 'gcx, 'tcx> UnsafetyChecker<'a, 'tcx> {
             param_env,
             used_unsafe: Default::default(),
             inherited_blocks: vec![],
+            unsafe_operations: vec![],
         }
     }
 }
@@ -139,7 +147,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                 &AggregateKind::Closure(def_id, _) |
                 &AggregateKind::Generator(def_id, _, _) => {
                     let UnsafetyCheckResult {
-                        violations, unsafe_blocks
+                        violations, unsafe_blocks, ..
                     } = self.tcx.unsafety_check_result(def_id);
                     self.register_violations(&violations, &unsafe_blocks);
                 }
@@ -294,11 +302,16 @@ impl<'a, 'tcx> UnsafetyChecker<'a, 'tcx> {
                 }
                 false
             }
-            Safety::BuiltinUnsafe | Safety::FnUnsafe => true,
+            Safety::BuiltinUnsafe => true,
+            Safety::FnUnsafe => {
+                self.unsafe_operations.extend(violations.iter().map(|&v| (None, v)));
+                true
+            }
             Safety::ExplicitUnsafe(node_id) => {
                 if !violations.is_empty() {
                     self.used_unsafe.insert(node_id);
                 }
+                self.unsafe_operations.extend(violations.iter().map(|&v| (Some(node_id), v)));
                 true
             }
         };
@@ -377,7 +390,8 @@ fn unsafety_check_result<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId)
             debug!("unsafety_violations: {:?} - remote, skipping", def_id);
             return UnsafetyCheckResult {
                 violations: Lrc::new([]),
-                unsafe_blocks: Lrc::new([])
+                unsafe_blocks: Lrc::new([]),
+                unsafe_operations: Lrc::new([]),
             }
         }
     };
@@ -391,7 +405,8 @@ fn unsafety_check_result<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId)
     check_unused_unsafe(tcx, def_id, &checker.used_unsafe, &mut checker.inherited_blocks);
     UnsafetyCheckResult {
         violations: checker.violations.into(),
-        unsafe_blocks: checker.inherited_blocks.into()
+        unsafe_blocks: checker.inherited_blocks.into(),
+        unsafe_operations: checker.unsafe_operations.into(),
     }
 }
 
@@ -478,7 +493,8 @@ pub fn check_unsafety<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) {
 
     let UnsafetyCheckResult {
         violations,
-        unsafe_blocks
+        unsafe_blocks,
+        ..
     } = tcx.unsafety_check_result(def_id);
 
     for &UnsafetyViolation {
@@ -535,5 +551,195 @@ pub fn check_unsafety<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) {
         if !is_used {
             report_unused_unsafe(tcx, &used_unsafe, block_id);
         }
+        check_safety_comment(tcx, block_id);
+    }
+}
+
+fn check_safety_comment(tcx: TyCtxt, block_id: ast::NodeId) {
+    if tcx.lint_level_at_node(UNDOCUMENTED_UNSAFE_BLOCKS, block_id).0 == Level::Allow {
+        return;
+    }
+    let span = tcx.hir.span(block_id);
+    if span.ctxt().outer().expn_info().is_some() || has_safety_comment(tcx, span) {
+        return;
+    }
+    let span = tcx.sess.source_map().def_span(span);
+    tcx.struct_span_lint_node(UNDOCUMENTED_UNSAFE_BLOCKS,
+                              block_id,
+                              span,
+                              "`unsafe` block without a `// SAFETY:` comment")
+        .help("explain why the operations in this block are sound in a comment starting \
+               with `// SAFETY:` directly above it")
+        .emit();
+}
+
+/// Whether the `unsafe` block at `span` is preceded by a comment containing `SAFETY:`, either
+/// on the lines directly above it or, for a `/* */` comment, on the line it starts on.
+fn has_safety_comment(tcx: TyCtxt, span: Span) -> bool {
+    let loc = tcx.sess.source_map().lookup_char_pos(span.lo());
+    if let Some(line) = loc.file.get_line(loc.line - 1) {
+        let before: String = line.chars().take(loc.col.0).collect();
+        if before.find("/*").map_or(false, |i| before[i..].contains("SAFETY:")) {
+            return true;
+        }
+    }
+    // Whether the lines above are in a `/* */` comment that ends on a line below.
+    let mut in_block_comment = false;
+    for line_index in (0..loc.line - 1).rev() {
+        let line = match loc.file.get_line(line_index) {
+            Some(line) => line,
+            None => return false,
+        };
+        let line = line.trim();
+        if in_block_comment {
+            if line.contains("SAFETY:") {
+                return true;
+            }
+            in_block_comment = !line.contains("/*");
+        } else if line.ends_with("*/") {
+            if line.contains("SAFETY:") {
+                return true;
+            }
+            if !line.starts_with("/*") {
+                if line.contains("/*") {
+                    // A comment after some code.
+                    return false;
+                }
+                in_block_comment = true;
+            }
+        } else if line.starts_with("//") {
+            if line.contains("SAFETY:") {
+                return true;
+            }
+        } else if !line.starts_with("#[") {
+            return false;
+        }
+    }
+    false
+}
+
+#[derive(RustcEncodable)]
+struct UnsafeInventory {
+    crate_name: String,
+    unsafe_blocks: Vec<UnsafeBlockEntry>,
+    unsafe_fns: Vec<UnsafeFnEntry>,
+    unsafe_impls: Vec<UnsafeImplEntry>,
+}
+
+#[derive(RustcEncodable)]
+struct UnsafeBlockEntry {
+    /// The function containing the block.
+    function: String,
+    span: String,
+    /// Whether the block contains any operation that needs it.
+    used: bool,
+    safety_comment: bool,
+    operations: Vec<UnsafeOperationEntry>,
+}
+
+#[derive(RustcEncodable)]
+struct UnsafeFnEntry {
+    function: String,
+    span: String,
+    /// The unsafe operations outside of `unsafe` blocks.
+    operations: Vec<UnsafeOperationEntry>,
+}
+
+#[derive(RustcEncodable)]
+struct UnsafeImplEntry {
+    trait_path: String,
+    self_ty: String,
+    span: String,
+}
+
+#[derive(RustcEncodable)]
+struct UnsafeOperationEntry {
+    description: String,
+    span: String,
+}
+
+/// Writes the `unsafe` blocks, unsafe functions and unsafe impls of the crate, along with the
+/// unsafe operations in each block and function, as JSON to `path`. This is used for
+/// `-Z unsafe-inventory`.
+pub fn write_unsafe_inventory<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, path: &str) {
+    let source_map = tcx.sess.source_map();
+    let span_to_string = |span| source_map.span_to_string(span);
+    let operation_entry = |violation: &UnsafetyViolation| UnsafeOperationEntry {
+        description: violation.description.to_string(),
+        span: span_to_string(violation.source_info.span),
+    };
+
+    let mut block_operations: FxHashMap<ast::NodeId, Vec<UnsafeOperationEntry>> =
+        FxHashMap::default();
+    let mut unsafe_blocks = vec![];
+    let mut unsafe_fns = vec![];
+    for def_id in tcx.body_owners() {
+        let result = tcx.unsafety_check_result(def_id);
+        let mut fn_operations = vec![];
+        for (block_id, violation) in result.unsafe_operations.iter() {
+            match *block_id {
+                Some(block_id) => {
+                    block_operations.entry(block_id).or_default().push(operation_entry(violation))
+                }
+                None => fn_operations.push(operation_entry(violation)),
+            }
+        }
+        if tcx.is_closure(def_id) {
+            // The blocks in closures are part of the result of their parent.
+            continue;
+        }
+        let function = tcx.item_path_str(def_id);
+        for &(block_id, used) in result.unsafe_blocks.iter() {
+            let span = tcx.hir.span(block_id);
+            unsafe_blocks.push((block_id, UnsafeBlockEntry {
+                function: function.clone(),
+                span: span_to_string(span),
+                used,
+                safety_comment: has_safety_comment(tcx, span),
+                operations: vec![],
+            }));
+        }
+        let is_unsafe_fn = match tcx.type_of(def_id).sty {
+            ty::FnDef(..) => tcx.fn_sig(def_id).unsafety() == hir::Unsafety::Unsafe,
+            _ => false,
+        };
+        if is_unsafe_fn {
+            unsafe_fns.push(UnsafeFnEntry {
+                function,
+                span: span_to_string(source_map.def_span(tcx.def_span(def_id))),
+                operations: fn_operations,
+            });
+        }
+    }
+    let unsafe_blocks = unsafe_blocks.into_iter().map(|(block_id, mut entry)| {
+        entry.operations = block_operations.remove(&block_id).unwrap_or_default();
+        entry
+    }).collect();
+
+    let mut unsafe_impls = vec![];
+    for item in tcx.hir.krate().items.values() {
+        if let hir::ItemKind::Impl(hir::Unsafety::Unsafe, ..) = item.node {
+            let def_id = tcx.hir.local_def_id(item.id);
+            let trait_path = match tcx.impl_trait_ref(def_id) {
+                Some(trait_ref) => tcx.item_path_str(trait_ref.def_id),
+                None => continue,
+            };
+            unsafe_impls.push(UnsafeImplEntry {
+                trait_path,
+                self_ty: tcx.type_of(def_id).to_string(),
+                span: span_to_string(source_map.def_span(item.span)),
+            });
+        }
+    }
+    unsafe_impls.sort_by(|a, b| a.span.cmp(&b.span));
+
+    let inventory = UnsafeInventory {
+        crate_name: tcx.crate_name(LOCAL_CRATE).to_string(),
+        unsafe_blocks,
+        unsafe_fns,
+        unsafe_impls,
+    };
+    if let Err(err) = fs::write(path, as_pretty_json(&inventory).to_string()) {
+        tcx.sess.err(&format!("failed to write unsafe inventory to {}: {}", path, err));
     }
 }
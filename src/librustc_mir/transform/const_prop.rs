@@ -13,14 +13,19 @@
 
 
 use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
+use rustc::lint::Level;
 use rustc::mir::{Constant, Location, Place, Mir, Operand, Rvalue, Local};
 use rustc::mir::{NullOp, UnOp, StatementKind, Statement, BasicBlock, LocalKind};
 use rustc::mir::{TerminatorKind, ClearCrossCrate, SourceInfo, BinOp, ProjectionElem};
+use rustc::mir::{Terminator, AssertMessage, START_BLOCK};
 use rustc::mir::visit::{Visitor, PlaceContext, MutatingUseContext, NonMutatingUseContext};
 use rustc::mir::interpret::{EvalErrorKind, Scalar, GlobalId, EvalResult};
-use rustc::ty::{TyCtxt, self, Instance};
+use rustc::ty::{TyCtxt, self, Instance, InstanceDef, TypeFoldable};
+use syntax::ast;
 use syntax::source_map::{Span, DUMMY_SP};
 use rustc::ty::subst::Substs;
+use rustc_data_structures::bit_set::BitSet;
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc::ty::ParamEnv;
 use rustc::ty::layout::{
@@ -217,6 +222,7 @@ impl<'a, 'mir, 'tcx> ConstPropagator<'a, 'mir, 'tcx> {
                     | GeneratorResumedAfterPanic
                     | ReferencedConstant
                     | InfiniteLoop
+                    | StepLimitReached
                     => {
                         // FIXME: report UB here
                     },
@@ -483,6 +489,401 @@ impl<'a, 'mir, 'tcx> ConstPropagator<'a, 'mir, 'tcx> {
     }
 }
 
+/// Whether the unconditional panic lint runs at all, which is decided for the whole crate so
+/// that `optimized_mir` knows whether to keep a copy of the MIR it optimizes.
+pub fn checks_unconditional_panics<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) -> bool {
+    let lint = ::rustc::lint::builtin::UNCONDITIONAL_PANIC;
+    tcx.lint_level_at_node(lint, ast::CRATE_NODE_ID).0 != Level::Allow
+}
+
+/// Lints the calls in the crate that always panic for the constant arguments they are passed.
+///
+/// This runs during analysis, on the MIR of the callers and callees before it is optimized, so
+/// that the calls are never inlined. Unlike the other lints of this module it does not run as
+/// part of `ConstProp`: building the MIR of a function must not depend on the MIR of the
+/// functions it calls.
+pub fn check_unconditional_panics<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    use rustc::hir::map::blocks::FnLikeNode;
+
+    if !checks_unconditional_panics(tcx) {
+        return;
+    }
+    for def_id in tcx.body_owners() {
+        let node_id = tcx.hir.as_local_node_id(def_id).unwrap();
+        if FnLikeNode::from_node(tcx.hir.get(node_id)).is_none() {
+            continue;
+        }
+        if tcx.generics_of(def_id).requires_monomorphization(tcx) {
+            // FIXME: can't handle code with generics
+            continue;
+        }
+        check_calls(tcx, def_id);
+    }
+}
+
+/// Lints the calls of the function `def_id`, following the temporaries whose value is known
+/// into the arguments of the calls like `ConstProp` does.
+fn check_calls<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) {
+    let mir = tcx.unoptimized_mir(def_id);
+    let instance = Instance::new(def_id, Substs::identity_for_item(tcx, def_id));
+    let mut caller = match CallPropagator::new(tcx, instance, mir) {
+        Some(caller) => caller,
+        None => return,
+    };
+    // The blocks are not visited along a path, so only the locals assigned once are known.
+    caller.can_propagate = CanConstProp::check(mir);
+    for data in mir.basic_blocks() {
+        for statement in &data.statements {
+            caller.statement(statement);
+        }
+        let terminator = data.terminator();
+        if let TerminatorKind::Call { ref func, ref args, .. } = terminator.kind {
+            // Only immediates can be moved into the callee's interpreter, anything else
+            // would refer to memory of the caller's.
+            let args: Vec<_> = args.iter().map(|arg| {
+                caller.eval_operand(arg).filter(|value| match value.op {
+                    interpret::Operand::Immediate(_) => true,
+                    interpret::Operand::Indirect(_) => false,
+                })
+            }).collect();
+            check_call(tcx, caller.param_env, mir, func, &args, terminator.source_info);
+        }
+        caller.terminator(terminator);
+    }
+}
+
+/// Follows the arguments of a call that are known constants into the callee, and reports
+/// the call if it is guaranteed to panic because of them.
+fn check_call<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    param_env: ParamEnv<'tcx>,
+    mir: &Mir<'tcx>,
+    func: &Operand<'tcx>,
+    args: &[Option<OpTy<'tcx>>],
+    source_info: SourceInfo,
+) {
+    let (def_id, substs) = match *func {
+        Operand::Constant(ref c) => match c.ty.sty {
+            ty::FnDef(def_id, substs) => (def_id, substs),
+            _ => return,
+        },
+        _ => return,
+    };
+    if substs.needs_subst() {
+        // FIXME: can't handle code with generics
+        return;
+    }
+    if args.iter().all(Option::is_none) {
+        return;
+    }
+    let lint_root = match mir.source_scope_local_data {
+        ClearCrossCrate::Set(ref ivs) => ivs[source_info.scope].lint_root,
+        ClearCrossCrate::Clear => return,
+    };
+    let lint = ::rustc::lint::builtin::UNCONDITIONAL_PANIC;
+    if tcx.lint_level_at_node(lint, lint_root).0 == Level::Allow {
+        return;
+    }
+
+    let instance = match Instance::resolve(tcx, param_env, def_id, substs) {
+        Some(instance) => instance,
+        None => return,
+    };
+    let callee = match instance.def {
+        InstanceDef::Item(callee) => callee,
+        _ => return,
+    };
+    // Diagnostics point into the callee, so we only look at functions of this crate.
+    if !callee.is_local() || !tcx.is_mir_available(callee) {
+        return;
+    }
+    if tcx.generics_of(callee).requires_monomorphization(tcx) {
+        // FIXME: can't handle code with generics
+        return;
+    }
+    let callee_mir = tcx.unoptimized_mir(callee);
+
+    let mut propagator = match CallPropagator::new(tcx, instance, callee_mir) {
+        Some(propagator) => propagator,
+        None => return,
+    };
+    let panic = match propagator.find_panic(args) {
+        Some(panic) => panic,
+        None => return,
+    };
+    // A panic that is reached without knowing any of the arguments is not caused by
+    // this call.
+    if propagator.find_panic(&vec![None; args.len()]).is_some() {
+        return;
+    }
+    tcx.struct_span_lint_node(
+        lint,
+        lint_root,
+        source_info.span,
+        &format!("this call to `{}` will panic at runtime", tcx.item_path_str(callee)),
+    ).span_note(panic.span, &panic.msg).emit();
+}
+
+/// A failing assertion found by `CallPropagator`.
+struct CalleePanic {
+    span: Span,
+    msg: String,
+}
+
+/// Where the path through a callee continues after a terminator.
+enum PathStep {
+    Goto(BasicBlock),
+    Panic(CalleePanic),
+    /// The callee returns or diverges, or the successor depends on unknown values.
+    End,
+}
+
+/// Walks the single path that a call takes through the callee's MIR when some of its
+/// arguments are known, keeping track of the locals whose values are known on that path.
+///
+/// Unlike `ConstPropagator`, which looks at every statement of a function, this only ever
+/// evaluates statements that are executed for the given arguments, so an assertion that fails
+/// on the path is guaranteed to fail (as long as the calls on the path return).
+struct CallPropagator<'a, 'tcx: 'a> {
+    ecx: EvalContext<'a, 'tcx, 'tcx, CompileTimeInterpreter<'a, 'tcx, 'tcx>>,
+    mir: &'tcx Mir<'tcx>,
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    param_env: ParamEnv<'tcx>,
+    locals: IndexVec<Local, Option<OpTy<'tcx>>>,
+    /// Locals that are never borrowed, and thus only change through direct assignments.
+    can_propagate: IndexVec<Local, bool>,
+}
+
+impl<'a, 'tcx> CallPropagator<'a, 'tcx> {
+    fn new(
+        tcx: TyCtxt<'a, 'tcx, 'tcx>,
+        instance: Instance<'tcx>,
+        mir: &'tcx Mir<'tcx>,
+    ) -> Option<CallPropagator<'a, 'tcx>> {
+        let ecx = mk_borrowck_eval_cx(tcx, instance, mir, DUMMY_SP).ok()?;
+        let mut not_borrowed = NotBorrowed(IndexVec::from_elem(true, &mir.local_decls));
+        not_borrowed.visit_mir(mir);
+        Some(CallPropagator {
+            ecx,
+            mir,
+            tcx,
+            param_env: tcx.param_env(instance.def_id()),
+            locals: IndexVec::from_elem(None, &mir.local_decls),
+            can_propagate: not_borrowed.0,
+        })
+    }
+
+    /// Returns the failing assertion that the path taken for `args` ends in, if any.
+    /// `None` arguments are not known.
+    fn find_panic(&mut self, args: &[Option<OpTy<'tcx>>]) -> Option<CalleePanic> {
+        for value in self.locals.iter_mut() {
+            *value = None;
+        }
+        for (local, &arg) in self.mir.args_iter().zip(args) {
+            self.write(&Place::Local(local), arg);
+        }
+
+        // Once a block is visited a second time, we are in a loop and our values are stale.
+        let mut visited = BitSet::new_empty(self.mir.basic_blocks().len());
+        let mut block = START_BLOCK;
+        while visited.insert(block) {
+            let data = &self.mir[block];
+            for statement in &data.statements {
+                self.statement(statement);
+            }
+            match self.terminator(data.terminator()) {
+                PathStep::Goto(target) => block = target,
+                PathStep::Panic(panic) => return Some(panic),
+                PathStep::End => return None,
+            }
+        }
+        None
+    }
+
+    fn write(&mut self, place: &Place<'tcx>, value: Option<OpTy<'tcx>>) {
+        match *place {
+            Place::Local(local) if self.can_propagate[local] => self.locals[local] = value,
+            _ => if let Some(local) = place.base_local() {
+                self.locals[local] = None;
+            },
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement<'tcx>) {
+        match statement.kind {
+            StatementKind::Assign(ref place, ref rvalue) => {
+                let value = self.eval_rvalue(rvalue, place);
+                self.write(place, value);
+            }
+            StatementKind::SetDiscriminant { ref place, .. } => self.write(place, None),
+            StatementKind::StorageLive(local) |
+            StatementKind::StorageDead(local) => self.locals[local] = None,
+            StatementKind::InlineAsm { ref outputs, .. } => {
+                for place in outputs.iter() {
+                    self.write(place, None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn terminator(&mut self, terminator: &Terminator<'tcx>) -> PathStep {
+        match terminator.kind {
+            TerminatorKind::Goto { target } |
+            TerminatorKind::Drop { target, .. } |
+            TerminatorKind::FalseEdges { real_target: target, .. } |
+            TerminatorKind::FalseUnwind { real_target: target, .. } => PathStep::Goto(target),
+            TerminatorKind::DropAndReplace { ref location, target, .. } => {
+                self.write(location, None);
+                PathStep::Goto(target)
+            }
+            // We do not look into nested calls, their result is unknown.
+            TerminatorKind::Call { destination: Some((ref place, target)), .. } => {
+                self.write(place, None);
+                PathStep::Goto(target)
+            }
+            TerminatorKind::SwitchInt { ref discr, ref values, ref targets, .. } => {
+                let bits = match self.eval_operand(discr).and_then(|discr| self.read_bits(discr)) {
+                    Some(bits) => bits,
+                    None => return PathStep::End,
+                };
+                let index = values.iter().position(|&value| value == bits)
+                    .unwrap_or(values.len());
+                PathStep::Goto(targets[index])
+            }
+            TerminatorKind::Assert { ref cond, expected, ref msg, target, .. } => {
+                let cond = self.eval_operand(cond)
+                    .and_then(|cond| self.ecx.read_scalar(cond).ok())
+                    .and_then(|cond| cond.not_undef().ok())
+                    .and_then(|cond| cond.to_bool().ok());
+                match cond {
+                    Some(cond) if cond == expected => PathStep::Goto(target),
+                    Some(_) => match self.panic_message(msg) {
+                        Some(msg) => PathStep::Panic(CalleePanic {
+                            span: terminator.source_info.span,
+                            msg,
+                        }),
+                        None => PathStep::End,
+                    },
+                    None => PathStep::End,
+                }
+            }
+            _ => PathStep::End,
+        }
+    }
+
+    fn panic_message(&mut self, msg: &AssertMessage<'tcx>) -> Option<String> {
+        use rustc::mir::interpret::EvalErrorKind::*;
+        match *msg {
+            Overflow(_) |
+            OverflowNeg |
+            DivisionByZero |
+            RemainderByZero => Some(msg.description().to_owned()),
+            BoundsCheck { ref len, ref index } => {
+                let len = self.eval_operand(len).and_then(|len| self.read_bits(len))?;
+                let index = self.eval_operand(index).and_then(|index| self.read_bits(index))?;
+                Some(format!(
+                    "index out of bounds: the len is {} but the index is {}",
+                    len,
+                    index,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn read_bits(&self, op: OpTy<'tcx>) -> Option<u128> {
+        self.ecx.read_scalar(op).ok()?.not_undef().ok()?.to_bits(op.layout.size).ok()
+    }
+
+    fn eval_operand(&mut self, op: &Operand<'tcx>) -> Option<OpTy<'tcx>> {
+        match *op {
+            Operand::Constant(ref c) => const_to_op(&self.ecx, c.literal).ok(),
+            Operand::Copy(ref place) | Operand::Move(ref place) => self.eval_place(place),
+        }
+    }
+
+    fn eval_place(&mut self, place: &Place<'tcx>) -> Option<OpTy<'tcx>> {
+        match *place {
+            Place::Local(local) => self.locals[local],
+            Place::Projection(ref proj) => match proj.elem {
+                ProjectionElem::Field(field, _) => {
+                    let base = self.eval_place(&proj.base)?;
+                    self.ecx.operand_field(base, field.index() as u64).ok()
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn eval_rvalue(&mut self, rvalue: &Rvalue<'tcx>, place: &Place<'tcx>) -> Option<OpTy<'tcx>> {
+        let place_ty = place.ty(&self.mir.local_decls, self.tcx).to_ty(self.tcx);
+        let layout = self.tcx.layout_of(self.param_env.and(place_ty)).ok()?;
+        let val = match *rvalue {
+            Rvalue::Use(ref op) => return self.eval_operand(op),
+            Rvalue::Len(ref place) => {
+                let len = match place.ty(&self.mir.local_decls, self.tcx).to_ty(self.tcx).sty {
+                    ty::Array(_, len) => len.assert_usize(self.tcx)?,
+                    _ => return None,
+                };
+                Immediate::Scalar(Scalar::from_uint(len, layout.size).into())
+            }
+            Rvalue::Cast(kind, ref op, _) => {
+                let op = self.eval_operand(op)?;
+                let dest = self.ecx.allocate(layout, MemoryKind::Stack).ok()?;
+                self.ecx.cast(op, kind, dest.into()).ok()?;
+                return self.ecx.read_immediate(dest.into()).ok().map(Into::into);
+            }
+            Rvalue::UnaryOp(op, ref arg) => {
+                let arg = self.eval_operand(arg)?;
+                let prim = self.ecx.read_scalar(arg).ok()?.not_undef().ok()?;
+                Immediate::Scalar(self.ecx.unary_op(op, prim, arg.layout).ok()?.into())
+            }
+            Rvalue::BinaryOp(op, ref left, ref right) |
+            Rvalue::CheckedBinaryOp(op, ref left, ref right) => {
+                let left = self.eval_operand(left)?;
+                let right = self.eval_operand(right)?;
+                let left = self.ecx.read_immediate(left).ok()?;
+                let right = self.ecx.read_immediate(right).ok()?;
+                let (val, overflow) = self.ecx.binary_op_imm(op, left, right).ok()?;
+                if let Rvalue::CheckedBinaryOp(..) = *rvalue {
+                    Immediate::ScalarPair(val.into(), Scalar::from_bool(overflow).into())
+                } else if overflow {
+                    return None;
+                } else {
+                    Immediate::Scalar(val.into())
+                }
+            }
+            _ => return None,
+        };
+        Some(OpTy { op: interpret::Operand::Immediate(val), layout })
+    }
+}
+
+/// Finds the locals whose address is taken, which may change without being assigned to.
+struct NotBorrowed(IndexVec<Local, bool>);
+
+impl<'tcx> Visitor<'tcx> for NotBorrowed {
+    fn visit_local(
+        &mut self,
+        &local: &Local,
+        context: PlaceContext<'tcx>,
+        _: Location,
+    ) {
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Borrow(..)) |
+            PlaceContext::MutatingUse(MutatingUseContext::AsmOutput) |
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow(..)) |
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow(..)) |
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::UniqueBorrow(..)) => {
+                self.0[local] = false;
+            }
+            _ => {}
+        }
+    }
+}
+
 fn type_size_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           param_env: ty::ParamEnv<'tcx>,
                           ty: ty::Ty<'tcx>) -> Option<u64> {
@@ -10,6 +10,7 @@
 
 use borrow_check::nll::type_check;
 use build;
+use lints;
 use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc::mir::{Mir, MirPhase, Promoted};
 use rustc::ty::TyCtxt;
@@ -56,6 +57,7 @@ pub(crate) fn provide(providers: &mut Providers) {
         mir_built,
         mir_const,
         mir_validated,
+        unoptimized_mir,
         optimized_mir,
         is_mir_available,
         ..*providers
@@ -207,6 +209,13 @@ fn mir_const<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Stea
     // Unsafety check uses the raw mir, so make sure it is run
     let _ = tcx.unsafety_check_result(def_id);
 
+    // So does the unconditional recursion lint, for this function and the functions it calls.
+    let node_id = tcx.hir.as_local_node_id(def_id).unwrap();
+    if let hir::BodyOwnerKind::Fn = tcx.hir.body_owner_kind(node_id) {
+        let _ = tcx.unconditional_calls(def_id);
+        lints::check_calls(tcx, def_id);
+    }
+
     let mut mir = tcx.mir_built(def_id).steal();
     run_passes(tcx, &mut mir, def_id, MirPhase::Const, &[
         // What we need to do constant evaluation.
@@ -235,11 +244,24 @@ fn mir_validated<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
     tcx.alloc_steal_mir(mir)
 }
 
+fn unoptimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Mir<'tcx> {
+    let mir = tcx.mir_validated(def_id).borrow();
+    tcx.alloc_mir(tcx.erase_regions(&*mir))
+}
+
 fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Mir<'tcx> {
     // (Mir-)Borrowck uses `mir_validated`, so we have to force it to
     // execute before we can steal.
     let _ = tcx.mir_borrowck(def_id);
 
+    // So does the unconditional panic lint, through a copy of the functions it looks into.
+    if const_prop::checks_unconditional_panics(tcx) {
+        let node_id = tcx.hir.as_local_node_id(def_id).unwrap();
+        if let hir::BodyOwnerKind::Fn = tcx.hir.body_owner_kind(node_id) {
+            let _ = tcx.unoptimized_mir(def_id);
+        }
+    }
+
     if tcx.use_ast_borrowck() {
         let _ = tcx.borrowck(def_id);
     }
@@ -198,6 +198,29 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
         }
     }
 
+    /// Error about a branch or loop whose feature gate is not enabled, in a body that
+    /// is allowed to contain the other kind of control flow.
+    fn control_flow_feature_err(&mut self, feature: &str, explain: &str) {
+        self.add(Qualif::NOT_CONST);
+        if self.mode != Mode::Fn {
+            emit_feature_err(
+                &self.tcx.sess.parse_sess,
+                feature,
+                self.span,
+                GateIssue::Language,
+                &format!("{} in {}s are unstable", explain, self.mode),
+            );
+        }
+    }
+
+    /// Whether branches (`const_if_match`) or loops (`const_loop`) may appear in this body,
+    /// in which case locals can be assigned more than once and every reachable block has
+    /// to be qualified instead of following a single path.
+    fn control_flow_allowed(&self) -> bool {
+        let features = self.tcx.features();
+        features.const_if_match || features.const_loop
+    }
+
     /// Add the given qualification to self.qualif.
     fn add(&mut self, qualif: Qualif) {
         self.qualif = self.qualif | qualif;
@@ -243,7 +266,17 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
             return;
         }
 
-        if self.tcx.features().const_let {
+        // Branches and loops assign temporaries and the return place once per path,
+        // so those assignments are merged even without `const_let`.
+        let merge_assignments = match *dest {
+            Place::Local(index) => match self.mir.local_kind(index) {
+                LocalKind::Temp | LocalKind::ReturnPointer => self.control_flow_allowed(),
+                LocalKind::Arg | LocalKind::Var => false,
+            },
+            _ => false,
+        };
+
+        if self.tcx.features().const_let || merge_assignments {
             let mut dest = dest;
             let index = loop {
                 match dest {
@@ -295,6 +328,18 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                 store(&mut self.local_qualif[index])
             }
 
+            Place::Projection(box Projection {
+                base: Place::Local(index),
+                elem: ProjectionElem::Deref
+            }) if self.tcx.features().const_heap
+               && self.mir.local_kind(index) == LocalKind::Temp
+               && self.mir.local_decls[index].ty.is_box() => {
+                // Part of `box expr`, the box inherits the qualification of its contents.
+                if let Some(ref mut box_qualif) = self.local_qualif[index] {
+                    *box_qualif = *box_qualif | qualif;
+                }
+            }
+
             Place::Projection(box Projection {
                 base: Place::Local(index),
                 elem: ProjectionElem::Deref
@@ -326,6 +371,49 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
 
         let mir = self.mir;
 
+        if self.control_flow_allowed() {
+            self.qualify_all_blocks();
+        } else {
+            self.qualify_single_path();
+        }
+
+        self.qualif = self.local_qualif[RETURN_PLACE].unwrap_or(Qualif::NOT_CONST);
+
+        // Account for errors in consts by using the
+        // conservative type qualification instead.
+        if self.qualif.intersects(Qualif::CONST_ERROR) {
+            self.qualif = Qualif::empty();
+            let return_ty = mir.return_ty();
+            self.add_type(return_ty);
+        }
+
+
+        // Collect all the temps we need to promote.
+        let mut promoted_temps = BitSet::new_empty(self.temp_promotion_state.len());
+
+        debug!("qualify_const: promotion_candidates={:?}", self.promotion_candidates);
+        for candidate in &self.promotion_candidates {
+            match *candidate {
+                Candidate::Ref(Location { block: bb, statement_index: stmt_idx }) => {
+                    match self.mir[bb].statements[stmt_idx].kind {
+                        StatementKind::Assign(_, box Rvalue::Ref(_, _, Place::Local(index))) => {
+                            promoted_temps.insert(index);
+                        }
+                        _ => {}
+                    }
+                }
+                Candidate::Argument { .. } => {}
+            }
+        }
+
+        (self.qualif, Lrc::new(promoted_temps))
+    }
+
+    /// Qualify the single path through a body without branches or loops, reporting
+    /// any other control flow as unimplemented.
+    fn qualify_single_path(&mut self) {
+        let mir = self.mir;
+
         let mut seen_blocks = BitSet::new_empty(mir.basic_blocks().len());
         let mut bb = START_BLOCK;
         loop {
@@ -358,42 +446,7 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
 
                 TerminatorKind::Return => {
                     if !self.tcx.features().const_let {
-                        // Check for unused values. This usually means
-                        // there are extra statements in the AST.
-                        for temp in mir.temps_iter() {
-                            if self.local_qualif[temp].is_none() {
-                                continue;
-                            }
-
-                            let state = self.temp_promotion_state[temp];
-                            if let TempState::Defined { location, uses: 0 } = state {
-                                let data = &mir[location.block];
-                                let stmt_idx = location.statement_index;
-
-                                // Get the span for the initialization.
-                                let source_info = if stmt_idx < data.statements.len() {
-                                    data.statements[stmt_idx].source_info
-                                } else {
-                                    data.terminator().source_info
-                                };
-                                self.span = source_info.span;
-
-                                // Treat this as a statement in the AST.
-                                self.statement_like();
-                            }
-                        }
-
-                        // Make sure there are no extra unassigned variables.
-                        self.qualif = Qualif::NOT_CONST;
-                        for index in mir.vars_iter() {
-                            if !self.const_fn_arg_vars.contains(index) {
-                                debug!("unassigned variable {:?}", index);
-                                self.assign(&Place::Local(index), Location {
-                                    block: bb,
-                                    statement_index: usize::MAX,
-                                });
-                            }
-                        }
+                        self.check_unused_values(bb);
                     }
 
                     break;
@@ -411,37 +464,122 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                 }
             }
         }
+    }
 
-        self.qualif = self.local_qualif[RETURN_PLACE].unwrap_or(Qualif::NOT_CONST);
+    /// Qualify every block reachable from the start of the body, checking each branch
+    /// and loop against its feature gate.
+    fn qualify_all_blocks(&mut self) {
+        let mir = self.mir;
+        let features = self.tcx.features();
 
-        // Account for errors in consts by using the
-        // conservative type qualification instead.
-        if self.qualif.intersects(Qualif::CONST_ERROR) {
-            self.qualif = Qualif::empty();
-            let return_ty = mir.return_ty();
-            self.add_type(return_ty);
+        let blocks: Vec<_> = traversal::reverse_postorder(mir).map(|(bb, _)| bb).collect();
+        let mut rpo_index = IndexVec::from_elem(usize::MAX, mir.basic_blocks());
+        for (index, &bb) in blocks.iter().enumerate() {
+            rpo_index[bb] = index;
         }
 
+        // Blocks are visited in reverse postorder, so an assignment is always seen before
+        // the uses it reaches through forward edges. Values carried around a loop by its
+        // back edge are not, so with loops we start every user variable out with the most
+        // conservative qualification its type allows.
+        let has_back_edge = blocks.iter().any(|&bb| {
+            mir[bb].terminator().successors().any(|&succ| rpo_index[succ] <= rpo_index[bb])
+        });
+        if has_back_edge {
+            for local in mir.vars_iter() {
+                let mut qualif = Qualif::MUTABLE_INTERIOR | Qualif::NEEDS_DROP;
+                qualif.restrict(mir.local_decls[local].ty, self.tcx, self.param_env);
+                self.local_qualif[local] = Some(qualif);
+            }
+        }
 
-        // Collect all the temps we need to promote.
-        let mut promoted_temps = BitSet::new_empty(self.temp_promotion_state.len());
+        for &bb in &blocks {
+            let data = &mir[bb];
+            // Unwinding never happens during constant evaluation.
+            if data.is_cleanup {
+                continue;
+            }
 
-        debug!("qualify_const: promotion_candidates={:?}", self.promotion_candidates);
-        for candidate in &self.promotion_candidates {
-            match *candidate {
-                Candidate::Ref(Location { block: bb, statement_index: stmt_idx }) => {
-                    match self.mir[bb].statements[stmt_idx].kind {
-                        StatementKind::Assign(_, box Rvalue::Ref(_, _, Place::Local(index))) => {
-                            promoted_temps.insert(index);
-                        }
-                        _ => {}
-                    }
-                }
-                Candidate::Argument { .. } => {}
+            self.visit_basic_block_data(bb, data);
+
+            match data.terminator().kind {
+                TerminatorKind::Goto { .. } |
+                TerminatorKind::Drop { .. } |
+                TerminatorKind::Assert { .. } |
+                TerminatorKind::Call { .. } |
+                TerminatorKind::Unreachable |
+                TerminatorKind::FalseUnwind { .. } => {}
+
+                TerminatorKind::SwitchInt { .. } |
+                TerminatorKind::FalseEdges { .. } => if !features.const_if_match {
+                    self.control_flow_feature_err(
+                        "const_if_match",
+                        "`if`, `match`, `&&` and `||`",
+                    );
+                },
+
+                TerminatorKind::Return => if !features.const_let {
+                    self.check_unused_values(bb);
+                },
+
+                TerminatorKind::DropAndReplace { .. } |
+                TerminatorKind::Resume |
+                TerminatorKind::Abort |
+                TerminatorKind::GeneratorDrop |
+                TerminatorKind::Yield { .. } => self.not_const(),
+            }
+
+            let is_loop = data.terminator().successors().any(|&succ| {
+                !mir[succ].is_cleanup && rpo_index[succ] <= rpo_index[bb]
+            });
+            if is_loop && !features.const_loop {
+                self.control_flow_feature_err("const_loop", "loops");
             }
         }
+    }
 
-        (self.qualif, Lrc::new(promoted_temps))
+    /// Without `const_let`, check that a body reaching `bb`'s `return` has no values
+    /// computed for their side effects and no unassigned variables, both of which mean
+    /// there are extra statements in the AST.
+    fn check_unused_values(&mut self, bb: BasicBlock) {
+        let mir = self.mir;
+
+        // Check for unused values. This usually means
+        // there are extra statements in the AST.
+        for temp in mir.temps_iter() {
+            if self.local_qualif[temp].is_none() {
+                continue;
+            }
+
+            let state = self.temp_promotion_state[temp];
+            if let TempState::Defined { location, uses: 0 } = state {
+                let data = &mir[location.block];
+                let stmt_idx = location.statement_index;
+
+                // Get the span for the initialization.
+                let source_info = if stmt_idx < data.statements.len() {
+                    data.statements[stmt_idx].source_info
+                } else {
+                    data.terminator().source_info
+                };
+                self.span = source_info.span;
+
+                // Treat this as a statement in the AST.
+                self.statement_like();
+            }
+        }
+
+        // Make sure there are no extra unassigned variables.
+        self.qualif = Qualif::NOT_CONST;
+        for index in mir.vars_iter() {
+            if !self.const_fn_arg_vars.contains(index) {
+                debug!("unassigned variable {:?}", index);
+                self.assign(&Place::Local(index), Location {
+                    block: bb,
+                    statement_index: usize::MAX,
+                });
+            }
+        }
     }
 
     fn is_const_panic_fn(&self, def_id: DefId) -> bool {
@@ -602,6 +740,12 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                             this.qualif.restrict(ty, this.tcx, this.param_env);
                         }
 
+                        ProjectionElem::Downcast(..)
+                            if this.mode != Mode::Fn && this.tcx.features().const_if_match => {
+                            let ty = place.ty(this.mir, this.tcx).to_ty(this.tcx);
+                            this.qualif.restrict(ty, this.tcx, this.param_env);
+                        }
+
                         ProjectionElem::ConstantIndex {..} |
                         ProjectionElem::Subslice {..} |
                         ProjectionElem::Downcast(..) => {
@@ -848,6 +992,11 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                 }
             }
 
+            // Const eval makes sure the allocation is freed again or
+            // only ends up in the final value of an immutable static.
+            Rvalue::NullaryOp(NullOp::Box, _)
+                if self.mode != Mode::Fn && self.tcx.features().const_heap => {}
+
             Rvalue::NullaryOp(NullOp::Box, _) => {
                 self.add(Qualif::NOT_CONST);
                 if self.mode != Mode::Fn {
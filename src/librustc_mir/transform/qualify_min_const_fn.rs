@@ -2,11 +2,22 @@ use rustc::hir::def_id::DefId;
 use rustc::hir;
 use rustc::mir::*;
 use rustc::ty::{self, Predicate, TyCtxt};
+use syntax::feature_gate::Features;
 use std::borrow::Cow;
 use syntax_pos::Span;
 
 type McfResult = Result<(), (Span, Cow<'static, str>)>;
 
+/// Whether an unstable feature relaxing the `min_const_fn` rules is enabled.
+///
+/// Crates using `staged_api` never get the relaxation: their stable `const fn`s are callable
+/// from user `min_const_fn`s, so enabling a feature for the standard library must not make
+/// it part of the stable subset.
+fn feature_allowed(tcx: TyCtxt<'a, 'tcx, 'tcx>, feature: fn(&Features) -> bool) -> bool {
+    let features = tcx.features();
+    !features.staged_api && feature(&features)
+}
+
 pub fn is_min_const_fn(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     def_id: DefId,
@@ -188,10 +199,11 @@ fn check_rvalue(
             }
         }
         Rvalue::NullaryOp(NullOp::SizeOf, _) => Ok(()),
-        Rvalue::NullaryOp(NullOp::Box, _) => Err((
-            span,
-            "heap allocations are not allowed in const fn".into(),
-        )),
+        Rvalue::NullaryOp(NullOp::Box, _) => if feature_allowed(tcx, |f| f.const_heap) {
+            Ok(())
+        } else {
+            Err((span, "heap allocations are not allowed in const fn".into()))
+        },
         Rvalue::UnaryOp(_, operand) => {
             let ty = operand.ty(mir, tcx);
             if ty.is_integral() || ty.is_bool() {
@@ -229,7 +241,11 @@ fn check_statement(
             check_rvalue(tcx, mir, rval, span)
         }
 
-        StatementKind::FakeRead(..) => Err((span, "match in const fn is unstable".into())),
+        StatementKind::FakeRead(..) => if feature_allowed(tcx, |f| f.const_if_match) {
+            Ok(())
+        } else {
+            Err((span, "match in const fn is unstable".into()))
+        },
 
         // just an assignment
         StatementKind::SetDiscriminant { .. } => Ok(()),
@@ -291,7 +307,9 @@ fn check_place(
                 | ProjectionElem::ConstantIndex { .. } | ProjectionElem::Subslice { .. } => {
                     return Err((span, "slice patterns in const fn are unstable".into()))
                 }
-                | ProjectionElem::Downcast(..) => {
+                | ProjectionElem::Downcast(..) => if feature_allowed(tcx, |f| f.const_if_match) {
+                    check_place(tcx, mir, &proj.base, span, mode)
+                } else {
                     Err((span, "`match` or `if let` in `const fn` is unstable".into()))
                 }
             }
@@ -318,10 +336,21 @@ fn check_terminator(
             check_operand(tcx, mir, value, span)
         },
 
-        TerminatorKind::FalseEdges { .. } | TerminatorKind::SwitchInt { .. } => Err((
+        TerminatorKind::FalseEdges { .. } | TerminatorKind::SwitchInt { .. }
+            if !feature_allowed(tcx, |f| f.const_if_match) => Err((
             span,
             "`if`, `match`, `&&` and `||` are not stable in const fn".into(),
         )),
+        TerminatorKind::FalseEdges { .. } => Ok(()),
+        TerminatorKind::SwitchInt {
+            discr,
+            switch_ty: _,
+            values: _,
+            targets: _,
+        } => check_operand(tcx, mir, discr, span),
+
+        // exhaustive `match`es end in an unreachable `otherwise` branch
+        TerminatorKind::Unreachable if feature_allowed(tcx, |f| f.const_if_match) => Ok(()),
         | TerminatorKind::Abort | TerminatorKind::Unreachable => {
             Err((span, "const fn with unreachable code is not stable".into()))
         }
@@ -364,7 +393,9 @@ fn check_terminator(
             cleanup: _,
         } => check_operand(tcx, mir, cond, span),
 
-        TerminatorKind::FalseUnwind { .. } => {
+        TerminatorKind::FalseUnwind { .. } => if feature_allowed(tcx, |f| f.const_loop) {
+            Ok(())
+        } else {
             Err((span, "loops are not allowed in const fn".into()))
         },
     }
@@ -20,11 +20,13 @@ pub mod patch;
 mod alignment;
 mod graphviz;
 pub(crate) mod pretty;
+pub mod parse;
 pub mod liveness;
 pub mod collect_writes;
 
 pub use self::alignment::is_disaligned;
 pub use self::pretty::{dump_enabled, dump_mir, write_mir_pretty, PassWhere};
+pub use self::parse::{mir_from_file_attr, parse_mir};
 pub use self::graphviz::{write_mir_graphviz};
 pub use self::graphviz::write_node_label as write_graphviz_node_label;
 
@@ -0,0 +1,1368 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A parser for the textual MIR printed by `pretty.rs`, so that passes can be tested on
+//! hand-written MIR.
+//!
+//! A function marked with `#[rustc_mir(from_file = "foo.mir")]` takes its MIR from `foo.mir`
+//! (relative to the file containing the function) instead of building it from its body. The
+//! input is a single function in the format of `-Z dump-mir`; comments are ignored, except
+//! for the `// cleanup` marker after a basic block label.
+//!
+//! Only a subset of MIR is understood: locals of primitive, reference, raw pointer, tuple,
+//! array and slice type, and the statements and terminators operating on them. Functions
+//! can be called if they are non-generic functions of the current crate. Everything in the
+//! parsed MIR is in the outermost source scope and gets the span of the function.
+//!
+//! The user type annotations of `-Z dump-mir` output cannot be read back: the `as` ascriptions
+//! of variables are ignored, and `AscribeUserType` statements become `nop`s. Inline assembly
+//! and `SetDiscriminant` statements are not supported at all.
+//!
+//! Malformed input is reported as a `ParseError`. This includes MIR that would make later
+//! passes fall over, like dereferencing a place that is no pointer, or assigning a value to a
+//! place of another type, but the parsed MIR is not otherwise type checked before borrowck.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::mir::interpret::{truncate, EvalErrorKind};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::subst::Substs;
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use syntax::ast;
+use syntax_pos::{FileName, Span};
+
+use dataflow::has_rustc_mir_with;
+
+/// Returns the MIR of `def_id` read from the file named by its `#[rustc_mir(from_file)]`
+/// attribute, or `None` if there is no such attribute or the file could not be used, in which
+/// case an error has been reported.
+pub fn mir_from_file_attr<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+) -> Option<Mir<'tcx>> {
+    let attrs = tcx.get_attrs(def_id);
+    let item = has_rustc_mir_with(&attrs, "from_file")?;
+    let file_name = match item.value_str() {
+        Some(file_name) => file_name,
+        None => {
+            tcx.sess.span_err(item.span, "expected `from_file = \"...\"`");
+            return None;
+        }
+    };
+    if !tcx.type_of(def_id).is_fn() || tcx.generics_of(def_id).requires_monomorphization(tcx) {
+        tcx.sess.span_err(item.span, "MIR can only be read from a file for non-generic functions");
+        return None;
+    }
+    let span = tcx.def_span(def_id);
+    let path = match tcx.sess.source_map().span_to_filename(span) {
+        FileName::Real(path) => path.with_file_name(&*file_name.as_str()),
+        _ => {
+            tcx.sess.span_err(item.span, "cannot locate MIR file relative to this source file");
+            return None;
+        }
+    };
+    let src = match fs::read_to_string(&path) {
+        Ok(src) => src,
+        Err(err) => {
+            tcx.sess.span_err(item.span, &format!(
+                "couldn't read MIR from {}: {}", path.display(), err));
+            return None;
+        }
+    };
+    let lint_root = tcx.hir.as_local_node_id(def_id).unwrap();
+    let result = parse_mir(tcx, &src, span, lint_root).and_then(|mir| {
+        check_signature(tcx, def_id, &mir)?;
+        Ok(mir)
+    });
+    match result {
+        Ok(mir) => Some(mir),
+        Err(err) => {
+            tcx.sess.span_err(item.span, &format!(
+                "couldn't parse MIR from {}: {}", path.display(), err));
+            None
+        }
+    }
+}
+
+/// Parses the MIR of a single function. All statements and terminators get `span`, and lints
+/// are reported at `lint_root`.
+pub fn parse_mir<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    src: &str,
+    span: Span,
+    lint_root: ast::NodeId,
+) -> Result<Mir<'tcx>, ParseError> {
+    let mut parser = Parser {
+        tcx,
+        tokens: lex(src)?,
+        pos: 0,
+        span,
+        lint_root,
+        local_decls: IndexVec::new(),
+    };
+    parser.parse_mir()
+}
+
+/// Makes sure the parsed MIR fits the signature of the function it replaces.
+fn check_signature<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+    mir: &Mir<'tcx>,
+) -> Result<(), ParseError> {
+    let sig = tcx.fn_sig(def_id);
+    let sig = tcx.erase_regions(&tcx.erase_late_bound_regions(&sig));
+    let mismatch = |what: String, expected: Ty<'tcx>, found: Ty<'tcx>| Err(ParseError {
+        line: 1,
+        msg: format!("{} has type `{}`, but the function expects `{}`", what, found, expected),
+    });
+    if mir.arg_count != sig.inputs().len() {
+        return Err(ParseError {
+            line: 1,
+            msg: format!("MIR takes {} arguments, but the function takes {}",
+                         mir.arg_count, sig.inputs().len()),
+        });
+    }
+    for (arg, &expected) in mir.args_iter().zip(sig.inputs()) {
+        let found = tcx.erase_regions(&mir.local_decls[arg].ty);
+        if found != expected {
+            return mismatch(format!("argument `{:?}`", arg), expected, found);
+        }
+    }
+    let found = tcx.erase_regions(&mir.return_ty());
+    if found != sig.output() {
+        return mismatch("the return place".to_string(), sig.output(), found);
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct ParseError {
+    /// The line of the input the error was found on, starting at 1.
+    pub line: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+type PResult<T> = Result<T, ParseError>;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// Identifiers and keywords, including locals (`_1`) and blocks (`bb0`).
+    Ident(String),
+    /// An integer literal together with its suffix, e.g. `5i32`.
+    Int(u128, String),
+    Str(String),
+    Char(char),
+    /// Lifetimes are irrelevant for MIR after type checking, and are skipped by the parser.
+    Lifetime,
+    Punct(&'static str),
+    /// Any other character, which the parser only accepts in the parts of the input it skips.
+    Other(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Token::Ident(ref ident) => write!(f, "`{}`", ident),
+            Token::Int(value, ref suffix) => write!(f, "`{}{}`", value, suffix),
+            Token::Str(ref s) => write!(f, "{:?}", s),
+            Token::Char(c) => write!(f, "{:?}", c),
+            Token::Lifetime => write!(f, "lifetime"),
+            Token::Punct(p) => write!(f, "`{}`", p),
+            Token::Other(c) => write!(f, "`{}`", c),
+        }
+    }
+}
+
+const PUNCTUATION: &[&str] = &[
+    "->", "::", "<-", "(", ")", "{", "}", "[", "]", ",", ";", ":", "=", "&", "*", "!", "-", ".",
+];
+
+/// Splits `src` into tokens, each with the line it was found on.
+fn lex(src: &str) -> PResult<Vec<(Token, usize)>> {
+    let mut tokens = vec![];
+    for (index, line) in src.lines().enumerate() {
+        let line_number = index + 1;
+        let err = |msg: String| Err(ParseError { line: line_number, msg });
+        let mut chars = line.char_indices().peekable();
+        while let Some(&(start, c)) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if line[start..].starts_with("//") {
+                // The only comment with a meaning marks cleanup blocks.
+                if line[start + 2..].trim() == "cleanup" {
+                    tokens.push((Token::Ident("cleanup".to_string()), line_number));
+                }
+                break;
+            } else if c == '"' {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, 'n')) => s.push('\n'),
+                            Some((_, 't')) => s.push('\t'),
+                            Some((_, c)) => s.push(c),
+                            None => return err("unterminated string literal".to_string()),
+                        },
+                        Some((_, c)) => s.push(c),
+                        None => return err("unterminated string literal".to_string()),
+                    }
+                }
+                tokens.push((Token::Str(s), line_number));
+            } else if c == '\'' {
+                chars.next();
+                let mut text = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if c == '\'' || !(c.is_alphanumeric() || c == '_' || c == '\\') {
+                        break;
+                    }
+                    text.push(c);
+                    chars.next();
+                }
+                if let Some(&(_, '\'')) = chars.peek() {
+                    chars.next();
+                    let c = match &text[..] {
+                        "\\n" => '\n',
+                        "\\t" => '\t',
+                        "\\\\" => '\\',
+                        _ if text.chars().count() == 1 => text.chars().next().unwrap(),
+                        _ => return err(format!("unsupported character literal '{}'", text)),
+                    };
+                    tokens.push((Token::Char(c), line_number));
+                } else {
+                    tokens.push((Token::Lifetime, line_number));
+                }
+            } else if c.is_ascii_digit() {
+                let mut value: u128 = 0;
+                let mut suffix = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if let (true, Some(digit)) = (suffix.is_empty(), c.to_digit(10)) {
+                        value = match value.checked_mul(10).and_then(|v| {
+                            v.checked_add(digit as u128)
+                        }) {
+                            Some(value) => value,
+                            None => return err("integer literal is too large".to_string()),
+                        };
+                    } else if c.is_alphanumeric() {
+                        suffix.push(c);
+                    } else if c != '_' {
+                        break;
+                    }
+                    chars.next();
+                }
+                tokens.push((Token::Int(value, suffix), line_number));
+            } else if c.is_alphabetic() || c == '_' {
+                let mut ident = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push((Token::Ident(ident), line_number));
+            } else {
+                match PUNCTUATION.iter().find(|p| line[start..].starts_with(**p)) {
+                    Some(punct) => {
+                        for _ in 0..punct.len() {
+                            chars.next();
+                        }
+                        tokens.push((Token::Punct(punct), line_number));
+                    }
+                    None => {
+                        chars.next();
+                        tokens.push((Token::Other(c), line_number));
+                    }
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    span: Span,
+    lint_root: ast::NodeId,
+    local_decls: IndexVec<Local, LocalDecl<'tcx>>,
+}
+
+impl<'a, 'tcx> Parser<'a, 'tcx> {
+    fn err<T>(&self, msg: String) -> PResult<T> {
+        let line = self.tokens.get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |&(_, line)| line);
+        Err(ParseError { line, msg })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|&(ref token, _)| token)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset).map(|&(ref token, _)| token)
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> PResult<T> {
+        match self.peek() {
+            Some(token) => self.err(format!("expected {}, found {}", expected, token)),
+            None => self.err(format!("expected {}, found end of input", expected)),
+        }
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        match self.peek() {
+            Some(&Token::Punct(p)) if p == punct => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> PResult<()> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", punct))
+        }
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        match self.peek() {
+            Some(&Token::Ident(ref i)) if i == ident => {}
+            _ => return false,
+        }
+        self.pos += 1;
+        true
+    }
+
+    fn expect_ident(&mut self, ident: &str) -> PResult<()> {
+        if self.eat_ident(ident) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", ident))
+        }
+    }
+
+    fn ident(&mut self) -> PResult<String> {
+        match self.peek() {
+            Some(&Token::Ident(ref ident)) => {
+                let ident = ident.clone();
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => self.unexpected("an identifier"),
+        }
+    }
+
+    fn int(&mut self) -> PResult<(u128, String)> {
+        match self.peek() {
+            Some(&Token::Int(value, ref suffix)) => {
+                let suffix = suffix.clone();
+                self.pos += 1;
+                Ok((value, suffix))
+            }
+            _ => self.unexpected("an integer"),
+        }
+    }
+
+    /// A path like `foo::bar`, as printed for functions.
+    fn path(&mut self) -> PResult<String> {
+        let mut path = self.ident()?;
+        while self.eat_punct("::") {
+            path.push_str("::");
+            path.push_str(&self.ident()?);
+        }
+        Ok(path)
+    }
+
+    /// Skips tokens up to the first of `ends` that is not nested in brackets, which is not
+    /// skipped itself.
+    fn skip_until(&mut self, ends: &[&str]) -> PResult<()> {
+        let mut depth = 0;
+        loop {
+            match self.peek() {
+                Some(&Token::Punct(p)) if depth == 0 && ends.contains(&p) => return Ok(()),
+                Some(&Token::Ident(ref i)) if depth == 0 && ends.contains(&&i[..]) => {
+                    return Ok(())
+                }
+                Some(&Token::Punct("(")) | Some(&Token::Punct("[")) | Some(&Token::Punct("{")) => {
+                    depth += 1
+                }
+                Some(&Token::Punct(")")) | Some(&Token::Punct("]")) | Some(&Token::Punct("}")) => {
+                    if depth == 0 {
+                        return self.unexpected(&format!("`{}`", ends.join("` or `")));
+                    }
+                    depth -= 1
+                }
+                Some(_) => {}
+                None => return self.unexpected(&format!("`{}`", ends.join("` or `"))),
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Runs `f` on the tokens of `src` instead of the tokens of the input, for the operands
+    /// that are printed inside of assertion messages.
+    fn parse_nested<T>(
+        &mut self,
+        src: &str,
+        f: impl FnOnce(&mut Self) -> PResult<T>,
+    ) -> PResult<T> {
+        let line = self.tokens[self.pos - 1].1;
+        let tokens = lex(src)?.into_iter().map(|(token, _)| (token, line)).collect();
+        let outer_tokens = ::std::mem::replace(&mut self.tokens, tokens);
+        let outer_pos = ::std::mem::replace(&mut self.pos, 0);
+        let result = f(self).and_then(|value| {
+            if self.pos == self.tokens.len() {
+                Ok(value)
+            } else {
+                self.unexpected("end of operand")
+            }
+        });
+        self.tokens = outer_tokens;
+        self.pos = outer_pos;
+        result
+    }
+
+    fn source_info(&self) -> SourceInfo {
+        SourceInfo { span: self.span, scope: OUTERMOST_SOURCE_SCOPE }
+    }
+
+    fn parse_mir(&mut self) -> PResult<Mir<'tcx>> {
+        self.expect_ident("fn")?;
+        self.path()?;
+
+        let mut decls = vec![];
+        self.expect_punct("(")?;
+        while !self.eat_punct(")") {
+            let local = self.parse_local()?;
+            self.expect_punct(":")?;
+            let ty = self.parse_ty()?;
+            decls.push((local, ty, Mutability::Not));
+            if !self.eat_punct(",") {
+                self.expect_punct(")")?;
+                break;
+            }
+        }
+        let arg_count = decls.len();
+        for (index, &(local, ..)) in decls.iter().enumerate() {
+            if local.index() != index + 1 {
+                return self.err(format!("argument {} must be `_{}`", index + 1, index + 1));
+            }
+        }
+        self.expect_punct("->")?;
+        let return_ty = self.parse_ty()?;
+        decls.push((RETURN_PLACE, return_ty, Mutability::Mut));
+
+        self.expect_punct("{")?;
+        self.parse_decls(&mut decls)?;
+        self.local_decls = self.build_local_decls(decls)?;
+
+        let mut basic_blocks = IndexVec::new();
+        while !self.eat_punct("}") {
+            let (block, data) = self.parse_block()?;
+            if block != basic_blocks.next_index() {
+                return self.err(format!("expected `{:?}`, found `{:?}`",
+                                        basic_blocks.next_index(), block));
+            }
+            basic_blocks.push(data);
+        }
+        if self.pos != self.tokens.len() {
+            return self.unexpected("end of input");
+        }
+        for data in basic_blocks.iter() {
+            for &target in data.terminator().successors() {
+                if target.index() >= basic_blocks.len() {
+                    return Err(ParseError {
+                        line: 1,
+                        msg: format!("jump to missing block `{:?}`", target),
+                    });
+                }
+            }
+        }
+
+        let mut source_scopes = IndexVec::new();
+        source_scopes.push(SourceScopeData { span: self.span, parent_scope: None });
+        let mut source_scope_local_data = IndexVec::new();
+        source_scope_local_data.push(SourceScopeLocalData {
+            lint_root: self.lint_root,
+            safety: Safety::Safe,
+        });
+        Ok(Mir::new(
+            basic_blocks,
+            source_scopes,
+            ClearCrossCrate::Set(source_scope_local_data),
+            IndexVec::new(),
+            None,
+            ::std::mem::replace(&mut self.local_decls, IndexVec::new()),
+            arg_count,
+            vec![],
+            self.span,
+        ))
+    }
+
+    /// Parses the `let` declarations of locals, which may be nested in `scope` blocks.
+    fn parse_decls(&mut self, decls: &mut Vec<(Local, Ty<'tcx>, Mutability)>) -> PResult<()> {
+        loop {
+            if self.eat_ident("let") {
+                let mutability = if self.eat_ident("mut") {
+                    Mutability::Mut
+                } else {
+                    Mutability::Not
+                };
+                let local = self.parse_local()?;
+                self.expect_punct(":")?;
+                let ty = self.parse_ty()?;
+                // The user type annotations of variables only matter to borrowck, and cannot
+                // be parsed.
+                while self.eat_ident("as") {
+                    self.skip_until(&["as", ";"])?;
+                }
+                self.expect_punct(";")?;
+                if local == RETURN_PLACE {
+                    if ty != decls.last().unwrap().1 {
+                        return self.err("the return place must have the return type".to_string());
+                    }
+                    continue;
+                }
+                decls.push((local, ty, mutability));
+            } else if self.eat_ident("scope") {
+                self.int()?;
+                self.expect_punct("{")?;
+                self.parse_decls(decls)?;
+                self.expect_punct("}")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn build_local_decls(
+        &self,
+        mut decls: Vec<(Local, Ty<'tcx>, Mutability)>,
+    ) -> PResult<IndexVec<Local, LocalDecl<'tcx>>> {
+        decls.sort_by_key(|&(local, ..)| local);
+        let mut local_decls = IndexVec::new();
+        for (local, ty, mutability) in decls {
+            if local != local_decls.next_index() {
+                let msg = if local < local_decls.next_index() {
+                    format!("`{:?}` is declared twice", local)
+                } else {
+                    format!("`{:?}` is not declared", local_decls.next_index())
+                };
+                return Err(ParseError { line: 1, msg });
+            }
+            let decl = LocalDecl::new_temp(ty, self.span);
+            local_decls.push(match mutability {
+                Mutability::Mut => decl,
+                Mutability::Not => decl.immutable(),
+            });
+        }
+        Ok(local_decls)
+    }
+
+    fn parse_local(&mut self) -> PResult<Local> {
+        if let Some(&Token::Ident(ref ident)) = self.peek() {
+            if ident.starts_with('_') {
+                if let Ok(index) = ident[1..].parse::<u32>() {
+                    if index > Local::MAX_AS_U32 {
+                        return self.err(format!("`{}` is out of range", ident));
+                    }
+                    self.pos += 1;
+                    return Ok(Local::new(index as usize));
+                }
+            }
+        }
+        self.unexpected("a local")
+    }
+
+    /// Parses a local used in the body, which has to be declared.
+    fn parse_declared_local(&mut self) -> PResult<Local> {
+        let local = self.parse_local()?;
+        if local.index() >= self.local_decls.len() {
+            self.pos -= 1;
+            return self.err(format!("`{:?}` is not declared", local));
+        }
+        Ok(local)
+    }
+
+    fn parse_block_name(&mut self) -> PResult<BasicBlock> {
+        if let Some(&Token::Ident(ref ident)) = self.peek() {
+            if ident.starts_with("bb") {
+                if let Ok(index) = ident[2..].parse::<u32>() {
+                    if index > BasicBlock::MAX_AS_U32 {
+                        return self.err(format!("`{}` is out of range", ident));
+                    }
+                    self.pos += 1;
+                    return Ok(BasicBlock::new(index as usize));
+                }
+            }
+        }
+        self.unexpected("a basic block")
+    }
+
+    fn parse_ty(&mut self) -> PResult<Ty<'tcx>> {
+        let tcx = self.tcx;
+        if self.eat_punct("(") {
+            let mut tys = vec![];
+            while !self.eat_punct(")") {
+                tys.push(self.parse_ty()?);
+                if !self.eat_punct(",") {
+                    self.expect_punct(")")?;
+                    break;
+                }
+            }
+            return Ok(tcx.mk_tup(tys.into_iter()));
+        }
+        if self.eat_punct("!") {
+            return Ok(tcx.types.never);
+        }
+        if self.eat_punct("&") {
+            if let Some(&Token::Lifetime) = self.peek() {
+                self.pos += 1;
+            }
+            let mutbl = if self.eat_ident("mut") {
+                ::rustc::hir::MutMutable
+            } else {
+                ::rustc::hir::MutImmutable
+            };
+            let ty = self.parse_ty()?;
+            return Ok(tcx.mk_ref(tcx.types.re_erased, ty::TypeAndMut { ty, mutbl }));
+        }
+        if self.eat_punct("*") {
+            let mutable = if self.eat_ident("mut") {
+                true
+            } else {
+                self.expect_ident("const")?;
+                false
+            };
+            let ty = self.parse_ty()?;
+            return Ok(if mutable { tcx.mk_mut_ptr(ty) } else { tcx.mk_imm_ptr(ty) });
+        }
+        if self.eat_punct("[") {
+            let ty = self.parse_ty()?;
+            let ty = if self.eat_punct(";") {
+                let (len, _) = self.int()?;
+                tcx.mk_array(ty, len as u64)
+            } else {
+                tcx.mk_slice(ty)
+            };
+            self.expect_punct("]")?;
+            return Ok(ty);
+        }
+        let name = self.ident()?;
+        match primitive_ty(tcx, &name) {
+            Some(ty) => Ok(ty),
+            None => {
+                self.pos -= 1;
+                self.err(format!("unsupported type `{}`", name))
+            }
+        }
+    }
+
+    fn parse_block(&mut self) -> PResult<(BasicBlock, BasicBlockData<'tcx>)> {
+        let block = self.parse_block_name()?;
+        self.expect_punct(":")?;
+        self.expect_punct("{")?;
+        let is_cleanup = self.eat_ident("cleanup");
+        let mut statements = vec![];
+        loop {
+            if self.is_last_in_block()? {
+                let kind = self.parse_terminator()?;
+                self.expect_punct(";")?;
+                self.expect_punct("}")?;
+                let terminator = Terminator { source_info: self.source_info(), kind };
+                return Ok((block, BasicBlockData {
+                    statements,
+                    terminator: Some(terminator),
+                    is_cleanup,
+                }));
+            }
+            let kind = self.parse_statement()?;
+            self.expect_punct(";")?;
+            statements.push(Statement { source_info: self.source_info(), kind });
+        }
+    }
+
+    /// Whether the statement or terminator starting at the current token is the last one
+    /// in its basic block, i.e. the terminator.
+    fn is_last_in_block(&self) -> PResult<bool> {
+        let mut depth = 0;
+        for offset in 0.. {
+            match self.peek_at(offset) {
+                Some(&Token::Punct("(")) | Some(&Token::Punct("[")) => depth += 1,
+                Some(&Token::Punct(")")) | Some(&Token::Punct("]")) => depth -= 1,
+                Some(&Token::Punct(";")) if depth == 0 => {
+                    return Ok(self.peek_at(offset + 1) == Some(&Token::Punct("}")));
+                }
+                Some(&Token::Punct("}")) if depth == 0 => break,
+                Some(_) => {}
+                None => break,
+            }
+        }
+        self.unexpected("a statement or terminator ending in `;`")
+    }
+
+    fn parse_statement(&mut self) -> PResult<StatementKind<'tcx>> {
+        if self.eat_ident("nop") {
+            return Ok(StatementKind::Nop);
+        }
+        if self.peek() == Some(&Token::Ident("asm".to_string())) &&
+            self.peek_at(1) == Some(&Token::Punct("!"))
+        {
+            return self.err("inline assembly is not supported".to_string());
+        }
+        if self.peek_at(1) == Some(&Token::Punct("(")) {
+            let keyword = match self.peek() {
+                Some(&Token::Ident(ref ident)) => ident.clone(),
+                _ => String::new(),
+            };
+            match &keyword[..] {
+                "StorageLive" | "StorageDead" => {
+                    self.pos += 2;
+                    let local = self.parse_declared_local()?;
+                    self.expect_punct(")")?;
+                    return Ok(if keyword == "StorageLive" {
+                        StatementKind::StorageLive(local)
+                    } else {
+                        StatementKind::StorageDead(local)
+                    });
+                }
+                "FakeRead" => {
+                    self.pos += 2;
+                    let cause = match &self.ident()?[..] {
+                        "ForMatchGuard" => FakeReadCause::ForMatchGuard,
+                        "ForMatchedPlace" => FakeReadCause::ForMatchedPlace,
+                        "ForLet" => FakeReadCause::ForLet,
+                        cause => {
+                            self.pos -= 1;
+                            return self.err(format!("unknown cause of a fake read `{}`", cause));
+                        }
+                    };
+                    self.expect_punct(",")?;
+                    let place = self.parse_place()?;
+                    self.expect_punct(")")?;
+                    return Ok(StatementKind::FakeRead(cause, place));
+                }
+                "Retag" => {
+                    self.pos += 2;
+                    let fn_entry = self.eat_punct("[");
+                    if fn_entry {
+                        self.expect_ident("fn")?;
+                        self.expect_ident("entry")?;
+                        self.expect_punct("]")?;
+                    }
+                    let place = self.parse_place()?;
+                    self.expect_punct(")")?;
+                    return Ok(StatementKind::Retag { fn_entry, place });
+                }
+                "EscapeToRaw" => {
+                    self.pos += 2;
+                    let operand = self.parse_operand()?;
+                    self.expect_punct(")")?;
+                    return Ok(StatementKind::EscapeToRaw(operand));
+                }
+                "AscribeUserType" => {
+                    // The user type cannot be parsed, and only matters to borrowck.
+                    self.pos += 2;
+                    self.parse_place()?;
+                    self.skip_until(&[")"])?;
+                    self.expect_punct(")")?;
+                    return Ok(StatementKind::Nop);
+                }
+                "discriminant" if self.is_set_discriminant() => {
+                    // Only enums have variants, and there is no syntax for enum types.
+                    return self.err("setting the discriminant is not supported".to_string());
+                }
+                _ => {}
+            }
+        }
+        let place = self.parse_place()?;
+        self.expect_punct("=")?;
+        let rvalue = self.parse_rvalue(&place)?;
+        self.check_assign(&place, &rvalue)?;
+        Ok(StatementKind::Assign(place, box rvalue))
+    }
+
+    /// Whether the statement at the current token is `discriminant(place) = variant`, rather
+    /// than an assignment of `discriminant(place)` to a place.
+    fn is_set_discriminant(&self) -> bool {
+        let mut depth = 0;
+        for offset in 1.. {
+            match self.peek_at(offset) {
+                Some(&Token::Punct("(")) => depth += 1,
+                Some(&Token::Punct(")")) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.peek_at(offset + 1) == Some(&Token::Punct("="));
+                    }
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+        false
+    }
+
+    /// Makes sure that `rvalue` has the type of the place it is assigned to.
+    fn check_assign(&self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>) -> PResult<()> {
+        let place_ty = self.place_ty(place);
+        let rvalue_ty = rvalue.ty(&self.local_decls, self.tcx);
+        if place_ty != rvalue_ty {
+            return self.err(format!(
+                "`{:?}` has type `{}`, but is assigned a value of type `{}`",
+                place, place_ty, rvalue_ty));
+        }
+        Ok(())
+    }
+
+    fn place_ty(&self, place: &Place<'tcx>) -> Ty<'tcx> {
+        place.ty(&self.local_decls, self.tcx).to_ty(self.tcx)
+    }
+
+    fn parse_place(&mut self) -> PResult<Place<'tcx>> {
+        let mut place = if self.eat_punct("(") {
+            let place = if self.eat_punct("*") {
+                let base = self.parse_place()?;
+                let base_ty = self.place_ty(&base);
+                if base_ty.builtin_deref(true).is_none() {
+                    return self.err(format!(
+                        "cannot dereference `{:?}` of type `{}`", base, base_ty));
+                }
+                base.deref()
+            } else {
+                let base = self.parse_place()?;
+                self.expect_punct(".")?;
+                let (field, _) = self.int()?;
+                self.expect_punct(":")?;
+                let ty = self.parse_ty()?;
+                let base_ty = self.place_ty(&base);
+                match base_ty.sty {
+                    ty::Tuple(tys) if field < tys.len() as u128 && tys[field as usize] == ty => {}
+                    _ => return self.err(format!(
+                        "`{:?}` of type `{}` has no field {} of type `{}`",
+                        base, base_ty, field, ty)),
+                }
+                base.field(Field::new(field as usize), ty)
+            };
+            self.expect_punct(")")?;
+            place
+        } else {
+            Place::Local(self.parse_declared_local()?)
+        };
+        while self.eat_punct("[") {
+            let index = self.parse_declared_local()?;
+            let index_ty = self.local_decls[index].ty;
+            if index_ty != self.tcx.types.usize {
+                self.pos -= 1;
+                return self.err(format!(
+                    "index `{:?}` has type `{}`, but indices are `usize`", index, index_ty));
+            }
+            let place_ty = self.place_ty(&place);
+            match place_ty.sty {
+                ty::Array(..) | ty::Slice(_) => {}
+                _ => return self.err(format!("cannot index `{:?}` of type `{}`", place, place_ty)),
+            }
+            self.expect_punct("]")?;
+            place = place.index(index);
+        }
+        Ok(place)
+    }
+
+    fn parse_operand(&mut self) -> PResult<Operand<'tcx>> {
+        if self.eat_ident("move") {
+            return Ok(Operand::Move(self.parse_place()?));
+        }
+        if self.eat_ident("const") {
+            return self.parse_constant();
+        }
+        Ok(Operand::Copy(self.parse_place()?))
+    }
+
+    fn parse_constant(&mut self) -> PResult<Operand<'tcx>> {
+        let tcx = self.tcx;
+        let negative = self.eat_punct("-");
+        let (bits, ty) = match self.peek().cloned() {
+            Some(Token::Int(value, suffix)) => {
+                let ty = match primitive_ty(tcx, &suffix) {
+                    Some(ty) if ty.is_integral() => ty,
+                    _ => return self.err(format!("unsupported integer suffix `{}`", suffix)),
+                };
+                self.pos += 1;
+                let value = if negative { (value as i128).wrapping_neg() as u128 } else { value };
+                (value, ty)
+            }
+            Some(Token::Char(c)) if !negative => {
+                self.pos += 1;
+                (c as u128, tcx.types.char)
+            }
+            Some(Token::Ident(ref ident)) if !negative && (ident == "true" || ident == "false") => {
+                self.pos += 1;
+                ((ident == "true") as u128, tcx.types.bool)
+            }
+            Some(Token::Ident(_)) if !negative => {
+                let path = self.path()?;
+                let def_id = match self.find_fn(&path) {
+                    Some(def_id) => def_id,
+                    None => {
+                        self.pos -= 1;
+                        return self.err(format!(
+                            "`{}` is not a non-generic function of this crate", path));
+                    }
+                };
+                let substs = Substs::identity_for_item(tcx, def_id);
+                return Ok(Operand::function_handle(tcx, def_id, substs, self.span));
+            }
+            _ => return self.unexpected("a constant"),
+        };
+        let size = tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)).unwrap().size;
+        Ok(Operand::Constant(box Constant {
+            span: self.span,
+            ty,
+            user_ty: None,
+            literal: ty::Const::from_bits(
+                tcx,
+                truncate(bits, size),
+                ty::ParamEnv::reveal_all().and(ty),
+            ),
+        }))
+    }
+
+    fn find_fn(&self, path: &str) -> Option<DefId> {
+        self.tcx.body_owners().find(|&def_id| {
+            self.tcx.item_path_str(def_id) == path &&
+                self.tcx.type_of(def_id).is_fn() &&
+                !self.tcx.generics_of(def_id).requires_monomorphization(self.tcx)
+        })
+    }
+
+    fn parse_operands(&mut self, close: &str) -> PResult<Vec<Operand<'tcx>>> {
+        let mut operands = vec![];
+        while !self.eat_punct(close) {
+            operands.push(self.parse_operand()?);
+            if !self.eat_punct(",") {
+                self.expect_punct(close)?;
+                break;
+            }
+        }
+        Ok(operands)
+    }
+
+    fn parse_rvalue(&mut self, dest: &Place<'tcx>) -> PResult<Rvalue<'tcx>> {
+        let tcx = self.tcx;
+        if self.eat_punct("&") {
+            let kind = if self.eat_ident("mut") {
+                BorrowKind::Mut { allow_two_phase_borrow: false }
+            } else {
+                BorrowKind::Shared
+            };
+            return Ok(Rvalue::Ref(tcx.types.re_erased, kind, self.parse_place()?));
+        }
+        if self.eat_punct("[") {
+            let elem_ty = match self.place_ty(dest).sty {
+                ty::Array(elem_ty, _) => elem_ty,
+                _ => return self.err("array assigned to a place that is no array".to_string()),
+            };
+            let operands = self.parse_operands("]")?;
+            return Ok(Rvalue::Aggregate(box AggregateKind::Array(elem_ty), operands));
+        }
+        if let Some(&Token::Punct("(")) = self.peek() {
+            // Both tuples and places with projections start with a parenthesis.
+            let start = self.pos;
+            if self.parse_place().is_err() {
+                self.pos = start + 1;
+                let operands = self.parse_operands(")")?;
+                return Ok(Rvalue::Aggregate(box AggregateKind::Tuple, operands));
+            }
+            self.pos = start;
+        }
+        if let (Some(&Token::Ident(ref name)), Some(&Token::Punct("("))) =
+            (self.peek(), self.peek_at(1))
+        {
+            let name = name.clone();
+            if name == "Len" || name == "discriminant" {
+                self.pos += 2;
+                let place = self.parse_place()?;
+                self.expect_punct(")")?;
+                let place_ty = self.place_ty(&place);
+                match place_ty.sty {
+                    ty::Array(..) | ty::Slice(_) => {}
+                    _ if name == "discriminant" => {}
+                    _ => return self.err(format!(
+                        "cannot take the length of `{:?}` of type `{}`", place, place_ty)),
+                }
+                return Ok(if name == "Len" {
+                    Rvalue::Len(place)
+                } else {
+                    Rvalue::Discriminant(place)
+                });
+            }
+            let unary = match &name[..] {
+                "Not" => Some(UnOp::Not),
+                "Neg" => Some(UnOp::Neg),
+                _ => None,
+            };
+            if let Some(op) = unary {
+                self.pos += 2;
+                let operand = self.parse_operand()?;
+                self.expect_punct(")")?;
+                return Ok(Rvalue::UnaryOp(op, operand));
+            }
+            let (checked, op_name) = if name.starts_with("Checked") {
+                (true, &name["Checked".len()..])
+            } else {
+                (false, &name[..])
+            };
+            if let Some(op) = bin_op(op_name) {
+                self.pos += 2;
+                let left = self.parse_operand()?;
+                self.expect_punct(",")?;
+                let right = self.parse_operand()?;
+                self.expect_punct(")")?;
+                let left_ty = left.ty(&self.local_decls, tcx);
+                let right_ty = right.ty(&self.local_decls, tcx);
+                let same_ty = match op {
+                    BinOp::Shl | BinOp::Shr | BinOp::Offset => true,
+                    _ => left_ty == right_ty,
+                };
+                if !same_ty {
+                    return self.err(format!(
+                        "`{}` of values of different types `{}` and `{}`",
+                        name, left_ty, right_ty));
+                }
+                return Ok(if checked {
+                    Rvalue::CheckedBinaryOp(op, left, right)
+                } else {
+                    Rvalue::BinaryOp(op, left, right)
+                });
+            }
+            return self.err(format!("unsupported rvalue `{}`", name));
+        }
+        let operand = self.parse_operand()?;
+        if self.eat_ident("as") {
+            let ty = self.parse_ty()?;
+            self.expect_punct("(")?;
+            let kind = match &self.ident()?[..] {
+                "Misc" => CastKind::Misc,
+                "ReifyFnPointer" => CastKind::ReifyFnPointer,
+                "ClosureFnPointer" => CastKind::ClosureFnPointer,
+                "UnsafeFnPointer" => CastKind::UnsafeFnPointer,
+                "Unsize" => CastKind::Unsize,
+                kind => {
+                    self.pos -= 1;
+                    return self.err(format!("unknown cast kind `{}`", kind));
+                }
+            };
+            self.expect_punct(")")?;
+            return Ok(Rvalue::Cast(kind, operand, ty));
+        }
+        Ok(Rvalue::Use(operand))
+    }
+
+    /// Parses the successors of a terminator, which are printed either as `-> bb1`, or as
+    /// `-> [label: bb1, label: bb2]`. The labels are implied by the terminator, and only the
+    /// blocks are returned, in order.
+    fn parse_successors(&mut self) -> PResult<Vec<BasicBlock>> {
+        if !self.eat_punct("->") {
+            return Ok(vec![]);
+        }
+        if !self.eat_punct("[") {
+            return Ok(vec![self.parse_block_name()?]);
+        }
+        let mut blocks = vec![];
+        while !self.eat_punct("]") {
+            self.ident()?;
+            self.expect_punct(":")?;
+            blocks.push(self.parse_block_name()?);
+            if !self.eat_punct(",") {
+                self.expect_punct("]")?;
+                break;
+            }
+        }
+        Ok(blocks)
+    }
+
+    fn parse_terminator(&mut self) -> PResult<TerminatorKind<'tcx>> {
+        let start = self.pos;
+        let keyword = match self.peek() {
+            Some(&Token::Ident(ref ident)) => ident.clone(),
+            _ => String::new(),
+        };
+        if self.peek_at(1) != Some(&Token::Punct("=")) {
+            self.pos += 1;
+        }
+        let kind = match &keyword[..] {
+            "return" => TerminatorKind::Return,
+            "resume" => TerminatorKind::Resume,
+            "abort" => TerminatorKind::Abort,
+            "unreachable" => TerminatorKind::Unreachable,
+            "goto" => {
+                let successors = self.parse_successors()?;
+                match successors[..] {
+                    [target] => TerminatorKind::Goto { target },
+                    _ => return self.err("`goto` needs exactly one target".to_string()),
+                }
+            }
+            "switchInt" => {
+                self.expect_punct("(")?;
+                let discr = self.parse_operand()?;
+                self.expect_punct(")")?;
+                let switch_ty = discr.ty(&self.local_decls, self.tcx);
+                if !(switch_ty.is_integral() || switch_ty.is_bool() || switch_ty.is_char()) {
+                    return self.err(format!("cannot switch on a value of type `{}`", switch_ty));
+                }
+                let (values, targets) = self.parse_switch_targets(switch_ty)?;
+                TerminatorKind::SwitchInt {
+                    discr,
+                    switch_ty,
+                    values: Cow::Owned(values),
+                    targets,
+                }
+            }
+            "drop" => {
+                self.expect_punct("(")?;
+                let location = self.parse_place()?;
+                self.expect_punct(")")?;
+                match self.parse_successors()?[..] {
+                    [target] => TerminatorKind::Drop { location, target, unwind: None },
+                    [target, unwind] => TerminatorKind::Drop {
+                        location,
+                        target,
+                        unwind: Some(unwind),
+                    },
+                    _ => return self.err("`drop` needs one or two targets".to_string()),
+                }
+            }
+            "assert" => {
+                self.expect_punct("(")?;
+                let expected = !self.eat_punct("!");
+                let cond = self.parse_operand()?;
+                let cond_ty = cond.ty(&self.local_decls, self.tcx);
+                if !cond_ty.is_bool() {
+                    return self.err(format!("cannot assert a value of type `{}`", cond_ty));
+                }
+                self.expect_punct(",")?;
+                let msg = match self.peek().cloned() {
+                    Some(Token::Str(msg)) => {
+                        self.pos += 1;
+                        self.parse_assert_message(&msg)?
+                    }
+                    _ => return self.unexpected("an assertion message"),
+                };
+                self.expect_punct(")")?;
+                let (target, cleanup) = match self.parse_successors()?[..] {
+                    [target] => (target, None),
+                    [target, cleanup] => (target, Some(cleanup)),
+                    _ => return self.err("`assert` needs one or two targets".to_string()),
+                };
+                TerminatorKind::Assert { cond, expected, msg, target, cleanup }
+            }
+            "falseEdges" => {
+                let successors = self.parse_successors()?;
+                match successors.split_first() {
+                    Some((&real_target, imaginary_targets)) => TerminatorKind::FalseEdges {
+                        real_target,
+                        imaginary_targets: imaginary_targets.to_vec(),
+                    },
+                    None => return self.err("`falseEdges` needs a real target".to_string()),
+                }
+            }
+            "falseUnwind" => match self.parse_successors()?[..] {
+                [real_target] => TerminatorKind::FalseUnwind { real_target, unwind: None },
+                [real_target, unwind] => TerminatorKind::FalseUnwind {
+                    real_target,
+                    unwind: Some(unwind),
+                },
+                _ => return self.err("`falseUnwind` needs one or two targets".to_string()),
+            },
+            _ => {
+                self.pos = start;
+                self.parse_call()?
+            }
+        };
+        Ok(kind)
+    }
+
+    fn parse_call(&mut self) -> PResult<TerminatorKind<'tcx>> {
+        let dest = if self.peek_at(1) == Some(&Token::Punct("=")) ||
+            self.peek() == Some(&Token::Punct("("))
+        {
+            let place = self.parse_place()?;
+            self.expect_punct("=")?;
+            Some(place)
+        } else {
+            None
+        };
+        let func = self.parse_operand()?;
+        let func_ty = func.ty(&self.local_decls, self.tcx);
+        if !func_ty.is_fn() {
+            return self.err("expected a terminator".to_string());
+        }
+        self.expect_punct("(")?;
+        let args = self.parse_operands(")")?;
+        let sig = func_ty.fn_sig(self.tcx);
+        let sig = self.tcx.erase_regions(&self.tcx.erase_late_bound_regions(&sig));
+        let arg_tys: Vec<_> = args.iter().map(|arg| arg.ty(&self.local_decls, self.tcx)).collect();
+        if arg_tys[..] != sig.inputs()[..] && !(sig.variadic && arg_tys.starts_with(sig.inputs()))
+        {
+            return self.err(format!(
+                "`{}` is called with arguments of the types ({}), but takes ({})",
+                func_ty, type_list(&arg_tys), type_list(sig.inputs())));
+        }
+        if let Some(ref dest) = dest {
+            let dest_ty = self.place_ty(dest);
+            if dest_ty != sig.output() {
+                return self.err(format!("`{:?}` has type `{}`, but `{}` returns `{}`",
+                                        dest, dest_ty, func_ty, sig.output()));
+            }
+        }
+        let successors = self.parse_successors()?;
+        let (destination, cleanup) = match (dest, &successors[..]) {
+            (Some(dest), &[target]) => (Some((dest, target)), None),
+            (Some(dest), &[target, cleanup]) => (Some((dest, target)), Some(cleanup)),
+            (None, &[]) => (None, None),
+            (None, &[cleanup]) => (None, Some(cleanup)),
+            _ => return self.err("wrong number of targets for a call".to_string()),
+        };
+        Ok(TerminatorKind::Call { func, args, destination, cleanup, from_hir_call: true })
+    }
+
+    fn parse_switch_targets(
+        &mut self,
+        switch_ty: Ty<'tcx>,
+    ) -> PResult<(Vec<u128>, Vec<BasicBlock>)> {
+        self.expect_punct("->")?;
+        self.expect_punct("[")?;
+        let mut values = vec![];
+        let mut targets = vec![];
+        loop {
+            if self.eat_ident("otherwise") {
+                self.expect_punct(":")?;
+                targets.push(self.parse_block_name()?);
+                self.expect_punct("]")?;
+                return Ok((values, targets));
+            }
+            let value = match self.parse_constant()? {
+                Operand::Constant(ref c) if c.ty == switch_ty => {
+                    c.literal.assert_bits(self.tcx, ty::ParamEnv::empty().and(switch_ty))
+                }
+                _ => None,
+            };
+            match value {
+                Some(value) => values.push(value),
+                None => return self.err(format!("expected a value of type `{}`", switch_ty)),
+            }
+            self.expect_punct(":")?;
+            targets.push(self.parse_block_name()?);
+            self.expect_punct(",")?;
+        }
+    }
+
+    fn parse_assert_message(&mut self, msg: &str) -> PResult<AssertMessage<'tcx>> {
+        const BOUNDS_CHECK: &str = "index out of bounds: the len is ";
+        const BOUNDS_CHECK_INDEX: &str = " but the index is ";
+        if msg.starts_with(BOUNDS_CHECK) {
+            if let Some(split) = msg.find(BOUNDS_CHECK_INDEX) {
+                let len = &msg[BOUNDS_CHECK.len()..split];
+                let index = &msg[split + BOUNDS_CHECK_INDEX.len()..];
+                let len = self.parse_nested(len, |this| this.parse_operand())?;
+                let index = self.parse_nested(index, |this| this.parse_operand())?;
+                return Ok(EvalErrorKind::BoundsCheck { len, index });
+            }
+        }
+        let candidates = vec![
+            EvalErrorKind::Overflow(BinOp::Add),
+            EvalErrorKind::Overflow(BinOp::Sub),
+            EvalErrorKind::Overflow(BinOp::Mul),
+            EvalErrorKind::Overflow(BinOp::Div),
+            EvalErrorKind::Overflow(BinOp::Rem),
+            EvalErrorKind::Overflow(BinOp::Shl),
+            EvalErrorKind::Overflow(BinOp::Shr),
+            EvalErrorKind::OverflowNeg,
+            EvalErrorKind::DivisionByZero,
+            EvalErrorKind::RemainderByZero,
+            EvalErrorKind::GeneratorResumedAfterReturn,
+            EvalErrorKind::GeneratorResumedAfterPanic,
+        ];
+        match candidates.into_iter().find(|candidate| candidate.description() == msg) {
+            Some(kind) => Ok(kind),
+            None => {
+                self.pos -= 1;
+                self.err(format!("unknown assertion message {:?}", msg))
+            }
+        }
+    }
+}
+
+fn type_list(tys: &[Ty<'_>]) -> String {
+    tys.iter().map(|ty| format!("`{}`", ty)).collect::<Vec<_>>().join(", ")
+}
+
+fn primitive_ty<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, name: &str) -> Option<Ty<'tcx>> {
+    let types = &tcx.types;
+    Some(match name {
+        "bool" => types.bool,
+        "char" => types.char,
+        "str" => types.str_,
+        "isize" => types.isize,
+        "i8" => types.i8,
+        "i16" => types.i16,
+        "i32" => types.i32,
+        "i64" => types.i64,
+        "i128" => types.i128,
+        "usize" => types.usize,
+        "u8" => types.u8,
+        "u16" => types.u16,
+        "u32" => types.u32,
+        "u64" => types.u64,
+        "u128" => types.u128,
+        "f32" => types.f32,
+        "f64" => types.f64,
+        _ => return None,
+    })
+}
+
+fn bin_op(name: &str) -> Option<BinOp> {
+    Some(match name {
+        "Add" => BinOp::Add,
+        "Sub" => BinOp::Sub,
+        "Mul" => BinOp::Mul,
+        "Div" => BinOp::Div,
+        "Rem" => BinOp::Rem,
+        "BitXor" => BinOp::BitXor,
+        "BitAnd" => BinOp::BitAnd,
+        "BitOr" => BinOp::BitOr,
+        "Shl" => BinOp::Shl,
+        "Shr" => BinOp::Shr,
+        "Eq" => BinOp::Eq,
+        "Lt" => BinOp::Lt,
+        "Le" => BinOp::Le,
+        "Ne" => BinOp::Ne,
+        "Ge" => BinOp::Ge,
+        "Gt" => BinOp::Gt,
+        "Offset" => BinOp::Offset,
+        _ => return None,
+    })
+}
@@ -398,6 +398,134 @@ impl<'a, 'gcx, 'tcx> CastCheck<'tcx> {
         err.emit();
     }
 
+    /// Lints numeric casts that can change the value being cast: casts to narrower integer
+    /// types, casts between signed and unsigned integers, and casts from floats to integers.
+    /// Integer casts that can't change the value are linted when `From` can do them instead.
+    ///
+    /// `usize` and `isize` count as anything from 16 to 64 bits wide, so that whether a cast
+    /// is linted doesn't depend on the target.
+    fn lossy_cast_lint(&self, fcx: &FnCtxt<'a, 'gcx, 'tcx>) {
+        // The narrowest and the widest the type is on any target, and whether it is signed.
+        let int_width = |ty: Ty<'tcx>| match ty.sty {
+            ty::Int(t) => Some((t.bit_width().map_or((16, 64), |w| (w, w)), true)),
+            ty::Uint(t) => Some((t.bit_width().map_or((16, 64), |w| (w, w)), false)),
+            // The widest `char` is `'\u{10FFFF}'`.
+            ty::Char => Some(((21, 21), false)),
+            _ => None,
+        };
+        let t_expr = fcx.ty_to_string(self.expr_ty);
+        let t_cast = fcx.ty_to_string(self.cast_ty);
+        match (int_width(self.expr_ty), int_width(self.cast_ty)) {
+            (Some(((expr_min, expr_max), expr_signed)), Some(((cast_min, _), cast_signed))) => {
+                let truncates = cast_min < expr_max;
+                if truncates {
+                    let mut err = fcx.tcx.struct_span_lint_node(
+                        lint::builtin::TRUNCATING_CASTS,
+                        self.expr.id,
+                        self.span,
+                        &format!("truncating cast: `{}` as `{}`", t_expr, t_cast));
+                    self.note_pointer_width(&mut err);
+                    self.suggest_try_from(fcx, &mut err);
+                    err.emit();
+                }
+                // Unsigned integers only change their value when cast to a signed type that
+                // is not wider.
+                let changes_sign = if expr_signed {
+                    !cast_signed
+                } else {
+                    cast_signed && cast_min <= expr_max
+                };
+                if changes_sign {
+                    let mut err = fcx.tcx.struct_span_lint_node(
+                        lint::builtin::SIGN_CHANGING_CASTS,
+                        self.expr.id,
+                        self.span,
+                        &format!("sign-changing cast: `{}` as `{}`", t_expr, t_cast));
+                    self.note_pointer_width(&mut err);
+                    self.suggest_try_from(fcx, &mut err);
+                    err.emit();
+                }
+                // The standard library has `From` impls for all the lossless conversions,
+                // except for those from `usize` and `isize`, and from `char` to anything but
+                // `u32`. Literals are better written with the suffix of the type instead.
+                let has_from_impl = expr_min == expr_max &&
+                    (!self.expr_ty.is_char() || self.cast_ty.sty == ty::Uint(ast::UintTy::U32));
+                let is_literal = if let hir::ExprKind::Lit(_) = self.expr.node {
+                    true
+                } else {
+                    false
+                };
+                if !truncates && !changes_sign && has_from_impl && !is_literal {
+                    let mut err = fcx.tcx.struct_span_lint_node(
+                        lint::builtin::LOSSLESS_CASTS,
+                        self.expr.id,
+                        self.span,
+                        &format!("lossless cast: `{}` as `{}`", t_expr, t_cast));
+                    if let Ok(snippet) = fcx.tcx.sess.source_map().span_to_snippet(self.expr.span) {
+                        err.span_suggestion_with_applicability(
+                            self.span,
+                            "use `From` to show that the value can't change",
+                            format!("{}::from({})", t_cast, snippet),
+                            Applicability::MachineApplicable,
+                        );
+                    }
+                    err.emit();
+                }
+            }
+            (None, Some(_)) if self.expr_ty.is_floating_point() => {
+                let mut err = fcx.tcx.struct_span_lint_node(
+                    lint::builtin::FLOAT_TO_INT_CASTS,
+                    self.expr.id,
+                    self.span,
+                    &format!("float-to-integer cast: `{}` as `{}`", t_expr, t_cast));
+                err.note(&format!("the value is rounded towards zero, and values that don't \
+                                   fit into `{}` are clamped to its range with \
+                                   `-Z saturating-float-casts`, and undefined behavior \
+                                   otherwise", t_cast));
+                err.emit();
+            }
+            _ => {}
+        }
+    }
+
+    /// Explains why a cast from or to `usize` or `isize` is linted even if it can't change the
+    /// value on the current target.
+    fn note_pointer_width(&self, err: &mut DiagnosticBuilder) {
+        let pointer_sized = [self.expr_ty, self.cast_ty].iter().find(|ty| match ty.sty {
+            ty::Int(ast::IntTy::Isize) | ty::Uint(ast::UintTy::Usize) => true,
+            _ => false,
+        }).cloned();
+        if let Some(ty) = pointer_sized {
+            err.note(&format!("`{}` is 16, 32 or 64 bits wide, depending on the target", ty));
+        }
+    }
+
+    /// Suggests replacing the cast with the `TryFrom` conversion, which reports values that
+    /// don't fit into the target type. The suggestion changes the type of the expression to a
+    /// `Result`, so it can't be applied as is.
+    fn suggest_try_from(&self, fcx: &FnCtxt<'a, 'gcx, 'tcx>, err: &mut DiagnosticBuilder) {
+        // There are no `TryFrom` impls for `char`.
+        if self.expr_ty.is_char() {
+            return;
+        }
+        if let Ok(snippet) = fcx.tcx.sess.source_map().span_to_snippet(self.expr.span) {
+            err.span_suggestion_with_applicability(
+                self.span,
+                "use `TryFrom` to handle values that don't fit",
+                format!("{}::try_from({})", fcx.ty_to_string(self.cast_ty), snippet),
+                Applicability::MaybeIncorrect,
+            );
+            err.note("`TryFrom` returns a `Result`, and needs `std::convert::TryFrom` in scope");
+            let features = fcx.tcx.features();
+            let try_from_enabled = features.declared_lib_features.iter().any(|&(name, _)| {
+                name == "try_from"
+            });
+            if !try_from_enabled {
+                err.note("`TryFrom` is unstable, and needs `#![feature(try_from)]`");
+            }
+        }
+    }
+
     pub fn check(mut self, fcx: &FnCtxt<'a, 'gcx, 'tcx>) {
         self.expr_ty = fcx.structurally_resolved_type(self.span, self.expr_ty);
         self.cast_ty = fcx.structurally_resolved_type(self.span, self.cast_ty);
@@ -420,6 +548,12 @@ impl<'a, 'gcx, 'tcx> CastCheck<'tcx> {
             match self.do_check(fcx) {
                 Ok(k) => {
                     debug!(" -> {:?}", k);
+                    match k {
+                        CastKind::NumericCast |
+                        CastKind::PrimIntCast |
+                        CastKind::U8CharCast => self.lossy_cast_lint(fcx),
+                        _ => {}
+                    }
                     fcx.tables.borrow_mut().cast_kinds_mut().insert(self.expr.hir_id, k);
                 }
                 Err(e) => self.report_cast_error(fcx, e),
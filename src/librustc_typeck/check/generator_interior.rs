@@ -16,18 +16,23 @@
 use rustc::hir::def_id::DefId;
 use rustc::hir::intravisit::{self, Visitor, NestedVisitorMap};
 use rustc::hir::{self, Pat, PatKind, Expr};
+use rustc::lint::builtin::MUST_NOT_SUSPEND;
 use rustc::middle::region;
 use rustc::ty::{self, Ty};
 use rustc_data_structures::sync::Lrc;
+use syntax::ast;
 use syntax_pos::Span;
+use syntax_pos::symbol::Symbol;
 use super::FnCtxt;
-use util::nodemap::FxHashMap;
+use util::nodemap::{FxHashMap, FxHashSet};
 
 struct InteriorVisitor<'a, 'gcx: 'a+'tcx, 'tcx: 'a> {
     fcx: &'a FnCtxt<'a, 'gcx, 'tcx>,
     types: FxHashMap<Ty<'tcx>, usize>,
     region_scope_tree: Lrc<region::ScopeTree>,
     expr_count: usize,
+    /// The spans already reported by the `must_not_suspend` lint.
+    must_not_suspend_spans: FxHashSet<Span>,
 }
 
 impl<'a, 'gcx, 'tcx> InteriorVisitor<'a, 'gcx, 'tcx> {
@@ -35,6 +40,7 @@ impl<'a, 'gcx, 'tcx> InteriorVisitor<'a, 'gcx, 'tcx> {
               ty: Ty<'tcx>,
               scope: Option<region::Scope>,
               expr: Option<&'tcx Expr>,
+              id: ast::NodeId,
               source_span: Span) {
         use syntax_pos::DUMMY_SP;
 
@@ -71,6 +77,11 @@ impl<'a, 'gcx, 'tcx> InteriorVisitor<'a, 'gcx, 'tcx> {
                               "the type is part of the generator because of this `yield`");
                 err.emit();
             } else {
+                // Without a scope, the type is only recorded to be conservative.
+                if scope.is_some() {
+                    self.check_must_not_suspend(ty, id, source_span, yield_span);
+                }
+
                 // Map the type to the number of types added before it
                 let entries = self.types.len();
                 self.types.entry(&ty).or_insert(entries);
@@ -80,6 +91,53 @@ impl<'a, 'gcx, 'tcx> InteriorVisitor<'a, 'gcx, 'tcx> {
                    expr, self.expr_count, expr.map(|e| e.span));
         }
     }
+
+    /// Warns about a value of type `ty`, which is live across the `yield` at `yield_span`,
+    /// if it is or contains a value of a `#[must_not_suspend]` type, like a lock guard.
+    fn check_must_not_suspend(&mut self,
+                              ty: Ty<'tcx>,
+                              id: ast::NodeId,
+                              source_span: Span,
+                              yield_span: Span) {
+        let tcx = self.fcx.tcx;
+        let (def_id, reason) = match find_must_not_suspend(tcx, ty) {
+            Some(found) => found,
+            None => return,
+        };
+        if !self.must_not_suspend_spans.insert(source_span) {
+            return;
+        }
+        let msg = format!("`{}` held across a `yield`", tcx.item_path_str(def_id));
+        let mut err = tcx.struct_span_lint_node(MUST_NOT_SUSPEND, id, source_span, &msg);
+        err.span_note(yield_span, "the value is still alive at this `yield`");
+        if let Some(reason) = reason {
+            err.note(&reason.as_str());
+        }
+        err.emit();
+    }
+}
+
+/// Returns the `#[must_not_suspend]` type in `ty` along with the reason given in its
+/// attribute. Tuples, arrays and type arguments are searched as well, as they own their
+/// contents.
+fn find_must_not_suspend<'a, 'gcx, 'tcx>(tcx: ty::TyCtxt<'a, 'gcx, 'tcx>,
+                                         ty: Ty<'tcx>)
+                                         -> Option<(DefId, Option<Symbol>)> {
+    match ty.sty {
+        ty::Adt(def, substs) => {
+            let attrs = tcx.get_attrs(def.did);
+            if let Some(attr) = attrs.iter().find(|attr| attr.check_name("must_not_suspend")) {
+                return Some((def.did, attr.value_str()));
+            }
+            if def.is_phantom_data() {
+                return None;
+            }
+            substs.types().filter_map(|ty| find_must_not_suspend(tcx, ty)).next()
+        }
+        ty::Tuple(tys) => tys.iter().filter_map(|&ty| find_must_not_suspend(tcx, ty)).next(),
+        ty::Array(ty, _) => find_must_not_suspend(tcx, ty),
+        _ => None,
+    }
 }
 
 pub fn resolve_interior<'a, 'gcx, 'tcx>(fcx: &'a FnCtxt<'a, 'gcx, 'tcx>,
@@ -92,6 +150,7 @@ pub fn resolve_interior<'a, 'gcx, 'tcx>(fcx: &'a FnCtxt<'a, 'gcx, 'tcx>,
         types: FxHashMap::default(),
         region_scope_tree: fcx.tcx.region_scope_tree(def_id),
         expr_count: 0,
+        must_not_suspend_spans: FxHashSet::default(),
     };
     intravisit::walk_body(&mut visitor, body);
 
@@ -156,7 +215,7 @@ impl<'a, 'gcx, 'tcx> Visitor<'tcx> for InteriorVisitor<'a, 'gcx, 'tcx> {
         if let PatKind::Binding(..) = pat.node {
             let scope = self.region_scope_tree.var_scope(pat.hir_id.local_id);
             let ty = self.fcx.tables.borrow().pat_ty(pat);
-            self.record(ty, Some(scope), None, pat.span);
+            self.record(ty, Some(scope), None, pat.id, pat.span);
         }
     }
 
@@ -169,11 +228,11 @@ impl<'a, 'gcx, 'tcx> Visitor<'tcx> for InteriorVisitor<'a, 'gcx, 'tcx> {
 
         // Record the unadjusted type
         let ty = self.fcx.tables.borrow().expr_ty(expr);
-        self.record(ty, scope, Some(expr), expr.span);
+        self.record(ty, scope, Some(expr), expr.id, expr.span);
 
         // Also include the adjusted types, since these can result in MIR locals
         for adjustment in self.fcx.tables.borrow().expr_adjustments(expr) {
-            self.record(adjustment.target, scope, Some(expr), expr.span);
+            self.record(adjustment.target, scope, Some(expr), expr.id, expr.span);
         }
     }
 }
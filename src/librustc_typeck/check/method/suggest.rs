@@ -21,10 +21,12 @@ use hir::def::Def;
 use hir::def_id::{CRATE_DEF_INDEX, DefId};
 use middle::lang_items::FnOnceTraitLangItem;
 use namespace::Namespace;
+use rustc::middle::cstore::ExternCrate;
 use rustc::traits::Obligation;
-use util::nodemap::FxHashSet;
+use util::nodemap::{FxHashMap, FxHashSet};
 
 use syntax::ast;
+use syntax::attr;
 use syntax::util::lev_distance::find_best_match_for_name;
 use errors::{Applicability, DiagnosticBuilder};
 use syntax_pos::{Span, FileName};
@@ -37,6 +39,7 @@ use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc::ty::Adt;
 
 use std::cmp::Ordering;
+use std::collections::VecDeque;
 
 use super::{MethodError, NoMatchData, CandidateSource};
 use super::probe::Mode;
@@ -477,6 +480,7 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
             MethodError::IllegalSizedBound(candidates) => {
                 let msg = format!("the `{}` method cannot be invoked on a trait object", item_name);
                 let mut err = self.sess().struct_span_err(span, &msg);
+                let candidates = self.trait_import_paths(candidates);
                 if !candidates.is_empty() {
                     let help = format!("{an}other candidate{s} {were} found in the following \
                                         trait{s}, perhaps add a `use` for {one_of_them}:",
@@ -502,13 +506,19 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
     fn suggest_use_candidates(&self,
                               err: &mut DiagnosticBuilder,
                               mut msg: String,
-                              candidates: Vec<DefId>) {
+                              candidates: Vec<String>) {
         let module_did = self.tcx.hir.get_module_parent(self.body_id);
         let module_id = self.tcx.hir.as_local_node_id(module_did).unwrap();
         let krate = self.tcx.hir.krate();
         let (span, found_use) = UsePlacementFinder::check(self.tcx, krate, module_id);
         if let Some(span) = span {
-            let path_strings = candidates.iter().map(|did| {
+            // A single candidate is the only trait that can provide the method.
+            let applicability = if candidates.len() == 1 {
+                Applicability::MachineApplicable
+            } else {
+                Applicability::MaybeIncorrect
+            };
+            let path_strings = candidates.iter().map(|path| {
                 // produce an additional newline to separate the new use statement
                 // from the directly following item.
                 let additional_newline = if found_use {
@@ -516,37 +526,22 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                 } else {
                     "\n"
                 };
-                format!(
-                    "use {};\n{}",
-                    with_crate_prefix(|| self.tcx.item_path_str(*did)),
-                    additional_newline
-                )
+                format!("use {};\n{}", path, additional_newline)
             });
 
             err.span_suggestions_with_applicability(
                                                     span,
                                                     &msg,
                                                     path_strings,
-                                                    Applicability::MaybeIncorrect,
+                                                    applicability,
             );
         } else {
             let limit = if candidates.len() == 5 { 5 } else { 4 };
-            for (i, trait_did) in candidates.iter().take(limit).enumerate() {
+            for (i, path) in candidates.iter().take(limit).enumerate() {
                 if candidates.len() > 1 {
-                    msg.push_str(
-                        &format!(
-                            "\ncandidate #{}: `use {};`",
-                            i + 1,
-                            with_crate_prefix(|| self.tcx.item_path_str(*trait_did))
-                        )
-                    );
+                    msg.push_str(&format!("\ncandidate #{}: `use {};`", i + 1, path));
                 } else {
-                    msg.push_str(
-                        &format!(
-                            "\n`use {};`",
-                            with_crate_prefix(|| self.tcx.item_path_str(*trait_did))
-                        )
-                    );
+                    msg.push_str(&format!("\n`use {};`", path));
                 }
             }
             if candidates.len() > limit {
@@ -556,13 +551,38 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
         }
     }
 
+    /// The paths to import `candidates` with, most relevant first: the traits of the local
+    /// crate, then those of other crates by the length of their shortest public path. The
+    /// traits of other crates that cannot be named from the local crate are left out.
+    fn trait_import_paths(&self, candidates: Vec<DefId>) -> Vec<String> {
+        let tcx = self.tcx;
+        let mut public_paths = None;
+        let mut paths = candidates.into_iter().filter_map(|did| {
+            let item_path = with_crate_prefix(|| tcx.item_path_str(did));
+            if did.is_local() {
+                return Some((did, item_path));
+            }
+            let public_paths = public_paths.get_or_insert_with(|| public_trait_paths(tcx));
+            let paths = public_paths.get(&did)?;
+            // Of equally short paths, prefer the one used to refer to the trait elsewhere.
+            if paths.contains(&item_path) {
+                Some((did, item_path))
+            } else {
+                Some((did, paths[0].clone()))
+            }
+        }).collect::<Vec<_>>();
+        paths.sort_by_key(|&(did, ref path)| {
+            (!did.is_local(), path.split("::").count(), path.clone())
+        });
+        paths.dedup();
+        paths.into_iter().map(|(_, path)| path).collect()
+    }
+
     fn suggest_valid_traits(&self,
                             err: &mut DiagnosticBuilder,
                             valid_out_of_scope_traits: Vec<DefId>) -> bool {
-        if !valid_out_of_scope_traits.is_empty() {
-            let mut candidates = valid_out_of_scope_traits;
-            candidates.sort();
-            candidates.dedup();
+        let candidates = self.trait_import_paths(valid_out_of_scope_traits);
+        if !candidates.is_empty() {
             err.help("items from traits can only be used if the trait is in scope");
             let msg = format!("the following {traits_are} implemented but not in scope, \
                                perhaps add a `use` for {one_of_them}:",
@@ -782,6 +802,68 @@ fn compute_all_traits<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Vec<DefId>
     traits
 }
 
+/// The shortest paths through public modules and re-exports of the crates that the local
+/// crate depends on directly, for each trait of another crate that can be named this way.
+fn public_trait_paths<'a, 'gcx, 'tcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>)
+                                      -> FxHashMap<DefId, Vec<String>> {
+    fn is_doc_hidden(tcx: TyCtxt, def_id: DefId) -> bool {
+        tcx.get_attrs(def_id).iter().any(|attr| {
+            attr.check_name("doc") &&
+            match attr.meta_item_list() {
+                None => false,
+                Some(l) => attr::list_contains_name(&l, "hidden"),
+            }
+        })
+    }
+
+    let mut paths: FxHashMap<DefId, Vec<String>> = FxHashMap::default();
+    let mut seen_mods = FxHashSet::default();
+    let mut queue = VecDeque::new();
+    for &cnum in tcx.crates().iter() {
+        let root = DefId {
+            krate: cnum,
+            index: CRATE_DEF_INDEX,
+        };
+        match *tcx.extern_crate(root) {
+            Some(ExternCrate { direct: true, .. }) => {}
+            _ => continue,
+        }
+        seen_mods.insert(root);
+        queue.push_back((root, with_crate_prefix(|| tcx.item_path_str(root))));
+    }
+
+    // Modules are searched breadth-first, so that each is reached by one of its shortest paths.
+    while let Some((module, module_path)) = queue.pop_front() {
+        for child in tcx.item_children(module).iter() {
+            if child.vis != ty::Visibility::Public {
+                continue;
+            }
+            let path = format!("{}::{}", module_path, child.ident);
+            match child.def {
+                Def::Trait(def_id) => {
+                    if is_doc_hidden(tcx, def_id) {
+                        continue;
+                    }
+                    let trait_paths = paths.entry(def_id).or_default();
+                    let len = path.split("::").count();
+                    match trait_paths.first().map(|shortest| shortest.split("::").count()) {
+                        Some(shortest) if shortest < len => {}
+                        Some(shortest) if shortest == len => trait_paths.push(path),
+                        _ => *trait_paths = vec![path],
+                    }
+                }
+                Def::Mod(def_id) => {
+                    if !is_doc_hidden(tcx, def_id) && seen_mods.insert(def_id) {
+                        queue.push_back((def_id, path));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    paths
+}
+
 pub fn provide(providers: &mut ty::query::Providers) {
     providers.all_traits = |tcx, cnum| {
         assert_eq!(cnum, LOCAL_CRATE);
@@ -0,0 +1,543 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Comparison of the public API of two versions of a crate, used with `--api-diff`.
+//!
+//! The previous version is given as the output of `--output-format json`, and the crate being
+//! documented is turned into the same JSON document, so both versions are read the same way.
+//! The public items of each version are collected by their path, along with their signature
+//! (with ids replaced by paths, and argument names left out), the trait impls of public types,
+//! including the auto trait impls that rustdoc synthesizes, and whether users can rely on
+//! knowing every field or variant of a type.
+//!
+//! Each difference is then classified by the semver rules: removing or changing an item, a
+//! trait impl or the default of a trait item is a major change, as is adding a field to a
+//! struct without private fields, a variant to an enum that is not `#[non_exhaustive]`, or an
+//! item without a default to a trait. Other additions are minor changes.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use errors;
+use rustc::util::nodemap::FxHashSet;
+use serialize::json::{self, Json};
+
+use clean;
+use config::RenderOptions;
+use html::render::RenderInfo;
+use json::{document, FORMAT_VERSION};
+
+static NULL: Json = Json::Null;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    Major,
+    Minor,
+}
+
+/// A public item, with everything about it that users of the crate can depend on.
+struct ApiItem {
+    /// The kind of the item, as in the JSON output. Required and provided trait methods both
+    /// use `method`.
+    kind: String,
+    /// The path of the item this one is a field, variant or associated item of.
+    parent: Option<String>,
+    signature: Json,
+    /// Whether users can rely on knowing all the fields or variants of this type, so that
+    /// adding one breaks them.
+    exhaustive: bool,
+    /// Whether implementors of the trait this item belongs to must provide it.
+    required: bool,
+}
+
+/// Compares the public API of `krate` to the one described by the JSON file at `old`, and
+/// prints the changes. Returns the exit code: `1` if there are major changes.
+pub fn run(krate: &clean::Crate,
+           options: &RenderOptions,
+           renderinfo: &RenderInfo,
+           old: &Path,
+           diag: &errors::Handler) -> isize {
+    let old_json = match fs::read_to_string(old) {
+        Ok(contents) => match json::from_str(&contents) {
+            Ok(json) => json,
+            Err(e) => {
+                diag.struct_err(&format!("could not parse `{}`: {}", old.display(), e)).emit();
+                return 1;
+            }
+        },
+        Err(e) => {
+            diag.struct_err(&format!("could not read `{}`: {}", old.display(), e)).emit();
+            return 1;
+        }
+    };
+    match field(&old_json, "format_version").as_u64() {
+        Some(version) if version == FORMAT_VERSION as u64 => {}
+        version => {
+            diag.struct_err(&format!("`{}` was not written with version {} of the JSON format",
+                                     old.display(), FORMAT_VERSION))
+                .note(&format!("the file has version {}",
+                               version.map_or("unknown".to_string(), |v| v.to_string())))
+                .help("generate it again with this version of rustdoc")
+                .emit();
+            return 1;
+        }
+    }
+    let new_json = document(krate, options, renderinfo);
+
+    let changes = compare(&Collector::collect(&old_json), &Collector::collect(&new_json));
+    let majors = changes.iter().filter(|&&(severity, _)| severity == Severity::Major).count();
+    for &(severity, ref description) in &changes {
+        let severity = match severity {
+            Severity::Major => "major",
+            Severity::Minor => "minor",
+        };
+        println!("{}: {}", severity, description);
+    }
+    println!("{} major and {} minor changes", majors, changes.len() - majors);
+    if majors > 0 { 1 } else { 0 }
+}
+
+fn compare(old: &BTreeMap<String, ApiItem>,
+           new: &BTreeMap<String, ApiItem>) -> Vec<(Severity, String)> {
+    let mut changes = vec![];
+    for (path, old_item) in old {
+        let kind = describe(&old_item.kind);
+        let new_item = match new.get(path) {
+            Some(new_item) => new_item,
+            None => {
+                changes.push((Severity::Major, format!("removed {} `{}`", kind, path)));
+                continue;
+            }
+        };
+        if new_item.kind != old_item.kind {
+            changes.push((Severity::Major, format!("changed `{}` from a {} to a {}",
+                                                   path, kind, describe(&new_item.kind))));
+            continue;
+        }
+        if new_item.signature != old_item.signature {
+            changes.push((Severity::Major,
+                          format!("changed the signature of {} `{}`", kind, path)));
+        }
+        if old_item.exhaustive != new_item.exhaustive {
+            let (severity, made) = if old_item.exhaustive {
+                (Severity::Major, "non-exhaustive")
+            } else {
+                (Severity::Minor, "exhaustive")
+            };
+            changes.push((severity, format!("made {} `{}` {}", kind, path, made)));
+        }
+        if old_item.required != new_item.required {
+            changes.push(if new_item.required {
+                (Severity::Major, format!("removed the default of {} `{}`", kind, path))
+            } else {
+                (Severity::Minor, format!("added a default to {} `{}`", kind, path))
+            });
+        }
+    }
+
+    for (path, new_item) in new {
+        if old.contains_key(path) {
+            continue;
+        }
+        let kind = describe(&new_item.kind);
+        let old_parent = new_item.parent.as_ref().and_then(|parent| old.get(parent));
+        let change = match old_parent {
+            Some(parent) if parent.exhaustive && new_item.kind == "structfield" => {
+                (Severity::Major, format!("added field `{}` to a {} without private fields",
+                                          path, describe(&parent.kind)))
+            }
+            Some(parent) if parent.exhaustive && new_item.kind == "variant" => {
+                (Severity::Major, format!("added variant `{}` to an exhaustive enum", path))
+            }
+            Some(_) if new_item.required => {
+                (Severity::Major, format!("added required {} `{}`", kind, path))
+            }
+            _ => (Severity::Minor, format!("added {} `{}`", kind, path)),
+        };
+        changes.push(change);
+    }
+
+    // Sorting is stable, so the changes of each severity stay in path order.
+    changes.sort_by_key(|&(severity, _)| severity);
+    changes
+}
+
+fn describe(kind: &str) -> &str {
+    match kind {
+        "mod" => "module",
+        "externcrate" => "extern crate",
+        "import" => "re-export",
+        "fn" => "function",
+        "type" => "type alias",
+        "existential" => "existential type",
+        "impl" => "trait impl",
+        "structfield" => "field",
+        "associatedtype" => "associated type",
+        "associatedconstant" => "associated constant",
+        "foreigntype" => "foreign type",
+        "attr" => "attribute macro",
+        "derive" => "derive macro",
+        _ => kind,
+    }
+}
+
+fn field<'a>(json: &'a Json, name: &str) -> &'a Json {
+    json.find(name).unwrap_or(&NULL)
+}
+
+fn string<'a>(json: &'a Json, name: &str) -> &'a str {
+    field(json, name).as_string().unwrap_or("")
+}
+
+fn list<'a>(json: &'a Json, name: &str) -> &'a [Json] {
+    field(json, name).as_array().map(|list| &list[..]).unwrap_or(&[])
+}
+
+/// Collects the public API from a JSON document.
+struct Collector<'a> {
+    index: &'a Json,
+    paths: &'a Json,
+    api: BTreeMap<String, ApiItem>,
+    /// The modules already walked, as modules can be re-exported.
+    modules: FxHashSet<&'a str>,
+}
+
+impl<'a> Collector<'a> {
+    fn collect(doc: &'a Json) -> BTreeMap<String, ApiItem> {
+        let mut collector = Collector {
+            index: field(doc, "index"),
+            paths: field(doc, "paths"),
+            api: BTreeMap::new(),
+            modules: FxHashSet::default(),
+        };
+        let krate = field(doc, "crate");
+        if let Some(root) = collector.item(field(krate, "root")) {
+            collector.add(root, string(krate, "name").to_string(), None);
+        }
+        collector.api
+    }
+
+    fn item(&self, id: &Json) -> Option<&'a Json> {
+        id.as_string().and_then(|id| self.index.find(id))
+    }
+
+    fn items(&self, item: &'a Json, name: &str) -> Vec<&'a Json> {
+        list(field(item, "inner"), name).iter().filter_map(|id| self.item(id)).collect()
+    }
+
+    fn add(&mut self, item: &'a Json, path: String, parent: Option<&str>) {
+        let inner = field(item, "inner");
+        let mut kind = string(item, "kind");
+        let non_exhaustive = list(item, "attrs").iter()
+            .any(|attr| attr.as_string().map_or(false, |attr| attr.contains("non_exhaustive")));
+        let exhaustive = match kind {
+            "struct" | "union" => {
+                field(inner, "fields_stripped").as_boolean() == Some(false) && !non_exhaustive
+            }
+            "enum" => !non_exhaustive,
+            "variant" => true,
+            _ => false,
+        };
+        let required = match kind {
+            "tymethod" => true,
+            "associatedtype" | "associatedconstant" => {
+                parent.is_some() && field(inner, "default").is_null()
+            }
+            _ => false,
+        };
+        if kind == "tymethod" {
+            kind = "method";
+        }
+
+        let signature = self.signature(kind, inner);
+        self.api.insert(path.clone(), ApiItem {
+            kind: kind.to_string(),
+            parent: parent.map(|parent| parent.to_string()),
+            signature,
+            exhaustive,
+            required,
+        });
+
+        match kind {
+            "mod" => {
+                if self.modules.insert(string(item, "id")) {
+                    for child in self.items(item, "items") {
+                        if is_public(child) {
+                            if let Some(name) = field(child, "name").as_string() {
+                                self.add(child, format!("{}::{}", path, name), None);
+                            }
+                        }
+                    }
+                }
+            }
+            "struct" | "union" | "variant" => {
+                let fields = if kind == "variant" {
+                    let variant_kind = field(inner, "variant_kind");
+                    self.items(variant_kind, "fields")
+                } else {
+                    self.items(item, "fields")
+                };
+                for child in fields {
+                    if kind == "variant" || is_public(child) {
+                        self.add_child(child, &path);
+                    }
+                }
+                self.add_impls(item, "impls", &path);
+            }
+            "enum" => {
+                for child in self.items(item, "variants") {
+                    self.add_child(child, &path);
+                }
+                self.add_impls(item, "impls", &path);
+            }
+            "trait" => {
+                for child in self.items(item, "items") {
+                    self.add_child(child, &path);
+                }
+                self.add_impls(item, "implementors", &path);
+            }
+            "primitive" | "foreigntype" => self.add_impls(item, "impls", &path),
+            _ => {}
+        }
+    }
+
+    fn add_child(&mut self, child: &'a Json, parent: &str) {
+        if let Some(name) = field(child, "name").as_string() {
+            self.add(child, format!("{}::{}", parent, name), Some(parent));
+        }
+    }
+
+    /// Adds the trait impls and the public inherent associated items of a type, or the impls
+    /// of a trait. Blanket impls are left out, as they follow from the other impls, and so are
+    /// negative impls, so that a type losing an auto trait shows up as a removed impl.
+    fn add_impls(&mut self, item: &'a Json, list_name: &str, path: &str) {
+        for imp in self.items(item, list_name) {
+            let inner = field(imp, "inner");
+            if !field(inner, "blanket_impl").is_null() ||
+                field(inner, "negative").as_boolean() == Some(true) {
+                continue;
+            }
+            let trait_ = field(inner, "trait");
+            if trait_.is_null() {
+                for child in self.items(imp, "items") {
+                    if is_public(child) {
+                        self.add_child(child, path);
+                    }
+                }
+            } else {
+                let name = format!("impl {} for {}",
+                                   self.type_string(trait_),
+                                   self.type_string(field(inner, "for")));
+                self.api.insert(name, ApiItem {
+                    kind: "impl".to_string(),
+                    parent: None,
+                    signature: self.canonical(field(inner, "generics")),
+                    exhaustive: false,
+                    required: false,
+                });
+            }
+        }
+    }
+
+    /// The parts of an item's `inner` fields that make up its signature.
+    fn signature(&self, kind: &str, inner: &Json) -> Json {
+        let fields: &[&str] = match kind {
+            "fn" | "method" => {
+                // Argument names are not part of the signature.
+                let decl = field(inner, "decl");
+                let inputs = list(decl, "inputs").iter()
+                    .map(|arg| arg.as_array().and_then(|arg| arg.get(1)).unwrap_or(&NULL))
+                    .map(|ty| self.canonical(ty))
+                    .collect();
+                let mut signature = json::Object::new();
+                signature.insert("inputs".to_string(), Json::Array(inputs));
+                signature.insert("output".to_string(), self.canonical(field(decl, "output")));
+                signature.insert("variadic".to_string(), field(decl, "variadic").clone());
+                signature.insert("generics".to_string(), self.canonical(field(inner, "generics")));
+                signature.insert("header".to_string(), field(inner, "header").clone());
+                return Json::Object(signature);
+            }
+            "variant" => {
+                // Struct variants list their fields by id, which are compared on their own.
+                let variant_kind = field(inner, "variant_kind");
+                return match string(variant_kind, "kind") {
+                    "tuple" => self.canonical(variant_kind),
+                    kind => Json::String(kind.to_string()),
+                };
+            }
+            "import" => {
+                let id = string(inner, "id");
+                return self.path_of(id).map_or_else(|| field(inner, "source").clone(),
+                                                    Json::String);
+            }
+            "struct" | "union" => &["generics", "struct_type"],
+            "enum" => &["generics"],
+            "structfield" => &["type"],
+            "trait" => &["generics", "bounds", "is_auto", "is_unsafe"],
+            "type" => &["type", "generics"],
+            "existential" => &["bounds", "generics"],
+            "static" => &["type", "mutable"],
+            "constant" | "associatedconstant" => &["type"],
+            "associatedtype" => &["bounds"],
+            "externcrate" => &["original_name"],
+            _ => &[],
+        };
+        Json::Object(fields.iter()
+            .map(|&name| (name.to_string(), self.canonical(field(inner, name))))
+            .collect())
+    }
+
+    /// The full path of the item with the given id, if it is known.
+    fn path_of(&self, id: &str) -> Option<String> {
+        let path = list(self.paths.find(id)?, "path");
+        let segments: Vec<_> = path.iter().filter_map(|s| s.as_string()).collect();
+        if segments.is_empty() { None } else { Some(segments.join("::")) }
+    }
+
+    /// The path a `resolved_path` type refers to.
+    fn resolved_path(&self, inner: &Json) -> String {
+        self.path_of(string(inner, "id")).unwrap_or_else(|| string(inner, "name").to_string())
+    }
+
+    /// Replaces the ids in `json`, which differ between documents, with the paths they refer
+    /// to.
+    fn canonical(&self, json: &Json) -> Json {
+        match *json {
+            Json::Object(ref object) => {
+                let mut canonical: json::Object = object.iter()
+                    .map(|(name, value)| (name.clone(), self.canonical(value)))
+                    .collect();
+                if string(json, "kind") == "resolved_path" {
+                    let path = self.resolved_path(field(json, "inner"));
+                    if let Some(&mut Json::Object(ref mut inner)) = canonical.get_mut("inner") {
+                        inner.remove("name");
+                        inner.insert("id".to_string(), Json::String(path));
+                    }
+                }
+                Json::Object(canonical)
+            }
+            Json::Array(ref values) => {
+                Json::Array(values.iter().map(|value| self.canonical(value)).collect())
+            }
+            ref value => value.clone(),
+        }
+    }
+
+    fn type_string(&self, ty: &Json) -> String {
+        let inner = field(ty, "inner");
+        let types = |list: &[Json]| {
+            list.iter().map(|ty| self.type_string(ty)).collect::<Vec<_>>().join(", ")
+        };
+        match string(ty, "kind") {
+            "resolved_path" => {
+                let args = field(inner, "args");
+                let args_inner = field(args, "inner");
+                let args = match string(args, "kind") {
+                    "angle_bracketed" => {
+                        let mut args: Vec<_> = list(args_inner, "lifetimes").iter()
+                            .filter_map(|lt| lt.as_string())
+                            .map(|lt| lt.to_string())
+                            .collect();
+                        args.extend(list(args_inner, "types").iter()
+                            .map(|ty| self.type_string(ty)));
+                        args.extend(list(args_inner, "bindings").iter().map(|binding| {
+                            format!("{} = {}",
+                                    string(binding, "name"),
+                                    self.type_string(field(binding, "type")))
+                        }));
+                        if args.is_empty() {
+                            String::new()
+                        } else {
+                            format!("<{}>", args.join(", "))
+                        }
+                    }
+                    "parenthesized" => {
+                        let output = field(args_inner, "output");
+                        format!("({}){}",
+                                types(list(args_inner, "inputs")),
+                                if output.is_null() {
+                                    String::new()
+                                } else {
+                                    format!(" -> {}", self.type_string(output))
+                                })
+                    }
+                    _ => String::new(),
+                };
+                format!("{}{}", self.resolved_path(inner), args)
+            }
+            "generic" | "primitive" => inner.as_string().unwrap_or("").to_string(),
+            "tuple" => {
+                format!("({})", types(inner.as_array().map(|list| &list[..]).unwrap_or(&[])))
+            }
+            "slice" => format!("[{}]", self.type_string(inner)),
+            "array" => {
+                format!("[{}; {}]", self.type_string(field(inner, "type")), string(inner, "len"))
+            }
+            "never" => "!".to_string(),
+            "unique" => format!("Box<{}>", self.type_string(inner)),
+            "raw_pointer" => {
+                let mutability = if field(inner, "mutable").as_boolean() == Some(true) {
+                    "mut"
+                } else {
+                    "const"
+                };
+                format!("*{} {}", mutability, self.type_string(field(inner, "type")))
+            }
+            "borrowed_ref" => {
+                let lifetime = field(inner, "lifetime").as_string()
+                    .map_or(String::new(), |lt| format!("{} ", lt));
+                let mutability = if field(inner, "mutable").as_boolean() == Some(true) {
+                    "mut "
+                } else {
+                    ""
+                };
+                format!("&{}{}{}", lifetime, mutability, self.type_string(field(inner, "type")))
+            }
+            "qualified_path" => {
+                format!("<{} as {}>::{}",
+                        self.type_string(field(inner, "self_type")),
+                        self.type_string(field(inner, "trait")),
+                        string(inner, "name"))
+            }
+            "impl_trait" => {
+                let bounds = inner.as_array().map(|list| &list[..]).unwrap_or(&[]).iter()
+                    .map(|bound| match string(bound, "kind") {
+                        "trait_bound" => {
+                            self.type_string(field(field(bound, "inner"), "trait"))
+                        }
+                        _ => string(bound, "inner").to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                format!("impl {}", bounds.join(" + "))
+            }
+            "function_pointer" => {
+                let decl = field(inner, "decl");
+                let inputs = list(decl, "inputs").iter()
+                    .map(|arg| arg.as_array().and_then(|arg| arg.get(1)).unwrap_or(&NULL))
+                    .map(|ty| self.type_string(ty))
+                    .collect::<Vec<_>>();
+                let output = field(decl, "output");
+                format!("fn({}){}",
+                        inputs.join(", "),
+                        if output.is_null() {
+                            String::new()
+                        } else {
+                            format!(" -> {}", self.type_string(output))
+                        })
+            }
+            _ => "_".to_string(),
+        }
+    }
+}
+
+fn is_public(item: &Json) -> bool {
+    field(item, "visibility").as_string() == Some("public")
+}
@@ -11,6 +11,7 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use errors;
 use errors::emitter::ColorConfig;
@@ -78,6 +79,16 @@ pub struct Options {
     pub should_test: bool,
     /// List of arguments to pass to the test harness, if running tests.
     pub test_args: Vec<String>,
+    /// Whether to compile the doctests that allow it into a single executable, rather than each
+    /// into its own.
+    pub merge_doctests: bool,
+    /// The directory in which to keep the compiled doctest executables, if any, instead of
+    /// deleting them after running them.
+    pub persist_doctests: Option<PathBuf>,
+    /// How long a doctest executable may run before it is killed and the test fails.
+    pub doctest_timeout: Option<Duration>,
+    /// The item paths whose doctests should be run. If empty, all doctests are run.
+    pub doctest_filters: Vec<String>,
 
     // Options that affect the documentation process
 
@@ -131,6 +142,10 @@ impl fmt::Debug for Options {
             .field("lint_cap", &self.lint_cap)
             .field("should_test", &self.should_test)
             .field("test_args", &self.test_args)
+            .field("merge_doctests", &self.merge_doctests)
+            .field("persist_doctests", &self.persist_doctests)
+            .field("doctest_timeout", &self.doctest_timeout)
+            .field("doctest_filters", &self.doctest_filters)
             .field("default_passes", &self.default_passes)
             .field("manual_passes", &self.manual_passes)
             .field("display_warnings", &self.display_warnings)
@@ -140,11 +155,39 @@ impl fmt::Debug for Options {
     }
 }
 
+/// The format of the generated documentation, as given by `--output-format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// HTML pages. The default.
+    Html,
+    /// A single JSON file describing the crate, see `json.rs`. Unstable.
+    Json,
+}
+
 /// Configuration options for the HTML page-creation process.
 #[derive(Clone, Debug)]
 pub struct RenderOptions {
     /// Output directory to generate docs into. Defaults to `doc`.
     pub output: PathBuf,
+    /// The format to write the documentation in.
+    pub output_format: OutputFormat,
+    /// If present, the JSON output of a previous version of the crate to compare the public API
+    /// to, instead of writing documentation.
+    pub api_diff: Option<PathBuf>,
+    /// Whether to print a report of how many items are documented, instead of writing
+    /// documentation.
+    pub show_coverage: bool,
+    /// If present, the file to write the calls to the items of `scrape_examples_target_crates`
+    /// found in the crate to, instead of writing documentation.
+    pub scrape_examples_output_path: Option<PathBuf>,
+    /// The crates whose items the calls are scraped for with `scrape_examples_output_path`.
+    pub scrape_examples_target_crates: Vec<String>,
+    /// Whether to compile the crate as a test when scraping it, so that the calls in the
+    /// `#[test]` functions are scraped too.
+    pub scrape_tests: bool,
+    /// The files of calls scraped from the examples of the crate, to show on the pages of the
+    /// items called.
+    pub with_examples: Vec<PathBuf>,
     /// External files to insert into generated pages.
     pub external_html: ExternalHtml,
     /// A pre-populated `IdMap` with the default headings and any headings added by Markdown files
@@ -181,6 +224,8 @@ pub struct RenderOptions {
     /// A file to use as the index page at the root of the output directory. Overrides
     /// `enable_index_page` to be true if set.
     pub index_page: Option<PathBuf>,
+    /// Whether to link the identifiers in the source pages to their definitions.
+    pub generate_link_to_definition: bool,
 
     // Options specific to reading standalone Markdown files
 
@@ -321,6 +366,20 @@ impl Options {
                                               .collect();
 
         let should_test = matches.opt_present("test");
+        let merge_doctests = matches.opt_present("merge-doctests");
+        let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
+        let doctest_timeout = match matches.opt_str("doctest-timeout") {
+            Some(secs) => match secs.parse() {
+                Ok(secs) => Some(Duration::from_secs(secs)),
+                Err(_) => {
+                    diag.struct_err(&format!("invalid number of seconds `{}` for \
+                                              --doctest-timeout", secs)).emit();
+                    return Err(1);
+                }
+            },
+            None => None,
+        };
+        let doctest_filters = matches.opt_strs("doctest-filter");
 
         let output = matches.opt_str("o")
                             .map(|s| PathBuf::from(&s))
@@ -388,13 +447,21 @@ impl Options {
             }
         }
 
-        match matches.opt_str("w").as_ref().map(|s| &**s) {
-            Some("html") | None => {}
+        let output_format = match matches.opt_str("w").as_ref().map(|s| &**s) {
+            Some("html") | None => OutputFormat::Html,
+            Some("json") => {
+                if !nightly_options::is_unstable_enabled(matches) {
+                    diag.struct_err("the `-Z unstable-options` flag must also be passed to \
+                                     enable `--output-format json`").emit();
+                    return Err(1);
+                }
+                OutputFormat::Json
+            }
             Some(s) => {
                 diag.struct_err(&format!("unknown output format: {}", s)).emit();
                 return Err(1);
             }
-        }
+        };
 
         let index_page = matches.opt_str("index-page").map(|s| PathBuf::from(&s));
         if let Some(ref index_page) = index_page {
@@ -434,6 +501,28 @@ impl Options {
         let markdown_playground_url = matches.opt_str("markdown-playground-url");
         let crate_version = matches.opt_str("crate-version");
         let enable_index_page = matches.opt_present("enable-index-page") || index_page.is_some();
+        let api_diff = matches.opt_str("api-diff").map(PathBuf::from);
+        let show_coverage = matches.opt_present("show-coverage");
+        let generate_link_to_definition = matches.opt_present("generate-link-to-definition");
+        let scrape_examples_output_path = matches.opt_str("scrape-examples-output-path")
+                                                 .map(PathBuf::from);
+        let scrape_examples_target_crates = matches.opt_strs("scrape-examples-target-crate");
+        let scrape_tests = matches.opt_present("scrape-tests");
+        let with_examples = matches.opt_strs("with-examples").into_iter()
+                                                             .map(PathBuf::from)
+                                                             .collect();
+
+        if scrape_examples_output_path.is_some() == scrape_examples_target_crates.is_empty() {
+            diag.struct_err("`--scrape-examples-output-path` and \
+                             `--scrape-examples-target-crate` must be given together")
+                .emit();
+            return Err(1);
+        }
+        if scrape_tests && scrape_examples_output_path.is_none() {
+            diag.struct_err("`--scrape-tests` must be used with `--scrape-examples-output-path`")
+                .emit();
+            return Err(1);
+        }
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -455,12 +544,23 @@ impl Options {
             lint_cap,
             should_test,
             test_args,
+            merge_doctests,
+            persist_doctests,
+            doctest_timeout,
+            doctest_filters,
             default_passes,
             manual_passes,
             display_warnings,
             crate_version,
             render_options: RenderOptions {
                 output,
+                output_format,
+                api_diff,
+                show_coverage,
+                scrape_examples_output_path,
+                scrape_examples_target_crates,
+                scrape_tests,
+                with_examples,
                 external_html,
                 id_map,
                 playground_url,
@@ -472,6 +572,7 @@ impl Options {
                 enable_minification,
                 enable_index_page,
                 index_page,
+                generate_link_to_definition,
                 markdown_no_toc,
                 markdown_css,
                 markdown_playground_url,
@@ -496,6 +597,10 @@ fn check_deprecated_options(matches: &getopts::Matches, diag: &errors::Handler)
     ];
 
     for flag in deprecated_flags.into_iter() {
+        // `--output-format json` selects the unstable JSON backend, which is not deprecated.
+        if *flag == "output-format" && matches.opt_str("w").map_or(false, |s| s == "json") {
+            continue;
+        }
         if matches.opt_present(flag) {
             let mut err = diag.struct_warn(&format!("the '{}' flag is considered deprecated",
                                                     flag));
@@ -48,7 +48,9 @@ use config::{Options as RustdocOptions, RenderOptions};
 use clean;
 use clean::{get_path_for_type, Clean, MAX_DEF_ID, AttributesExt};
 use html::render::RenderInfo;
+use html::span_map;
 use passes;
+use scrape_examples;
 
 pub use rustc::session::config::{Input, Options, CodegenOptions};
 pub use rustc::session::search_paths::SearchPaths;
@@ -352,6 +354,13 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
     let missing_docs = rustc_lint::builtin::MISSING_DOCS.name;
     let missing_doc_example = rustc_lint::builtin::MISSING_DOC_CODE_EXAMPLES.name;
     let private_doc_tests = rustc_lint::builtin::PRIVATE_DOC_TESTS.name;
+    // The lints about the Markdown of the documentation, which keep their default level.
+    let markdown_lints = [
+        lint::builtin::INVALID_HTML_TAGS.name,
+        lint::builtin::BARE_URLS.name,
+        lint::builtin::UNCLOSED_CODE_BLOCKS.name,
+        lint::builtin::BROKEN_FOOTNOTE_REFERENCES.name,
+    ];
 
     // In addition to those specific lints, we also need to whitelist those given through
     // command line, otherwise they'll get ignored and we don't want that.
@@ -360,6 +369,7 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                                      missing_docs.to_owned(),
                                      missing_doc_example.to_owned(),
                                      private_doc_tests.to_owned()];
+    whitelisted_lints.extend(markdown_lints.iter().map(|lint| lint.to_string()));
 
     whitelisted_lints.extend(lint_opts.iter().map(|(lint, _)| lint).cloned());
 
@@ -368,7 +378,8 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                     .chain(rustc_lint::SoftLints.get_lints().into_iter())
                     .filter_map(|lint| {
                         if lint.name == warnings_lint_name ||
-                           lint.name == intra_link_resolution_failure_name {
+                           lint.name == intra_link_resolution_failure_name ||
+                           markdown_lints.contains(&lint.name) {
                             None
                         } else {
                             Some((lint.name_lower(), lint::Allow))
@@ -399,6 +410,8 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
         error_format,
         edition,
         describe_lints,
+        // The `#[test]` functions are removed unless the crate is compiled as a test.
+        test: render_options.scrape_tests,
         ..Options::default()
     };
     driver::spawn_thread_pool(sessopts, move |sessopts| {
@@ -553,6 +566,23 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                 v.clean(&ctxt)
             };
 
+            if render_options.generate_link_to_definition {
+                let span_map = span_map::collect(&ctxt);
+                ctxt.renderinfo.borrow_mut().span_map = span_map;
+            }
+
+            if render_options.scrape_examples_output_path.is_some() {
+                let calls = scrape_examples::collect(&ctxt,
+                                                     &render_options.scrape_examples_target_crates);
+                ctxt.renderinfo.borrow_mut().scraped_calls = calls;
+            }
+            if !render_options.with_examples.is_empty() {
+                match scrape_examples::load(&ctxt, &render_options.with_examples) {
+                    Ok(calls) => ctxt.renderinfo.borrow_mut().call_locations = calls,
+                    Err(e) => sess.fatal(&e),
+                }
+            }
+
             fn report_deprecated_attr(name: &str, diag: &errors::Handler) {
                 let mut msg = diag.struct_warn(&format!("the `#![doc({})]` attribute is \
                                                          considered deprecated", name));
@@ -0,0 +1,195 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The documentation coverage report, used with `--show-coverage`.
+//!
+//! Counts how many of the items left after the passes have run have documentation, and how
+//! many have a code example in their documentation, per module and per item kind. Imports and
+//! impls are not counted themselves, and neither are the items of trait impls, as they show
+//! the documentation of the trait; the items of inherent impls are.
+
+use std::collections::BTreeMap;
+
+use serialize::json::{Json, ToJson};
+
+use clean::{self, Item};
+use config::{OutputFormat, RenderOptions};
+use html::markdown::{find_testable_code, ErrorCodes, LangString};
+
+#[derive(Default, Copy, Clone)]
+struct ItemCount {
+    total: u64,
+    with_docs: u64,
+    with_examples: u64,
+}
+
+impl ItemCount {
+    fn count_item(&mut self, has_docs: bool, has_examples: bool) {
+        self.total += 1;
+        if has_docs {
+            self.with_docs += 1;
+        }
+        if has_examples {
+            self.with_examples += 1;
+        }
+    }
+
+    fn percentage(&self, count: u64) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+impl ToJson for ItemCount {
+    fn to_json(&self) -> Json {
+        let mut obj = BTreeMap::new();
+        obj.insert("total".to_string(), self.total.to_json());
+        obj.insert("with_docs".to_string(), self.with_docs.to_json());
+        obj.insert("with_examples".to_string(), self.with_examples.to_json());
+        Json::Object(obj)
+    }
+}
+
+struct CoverageCounter {
+    modules: BTreeMap<String, ItemCount>,
+    kinds: BTreeMap<String, ItemCount>,
+    total: ItemCount,
+    module_path: Vec<String>,
+}
+
+/// Prints the coverage report of `krate`, as a table or, with `--output-format json`, as JSON.
+pub fn run(krate: &clean::Crate, options: &RenderOptions) {
+    let mut counter = CoverageCounter {
+        modules: BTreeMap::new(),
+        kinds: BTreeMap::new(),
+        total: ItemCount::default(),
+        module_path: vec![krate.name.clone()],
+    };
+    if let Some(ref module) = krate.module {
+        counter.visit(module);
+    }
+
+    match options.output_format {
+        OutputFormat::Json => {
+            let mut obj = BTreeMap::new();
+            obj.insert("crate".to_string(), krate.name.to_json());
+            obj.insert("total".to_string(), counter.total.to_json());
+            obj.insert("modules".to_string(), counter.modules.to_json());
+            obj.insert("kinds".to_string(), counter.kinds.to_json());
+            println!("{}", Json::Object(obj));
+        }
+        OutputFormat::Html => {
+            print_table("Module", &counter.modules, &counter.total);
+            println!();
+            print_table("Kind", &counter.kinds, &counter.total);
+        }
+    }
+}
+
+impl CoverageCounter {
+    fn visit(&mut self, item: &Item) {
+        match item.inner {
+            clean::StrippedItem(..) | clean::ImportItem(..) | clean::ExternCrateItem(..) => {
+                return;
+            }
+            clean::ImplItem(ref i) => {
+                if i.trait_.is_none() {
+                    for item in &i.items {
+                        self.visit(item);
+                    }
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let is_crate = item.is_crate();
+        if item.is_mod() && !is_crate {
+            self.module_path.push(item.name.clone().unwrap_or_default());
+        }
+        self.count(item);
+        match item.inner {
+            clean::ModuleItem(ref m) => self.visit_all(&m.items),
+            clean::StructItem(ref s) => self.visit_all(&s.fields),
+            clean::UnionItem(ref u) => self.visit_all(&u.fields),
+            clean::EnumItem(ref e) => self.visit_all(&e.variants.raw),
+            clean::VariantItem(clean::Variant { kind: clean::VariantKind::Struct(ref s) }) => {
+                self.visit_all(&s.fields)
+            }
+            clean::TraitItem(ref t) => self.visit_all(&t.items),
+            _ => {}
+        }
+        if item.is_mod() && !is_crate {
+            self.module_path.pop();
+        }
+    }
+
+    fn visit_all(&mut self, items: &[Item]) {
+        for item in items {
+            self.visit(item);
+        }
+    }
+
+    fn count(&mut self, item: &Item) {
+        struct Tests {
+            found_tests: usize,
+        }
+
+        impl ::test::Tester for Tests {
+            fn add_test(&mut self, _: String, _: LangString, _: usize) {
+                self.found_tests += 1;
+            }
+        }
+
+        let doc = item.collapsed_doc_value().unwrap_or_default();
+        let has_docs = !doc.trim().is_empty();
+        let mut tests = Tests { found_tests: 0 };
+        let has_examples = has_docs &&
+            find_testable_code(&doc, &mut tests, ErrorCodes::No).is_ok() &&
+            tests.found_tests > 0;
+
+        let module = self.module_path.join("::");
+        self.modules.entry(module).or_default().count_item(has_docs, has_examples);
+        self.kinds.entry(item.type_().css_class().to_string()).or_default()
+            .count_item(has_docs, has_examples);
+        self.total.count_item(has_docs, has_examples);
+    }
+}
+
+fn print_table(heading: &str, rows: &BTreeMap<String, ItemCount>, total: &ItemCount) {
+    let width = rows.keys().map(|name| name.len())
+        .chain(vec![heading.len(), "Total".len()])
+        .max()
+        .unwrap_or(0);
+    let separator = format!("|{}|-------|------------|------------|----------|------------|",
+                            "-".repeat(width + 2));
+    println!("| {:<width$} | Items | Documented | Percentage | Examples | Percentage |",
+             heading, width = width);
+    println!("{}", separator);
+    for (name, count) in rows {
+        print_row(name, count, width);
+    }
+    println!("{}", separator);
+    print_row("Total", total, width);
+}
+
+fn print_row(name: &str, count: &ItemCount, width: usize) {
+    println!("| {:<width$} | {:>5} | {:>10} | {:>9.1}% | {:>8} | {:>9.1}% |",
+             name,
+             count.total,
+             count.with_docs,
+             count.percentage(count.with_docs),
+             count.with_examples,
+             count.percentage(count.with_examples),
+             width = width);
+}
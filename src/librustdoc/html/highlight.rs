@@ -13,10 +13,13 @@
 //! This module uses libsyntax's lexer to provide token-based highlighting for
 //! the HTML documentation generated by rustdoc.
 //!
-//! Use the `render_with_highlighting` to highlight some rust code.
+//! Use the `render_with_highlighting` to highlight some rust code, or
+//! `render_source_with_links` to also link identifiers to their definitions.
 
 use html::escape::Escape;
 
+use rustc::util::nodemap::FxHashMap;
+
 use std::fmt::Display;
 use std::io;
 use std::io::prelude::*;
@@ -25,7 +28,7 @@ use syntax::source_map::{SourceMap, FilePathMapping};
 use syntax::parse::lexer::{self, TokenAndSpan};
 use syntax::parse::token;
 use syntax::parse;
-use syntax_pos::{Span, FileName};
+use syntax_pos::{BytePos, Span, FileName};
 
 /// Highlights `src`, returning the HTML output.
 pub fn render_with_highlighting(
@@ -33,6 +36,22 @@ pub fn render_with_highlighting(
     class: Option<&str>,
     extension: Option<&str>,
     tooltip: Option<(&str, &str)>,
+) -> String {
+    render(src, class, extension, tooltip, &FxHashMap::default())
+}
+
+/// Highlights the source file `src`, making the identifiers at the byte ranges in `links` link to
+/// the corresponding URLs, and returns the HTML output.
+pub fn render_source_with_links(src: &str, links: &FxHashMap<(u32, u32), String>) -> String {
+    render(src, None, None, None, links)
+}
+
+fn render(
+    src: &str,
+    class: Option<&str>,
+    extension: Option<&str>,
+    tooltip: Option<(&str, &str)>,
+    links: &FxHashMap<(u32, u32), String>,
 ) -> String {
     debug!("highlighting: ================\n{}\n==============", src);
     let sess = parse::ParseSess::new(FilePathMapping::empty());
@@ -47,6 +66,7 @@ pub fn render_with_highlighting(
     }
     write_header(class, &mut out).unwrap();
 
+    let start_pos = fm.start_pos;
     let lexer = match lexer::StringReader::new_without_err(&sess, fm, None, "Output from rustc:") {
         Ok(l) => l,
         Err(_) => {
@@ -59,7 +79,7 @@ pub fn render_with_highlighting(
             return String::new();
         }
     };
-    let mut classifier = Classifier::new(lexer, sess.source_map());
+    let mut classifier = Classifier::new(lexer, sess.source_map(), links, start_pos);
     if classifier.write_source(&mut out).is_err() {
         classifier.lexer.emit_fatal_errors();
         return format!("<pre>{}</pre>", src);
@@ -78,6 +98,10 @@ pub fn render_with_highlighting(
 struct Classifier<'a> {
     lexer: lexer::StringReader<'a>,
     source_map: &'a SourceMap,
+    /// The URLs that the tokens link to, by their byte range in the source.
+    links: &'a FxHashMap<(u32, u32), String>,
+    /// The position of the source in `source_map`.
+    start_pos: BytePos,
 
     // State of the classifier.
     in_attribute: bool,
@@ -137,6 +161,13 @@ trait Writer {
                           text: T,
                           klass: Class)
                           -> io::Result<()>;
+
+    /// Called for a span of text that links to `href`, highlighted like with `string`.
+    fn link<T: Display>(&mut self,
+                        text: T,
+                        klass: Class,
+                        href: &str)
+                        -> io::Result<()>;
 }
 
 // Implement `Writer` for anthing that can be written to, this just implements
@@ -152,6 +183,16 @@ impl<U: Write> Writer for U {
         }
     }
 
+    fn link<T: Display>(&mut self,
+                        text: T,
+                        klass: Class,
+                        href: &str)
+                        -> io::Result<()> {
+        write!(self, "<a href=\"{}\">", Escape(href))?;
+        self.string(text, klass)?;
+        write!(self, "</a>")
+    }
+
     fn enter_span(&mut self, klass: Class) -> io::Result<()> {
         write!(self, "<span class=\"{}\">", klass.rustdoc_class())
     }
@@ -162,10 +203,16 @@ impl<U: Write> Writer for U {
 }
 
 impl<'a> Classifier<'a> {
-    fn new(lexer: lexer::StringReader<'a>, source_map: &'a SourceMap) -> Classifier<'a> {
+    fn new(lexer: lexer::StringReader<'a>,
+           source_map: &'a SourceMap,
+           links: &'a FxHashMap<(u32, u32), String>,
+           start_pos: BytePos)
+           -> Classifier<'a> {
         Classifier {
             lexer,
             source_map,
+            links,
+            start_pos,
             in_attribute: false,
             in_macro: false,
             in_macro_nonterminal: false,
@@ -350,8 +397,13 @@ impl<'a> Classifier<'a> {
         };
 
         // Anything that didn't return above is the simple case where we the
-        // class just spans a single token, so we can use the `string` method.
-        out.string(Escape(&self.snip(tas.sp)), klass)
+        // class just spans a single token, so we can use the `string` method,
+        // or `link` if the token links to its definition.
+        let range = ((tas.sp.lo() - self.start_pos).0, (tas.sp.hi() - self.start_pos).0);
+        match self.links.get(&range) {
+            Some(href) => out.link(Escape(&self.snip(tas.sp)), klass, href),
+            None => out.string(Escape(&self.snip(tas.sp)), klass),
+        }
     }
 
     // Helper function to get a snippet from the source_map.
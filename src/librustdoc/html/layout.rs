@@ -133,7 +133,9 @@ pub fn render<T: fmt::Display, S: fmt::Display>(
                 </p>\
                 <p>\
                     Search functions by type signature (e.g. \
-                    <code>vec -> usize</code> or <code>* -> vec</code>)\
+                    <code>vec -> usize</code>, <code>* -> vec</code> or \
+                    <code>Vec&lt;T&gt; -> Option&lt;T&gt;</code>, where a single letter \
+                    stands for any type)\
                 </p>\
                 <p>\
                     Search multiple things at once by splitting your query with comma (e.g. \
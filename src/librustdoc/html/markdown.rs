@@ -855,6 +855,23 @@ pub fn plain_summary_line(md: &str) -> String {
     s
 }
 
+/// Returns the range of `s` in `md`, if `s` is a slice of `md`.
+fn locate_in(md: &str, s: &str) -> Option<Range<usize>> {
+    unsafe {
+        let s_start = s.as_ptr();
+        let s_end = s_start.add(s.len());
+        let md_start = md.as_ptr();
+        let md_end = md_start.add(md.len());
+        if md_start <= s_start && s_end <= md_end {
+            let start = s_start.offset_from(md_start) as usize;
+            let end = s_end.offset_from(md_start) as usize;
+            Some(start..end)
+        } else {
+            None
+        }
+    }
+}
+
 pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     if md.is_empty() {
         return vec![];
@@ -868,19 +885,7 @@ pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     let shortcut_links = RefCell::new(vec![]);
 
     {
-        let locate = |s: &str| unsafe {
-            let s_start = s.as_ptr();
-            let s_end = s_start.add(s.len());
-            let md_start = md.as_ptr();
-            let md_end = md_start.add(md.len());
-            if md_start <= s_start && s_end <= md_end {
-                let start = s_start.offset_from(md_start) as usize;
-                let end = s_end.offset_from(md_start) as usize;
-                Some(start..end)
-            } else {
-                None
-            }
-        };
+        let locate = |s: &str| locate_in(md, s);
 
         let push = |_: &str, s: &str| {
             shortcut_links.borrow_mut().push((s.to_owned(), locate(s)));
@@ -911,6 +916,267 @@ pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     links
 }
 
+/// A problem with the Markdown of some documentation, which makes it render differently from
+/// what was likely intended.
+#[derive(Debug, PartialEq)]
+pub enum MarkdownProblem {
+    /// An HTML tag which is never closed, with its name.
+    UnclosedHtmlTag(String),
+    /// A closing HTML tag which was never opened, with its name.
+    UnopenedHtmlTag(String),
+    /// A URL in the text, which isn't turned into a link.
+    BareUrl(String),
+    /// A code block whose fence is never closed, so that it goes on to the end of the
+    /// documentation.
+    UnclosedCodeBlock,
+    /// A reference to a footnote which isn't defined, with its label.
+    BrokenFootnoteReference(String),
+}
+
+/// The HTML elements which have no closing tag.
+const VOID_HTML_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Finds the problems with the Markdown of `md`, with their range in it.
+pub fn markdown_problems(md: &str) -> Vec<(MarkdownProblem, Range<usize>)> {
+    let mut problems = vec![];
+    if md.is_empty() {
+        return problems;
+    }
+
+    let mut opts = Options::empty();
+    opts.insert(OPTION_ENABLE_TABLES);
+    opts.insert(OPTION_ENABLE_FOOTNOTES);
+
+    // The references to undefined footnotes are reported as broken links.
+    let broken_footnotes = RefCell::new(vec![]);
+    {
+        let push = |_: &str, s: &str| {
+            if s.starts_with('^') {
+                if let Some(mut range) = locate_in(md, s) {
+                    if md[..range.start].ends_with('[') && md[range.end..].starts_with(']') {
+                        range = range.start - 1..range.end + 1;
+                    }
+                    broken_footnotes.borrow_mut().push((s[1..].to_owned(), range));
+                }
+            }
+            None
+        };
+        let p = Parser::new_with_broken_link_callback(md, opts, Some(&push));
+
+        // The open HTML tags, with their name and range.
+        let mut tags: Vec<(String, Range<usize>)> = vec![];
+        let mut footnote_references = vec![];
+        let mut footnote_definitions = vec![];
+        // The number of links and code spans or blocks the parser is in, whose text is not
+        // checked for URLs.
+        let mut in_link_or_code = 0;
+        // The range of the text seen since the last event which wasn't text.
+        let mut text: Option<Range<usize>> = None;
+
+        for event in p {
+            if let Event::Text(ref s) = event {
+                if in_link_or_code == 0 {
+                    match (text.clone(), locate_in(md, s)) {
+                        (Some(ref prev), Some(ref range)) if prev.end == range.start => {
+                            text = Some(prev.start..range.end);
+                        }
+                        (prev, range) => {
+                            if let Some(prev) = prev {
+                                find_bare_urls(md, prev, &mut problems);
+                            }
+                            text = range;
+                        }
+                    }
+                }
+                continue;
+            }
+            if let Some(prev) = text.take() {
+                find_bare_urls(md, prev, &mut problems);
+            }
+
+            match event {
+                Event::Start(Tag::Link(..)) | Event::Start(Tag::Image(..)) |
+                Event::Start(Tag::Code) | Event::Start(Tag::CodeBlock(_)) => {
+                    in_link_or_code += 1;
+                }
+                Event::End(Tag::Link(..)) | Event::End(Tag::Image(..)) |
+                Event::End(Tag::Code) | Event::End(Tag::CodeBlock(_)) => {
+                    in_link_or_code -= 1;
+                }
+                Event::Html(ref s) | Event::InlineHtml(ref s) => {
+                    if let Some(range) = locate_in(md, s) {
+                        check_html_tags(md, range, &mut tags, &mut problems);
+                    }
+                }
+                Event::FootnoteReference(ref label) => {
+                    if let Some(mut range) = locate_in(md, label) {
+                        if md[..range.start].ends_with("[^") && md[range.end..].starts_with(']') {
+                            range = range.start - 2..range.end + 1;
+                        }
+                        footnote_references.push((label.to_string(), range));
+                    }
+                }
+                Event::Start(Tag::FootnoteDefinition(ref label)) => {
+                    footnote_definitions.push(label.to_lowercase());
+                }
+                _ => {}
+            }
+        }
+        if let Some(prev) = text.take() {
+            find_bare_urls(md, prev, &mut problems);
+        }
+
+        for (name, range) in tags {
+            problems.push((MarkdownProblem::UnclosedHtmlTag(name), range));
+        }
+        for (label, range) in footnote_references {
+            if !footnote_definitions.contains(&label.to_lowercase()) {
+                problems.push((MarkdownProblem::BrokenFootnoteReference(label), range));
+            }
+        }
+    }
+    for (label, range) in broken_footnotes.into_inner() {
+        problems.push((MarkdownProblem::BrokenFootnoteReference(label), range));
+    }
+
+    if let Some(range) = unclosed_code_fence(md) {
+        problems.push((MarkdownProblem::UnclosedCodeBlock, range));
+    }
+
+    problems.sort_by_key(|&(_, ref range)| range.start);
+    problems
+}
+
+/// Matches the HTML tags in the `range` of `md` against the `open` ones, reporting those which
+/// close a tag that isn't open and those left open by a closing tag.
+fn check_html_tags(md: &str,
+                   range: Range<usize>,
+                   open: &mut Vec<(String, Range<usize>)>,
+                   problems: &mut Vec<(MarkdownProblem, Range<usize>)>) {
+    let html = &md[range.clone()];
+    let mut pos = 0;
+    while let Some(start) = html[pos..].find('<').map(|start| pos + start) {
+        let rest = &html[start..];
+        if rest.starts_with("<!--") {
+            pos = rest.find("-->").map_or(html.len(), |end| start + end + 3);
+            continue;
+        }
+        let end = match rest.find('>') {
+            Some(end) => start + end + 1,
+            None => break,
+        };
+        pos = end;
+
+        let tag = &html[start + 1..end - 1];
+        let (closing, tag) = if tag.starts_with('/') { (true, &tag[1..]) } else { (false, tag) };
+        let name_len = tag.find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+                          .unwrap_or(tag.len());
+        if name_len == 0 || !tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            // Not a tag, like `<!DOCTYPE>` or `<?...>`.
+            continue;
+        }
+        let name = &tag[..name_len];
+        let tag_range = range.start + start..range.start + end;
+
+        if closing {
+            let index = open.iter().rposition(|&(ref open_name, _)| {
+                open_name.eq_ignore_ascii_case(name)
+            });
+            match index {
+                Some(index) => {
+                    for (unclosed, unclosed_range) in open.drain(index + 1..) {
+                        problems.push((MarkdownProblem::UnclosedHtmlTag(unclosed), unclosed_range));
+                    }
+                    open.pop();
+                }
+                None => {
+                    problems.push((MarkdownProblem::UnopenedHtmlTag(name.to_owned()), tag_range));
+                }
+            }
+        } else if !tag.ends_with('/') &&
+                  !VOID_HTML_ELEMENTS.contains(&&*name.to_ascii_lowercase()) {
+            open.push((name.to_owned(), tag_range));
+        }
+    }
+}
+
+/// Reports the URLs in the `range` of `md`, which is text outside of links and code.
+fn find_bare_urls(md: &str,
+                  range: Range<usize>,
+                  problems: &mut Vec<(MarkdownProblem, Range<usize>)>) {
+    let text = &md[range.clone()];
+    let mut pos = 0;
+    while let Some(start) = text[pos..].find("http").map(|start| pos + start) {
+        let rest = &text[start..];
+        pos = start + "http".len();
+        if !rest.starts_with("http://") && !rest.starts_with("https://") {
+            continue;
+        }
+        if text[..start].ends_with(|c: char| c.is_alphanumeric()) {
+            continue;
+        }
+        let len = rest.find(|c: char| c.is_whitespace() || c == '<' || c == '>' || c == '"')
+                      .unwrap_or(rest.len());
+        let mut url = &rest[..len];
+        // Leave out the punctuation following the URL, and the closing parenthesis around it.
+        loop {
+            if url.ends_with(|c: char| ".,;:!?'*".contains(c)) ||
+                (url.ends_with(')') && url.matches(')').count() > url.matches('(').count()) {
+                url = &url[..url.len() - 1];
+            } else {
+                break;
+            }
+        }
+        pos = start + len;
+        if url.ends_with("://") {
+            continue;
+        }
+        problems.push((MarkdownProblem::BareUrl(url.to_owned()),
+                       range.start + start..range.start + start + url.len()));
+    }
+}
+
+/// Returns the range of the fence which opens a code block of `md` and is never closed.
+fn unclosed_code_fence(md: &str) -> Option<Range<usize>> {
+    // The character and the length of the open fence, and its range.
+    let mut fence: Option<(char, usize, Range<usize>)> = None;
+    let mut offset = 0;
+    for line in md.split('\n') {
+        let line_start = offset;
+        offset += line.len() + 1;
+
+        let trimmed = line.trim_start();
+        let fence_char = match trimmed.chars().next() {
+            Some(c) if c == '`' || c == '~' => c,
+            _ => continue,
+        };
+        let len = trimmed.chars().take_while(|&c| c == fence_char).count();
+        if len < 3 {
+            continue;
+        }
+        let info = &trimmed[len..];
+        match fence {
+            None => {
+                // The info string of a backtick fence can't contain backticks.
+                if fence_char == '`' && info.contains('`') {
+                    continue;
+                }
+                let start = line_start + line.len() - trimmed.len();
+                fence = Some((fence_char, len, start..line_start + line.trim_end().len()));
+            }
+            Some((open_char, open_len, _)) => {
+                if fence_char == open_char && len >= open_len && info.trim().is_empty() {
+                    fence = None;
+                }
+            }
+        }
+    }
+    fence.map(|(_, _, range)| range)
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct IdMap {
     map: FxHashMap<String, usize>,
@@ -970,6 +1236,7 @@ fn test_unique_id() {
 #[cfg(test)]
 mod tests {
     use super::{ErrorCodes, LangString, Markdown, MarkdownHtml, IdMap};
+    use super::{MarkdownProblem, check_html_tags, find_bare_urls, unclosed_code_fence};
     use super::plain_summary_line;
     use std::cell::RefCell;
     use syntax::edition::Edition;
@@ -1091,4 +1358,37 @@ mod tests {
         t("Struct<'a, T>", "<p>Struct&lt;'a, T&gt;</p>\n");
         t("Struct<br>", "<p>Struct&lt;br&gt;</p>\n");
     }
+
+    #[test]
+    fn test_check_html_tags() {
+        let md = "<div><b>x</i></div> <br> <img/> <!-- <p> --> Vec<T> </span>";
+        let mut open = vec![];
+        let mut problems = vec![];
+        check_html_tags(md, 0..md.len(), &mut open, &mut problems);
+        assert_eq!(problems, vec![
+            (MarkdownProblem::UnopenedHtmlTag("i".to_owned()), 9..13),
+            (MarkdownProblem::UnclosedHtmlTag("b".to_owned()), 5..8),
+            (MarkdownProblem::UnopenedHtmlTag("span".to_owned()), 52..59),
+        ]);
+        assert_eq!(open, vec![("T".to_owned(), 48..51)]);
+    }
+
+    #[test]
+    fn test_find_bare_urls() {
+        let md = "See https://example.com/a_(b) and (https://x.org/y). Not http:// or xhttp://a.";
+        let mut problems = vec![];
+        find_bare_urls(md, 0..md.len(), &mut problems);
+        assert_eq!(problems, vec![
+            (MarkdownProblem::BareUrl("https://example.com/a_(b)".to_owned()), 4..29),
+            (MarkdownProblem::BareUrl("https://x.org/y".to_owned()), 35..50),
+        ]);
+    }
+
+    #[test]
+    fn test_unclosed_code_fence() {
+        assert_eq!(unclosed_code_fence("```rust\nfoo\n```\n"), None);
+        assert_eq!(unclosed_code_fence("```rust\nfoo\n"), Some(0..7));
+        assert_eq!(unclosed_code_fence("````\n```\n"), Some(0..4));
+        assert_eq!(unclosed_code_fence("~~~\n```\n~~~ \nx\n  ``` text\n"), Some(17..25));
+    }
 }
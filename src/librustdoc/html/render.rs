@@ -77,6 +77,8 @@ use html::format::fmt_impl_for_trait_page;
 use html::item_type::ItemType;
 use html::markdown::{self, Markdown, MarkdownHtml, MarkdownSummaryLine, ErrorCodes, IdMap};
 use html::{highlight, layout, static_files};
+use html::span_map::{LinkFromSrc, SpanMap};
+use scrape_examples::{AllCallLocations, CallData, CallLocation, FnCallLocations};
 
 use minifier;
 
@@ -140,6 +142,8 @@ struct SharedContext {
     /// Suffix to be added on resource files (if suffix is "-v2" then "light.css" becomes
     /// "light-v2.css").
     pub resource_suffix: String,
+    /// The calls to the items of the crate found in its examples, shown on their pages.
+    pub call_locations: FxHashMap<DefId, FnCallLocations>,
 }
 
 impl SharedContext {
@@ -324,6 +328,9 @@ pub struct Cache {
     stack: Vec<String>,
     parent_stack: Vec<DefId>,
     parent_is_trait_impl: bool,
+    /// The type of the impl whose items are being crawled, which `Self` stands for in their
+    /// signatures.
+    impl_self_type: Option<clean::Type>,
     search_index: Vec<IndexItem>,
     stripped_mod: bool,
     deref_trait_did: Option<DefId>,
@@ -336,7 +343,7 @@ pub struct Cache {
     // then the fully qualified name of the structure isn't presented in `paths`
     // yet when its implementation methods are being indexed. Caches such methods
     // and their parent id here and indexes them at the end of crate parsing.
-    orphan_impl_items: Vec<(DefId, clean::Item)>,
+    orphan_impl_items: Vec<(DefId, clean::Item, Option<IndexItemFunctionType>)>,
 
     // Similarly to `orphan_impl_items`, sometimes trait impls are picked up
     // even though the trait itself is not exported. This can happen if a trait
@@ -364,6 +371,12 @@ pub struct RenderInfo {
     pub deref_trait_did: Option<DefId>,
     pub deref_mut_trait_did: Option<DefId>,
     pub owned_box_did: Option<DefId>,
+    /// The links from the source to the definitions, with `--generate-link-to-definition`.
+    pub span_map: SpanMap,
+    /// The calls scraped from the crate, with `--scrape-examples-output-path`.
+    pub scraped_calls: AllCallLocations,
+    /// The calls to the items of the crate found in its examples, with `--with-examples`.
+    pub call_locations: FxHashMap<DefId, FnCallLocations>,
 }
 
 /// Helper struct to render all source code to HTML pages
@@ -372,11 +385,26 @@ struct SourceCollector<'a> {
 
     /// Root destination to place all HTML output into
     dst: PathBuf,
+
+    /// The links from the identifiers in the sources to their definitions.
+    span_map: SpanMap,
 }
 
 /// Wrapper struct to render the source code of a file. This will do things like
-/// adding line numbers to the left-hand side.
-struct Source<'a>(&'a str);
+/// adding line numbers to the left-hand side, linking identifiers to the given
+/// URLs by their byte range in the file, and listing the references to the
+/// definitions of the file below it.
+struct Source<'a>(&'a str, &'a FxHashMap<(u32, u32), String>, &'a [SourceReferences]);
+
+/// The references to a definition of a source file, listed below the source.
+struct SourceReferences {
+    /// The id of the list, which the name of the definition links to.
+    id: String,
+    name: String,
+    line: usize,
+    /// The URL of the line of each reference, and the file and line it is on.
+    references: Vec<(String, String)>,
+}
 
 // Helper structs for rendering items/sidebars and carrying along contextual
 // information
@@ -390,7 +418,7 @@ struct Item<'a> {
 struct Sidebar<'a> { cx: &'a Context, item: &'a clean::Item, }
 
 /// Struct representing one entry in the JS search index. These are all emitted
-/// by hand to a large JS file at the end of cache-creation.
+/// by hand to the search index shard of the crate at the end of cache-creation.
 #[derive(Debug)]
 struct IndexItem {
     ty: ItemType,
@@ -402,41 +430,34 @@ struct IndexItem {
     search_type: Option<IndexItemFunctionType>,
 }
 
-impl ToJson for IndexItem {
-    fn to_json(&self) -> Json {
-        assert_eq!(self.parent.is_some(), self.parent_idx.is_some());
-
-        let mut data = Vec::with_capacity(6);
-        data.push((self.ty as usize).to_json());
-        data.push(self.name.to_json());
-        data.push(self.path.to_json());
-        data.push(self.desc.to_json());
-        data.push(self.parent_idx.to_json());
-        data.push(self.search_type.to_json());
-
-        Json::Array(data)
-    }
-}
-
 /// A type used for the search index.
+///
+/// References and pointers are replaced by the type they point to, and `Self` by the type of
+/// the impl, so that a signature can be searched for the way it is usually thought of.
 #[derive(Debug)]
-struct Type {
-    name: Option<String>,
-    generics: Option<Vec<String>>,
+enum Type {
+    /// A generic parameter of the function or of its parent, numbered in order of appearance so
+    /// that the search can unify the types it stands for.
+    Generic(usize),
+    /// A type with its name in lowercase, and its generic arguments.
+    Named(String, Vec<Type>),
+    /// A type that can't be searched for, like an associated type projection.
+    Unknown,
 }
 
 impl ToJson for Type {
     fn to_json(&self) -> Json {
-        match self.name {
-            Some(ref name) => {
+        match *self {
+            Type::Generic(index) => Json::Array(vec![index.to_json()]),
+            Type::Named(ref name, ref generics) => {
                 let mut data = Vec::with_capacity(2);
                 data.push(name.to_json());
-                if let Some(ref generics) = self.generics {
+                if !generics.is_empty() {
                     data.push(generics.to_json());
                 }
                 Json::Array(data)
             }
-            None => Json::Null
+            Type::Unknown => Json::Null,
         }
     }
 }
@@ -450,17 +471,12 @@ struct IndexItemFunctionType {
 
 impl ToJson for IndexItemFunctionType {
     fn to_json(&self) -> Json {
-        // If we couldn't figure out a type, just write `null`.
-        if self.inputs.iter().chain(self.output.iter()).any(|ref i| i.name.is_none()) {
-            Json::Null
-        } else {
-            let mut data = Vec::with_capacity(2);
-            data.push(self.inputs.to_json());
-            if let Some(ref output) = self.output {
-                data.push(output.to_json());
-            }
-            Json::Array(data)
+        let mut data = Vec::with_capacity(2);
+        data.push(self.inputs.to_json());
+        if let Some(ref output) = self.output {
+            data.push(output.to_json());
         }
+        Json::Array(data)
     }
 }
 
@@ -533,6 +549,7 @@ pub fn run(mut krate: clean::Crate,
         sort_modules_alphabetically,
         themes,
         resource_suffix,
+        call_locations: Default::default(),
     };
 
     // If user passed in `--playground-url` arg, we fill in crate name here
@@ -572,15 +589,6 @@ pub fn run(mut krate: clean::Crate,
     }
     let dst = output;
     try_err!(fs::create_dir_all(&dst), &dst);
-    krate = render_sources(&dst, &mut scx, krate)?;
-    let cx = Context {
-        current: Vec::new(),
-        dst,
-        render_redirect_pages: false,
-        codes: ErrorCodes::from(UnstableFeatures::from_environment().is_nightly_build()),
-        id_map: Rc::new(RefCell::new(id_map)),
-        shared: Arc::new(scx),
-    };
 
     // Crawl the crate to build various caches used for the output
     let RenderInfo {
@@ -592,7 +600,11 @@ pub fn run(mut krate: clean::Crate,
         deref_trait_did,
         deref_mut_trait_did,
         owned_box_did,
+        span_map,
+        scraped_calls: _,
+        call_locations,
     } = renderinfo;
+    scx.call_locations = call_locations;
 
     let external_paths = external_paths.into_iter()
         .map(|(k, (v, t))| (k, (v, ItemType::from(t))))
@@ -608,6 +620,7 @@ pub fn run(mut krate: clean::Crate,
         parent_stack: Vec::new(),
         search_index: Vec::new(),
         parent_is_trait_impl: false,
+        impl_self_type: None,
         extern_locations: Default::default(),
         primitive_locations: Default::default(),
         stripped_mod: false,
@@ -635,7 +648,7 @@ pub fn run(mut krate: clean::Crate,
         };
         let extern_url = extern_html_root_urls.get(&e.name).map(|u| &**u);
         cache.extern_locations.insert(n, (e.name.clone(), src_root,
-                                          extern_location(e, extern_url, &cx.dst)));
+                                          extern_location(e, extern_url, &dst)));
 
         let did = DefId { krate: n, index: CRATE_DEF_INDEX };
         cache.external_paths.insert(did, (vec![e.name.to_string()], ItemType::Module));
@@ -666,7 +679,7 @@ pub fn run(mut krate: clean::Crate,
     }
 
     // Build our search index
-    let index = build_index(&krate, &mut cache);
+    let (index_entry, index_shard) = build_index(&krate, &mut cache);
 
     // Freeze the cache now that the index has been built. Put an Arc into TLS
     // for future parallelization opportunities
@@ -674,25 +687,56 @@ pub fn run(mut krate: clean::Crate,
     CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
     CURRENT_LOCATION_KEY.with(|s| s.borrow_mut().clear());
 
-    write_shared(&cx, &krate, &*cache, index, &md_opts, diag)?;
+    // The sources are rendered once the cache is built, so that they can link to the
+    // documentation of other crates.
+    krate = render_sources(&dst, &mut scx, krate, span_map)?;
+    let cx = Context {
+        current: Vec::new(),
+        dst,
+        render_redirect_pages: false,
+        codes: ErrorCodes::from(UnstableFeatures::from_environment().is_nightly_build()),
+        id_map: Rc::new(RefCell::new(id_map)),
+        shared: Arc::new(scx),
+    };
+
+    write_shared(&cx, &krate, &*cache, index_entry, index_shard, &md_opts, diag)?;
 
     // And finally render the whole crate's documentation
     cx.krate(krate)
 }
 
 /// Build the search index from the collected metadata
-fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
+///
+/// Returns the entry of the crate in `search-index.js`, which only lists the crates, and the
+/// shard of the index with the items of the crate, which is only loaded once the user searches.
+///
+/// The items are encoded by column in the shard, to keep it small:
+///
+/// * `t` is a string with the item type of each item, as the letter `A` plus its number,
+/// * `n` is the name of each item,
+/// * `q` is the path of the items, as `[index, path]` pairs for each item whose path differs
+///   from the one of the item before it,
+/// * `d` is the description of each item,
+/// * `i` is one plus the index in `p` of the parent of each item, or zero if it has none,
+/// * `f` is the function type of each item, or null if it isn't a function,
+/// * `p` is the item type and the name of each parent.
+fn build_index(krate: &clean::Crate, cache: &mut Cache) -> (String, String) {
     let mut nodeid_to_pathid = FxHashMap::default();
-    let mut crate_items = Vec::with_capacity(cache.search_index.len());
+    let mut crate_types = String::with_capacity(cache.search_index.len());
+    let mut crate_names = Vec::with_capacity(cache.search_index.len());
+    let mut crate_item_paths = Vec::new();
+    let mut crate_descs = Vec::with_capacity(cache.search_index.len());
+    let mut crate_parents = Vec::with_capacity(cache.search_index.len());
+    let mut crate_fn_types = Vec::with_capacity(cache.search_index.len());
     let mut crate_paths = Vec::<Json>::new();
 
     let Cache { ref mut search_index,
-                ref orphan_impl_items,
+                ref mut orphan_impl_items,
                 ref mut paths, .. } = *cache;
 
     // Attach all orphan items to the type's definition if the type
     // has since been learned.
-    for &(did, ref item) in orphan_impl_items {
+    for (did, item, search_type) in orphan_impl_items.drain(..) {
         if let Some(&(ref fqp, _)) = paths.get(&did) {
             search_index.push(IndexItem {
                 ty: item.type_(),
@@ -701,7 +745,7 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
                 desc: plain_summary_line(item.doc_value()),
                 parent: Some(did),
                 parent_idx: None,
-                search_type: get_index_search_type(&item),
+                search_type,
             });
         }
     }
@@ -711,7 +755,7 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
     let mut lastpath = String::new();
     let mut lastpathid = 0usize;
 
-    for item in search_index {
+    for (index, item) in search_index.iter_mut().enumerate() {
         item.parent_idx = item.parent.map(|nodeid| {
             if nodeid_to_pathid.contains_key(&nodeid) {
                 *nodeid_to_pathid.get(&nodeid).unwrap()
@@ -726,35 +770,46 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
             }
         });
 
-        // Omit the parent path if it is same to that of the prior item.
-        if lastpath == item.path {
-            item.path.clear();
-        } else {
+        // Only record the parent path where it differs from that of the prior item.
+        if index == 0 || lastpath != item.path {
             lastpath = item.path.clone();
+            crate_item_paths.push((index, item.path.clone()).to_json());
         }
-        crate_items.push(item.to_json());
+        crate_types.push((b'A' + item.ty as u8) as char);
+        crate_names.push(item.name.to_json());
+        crate_descs.push(item.desc.to_json());
+        crate_parents.push(item.parent_idx.map_or(0, |idx| idx + 1).to_json());
+        crate_fn_types.push(item.search_type.to_json());
     }
 
     let crate_doc = krate.module.as_ref().map(|module| {
         plain_summary_line(module.doc_value())
     }).unwrap_or(String::new());
 
+    let mut crate_entry = BTreeMap::new();
+    crate_entry.insert("doc".to_owned(), Json::String(crate_doc.clone()));
+
     let mut crate_data = BTreeMap::new();
     crate_data.insert("doc".to_owned(), Json::String(crate_doc));
-    crate_data.insert("items".to_owned(), Json::Array(crate_items));
-    crate_data.insert("paths".to_owned(), Json::Array(crate_paths));
+    crate_data.insert("t".to_owned(), Json::String(crate_types));
+    crate_data.insert("n".to_owned(), Json::Array(crate_names));
+    crate_data.insert("q".to_owned(), Json::Array(crate_item_paths));
+    crate_data.insert("d".to_owned(), Json::Array(crate_descs));
+    crate_data.insert("i".to_owned(), Json::Array(crate_parents));
+    crate_data.insert("f".to_owned(), Json::Array(crate_fn_types));
+    crate_data.insert("p".to_owned(), Json::Array(crate_paths));
 
-    // Collect the index into a string
-    format!("searchIndex[{}] = {};",
-            as_json(&krate.name),
-            Json::Object(crate_data))
+    // Collect the index into strings
+    (format!("searchIndex[{}] = {};", as_json(&krate.name), Json::Object(crate_entry)),
+     format!("addSearchShard({}, {});", as_json(&krate.name), Json::Object(crate_data)))
 }
 
 fn write_shared(
     cx: &Context,
     krate: &clean::Crate,
     cache: &Cache,
-    search_index: String,
+    search_index_entry: String,
+    search_index_shard: String,
     options: &RenderOptions,
     diag: &errors::Handler,
 ) -> Result<(), Error> {
@@ -969,10 +1024,21 @@ themePicker.onblur = handleThemeButtonsBlur;
         }
     }
 
-    // Update the search index
+    // Write the shard of the search index with the items of this crate
+    let shards = cx.dst.join("search-index");
+    try_err!(fs::create_dir_all(&shards), &shards);
+    let dst = shards.join(&format!("{}.js", krate.name));
+    {
+        let mut w = try_err!(File::create(&dst), &dst);
+        try_err!(write_minify_replacer(&mut w, &search_index_shard, options.enable_minification,
+                                       &[(minifier::js::Keyword::Null, "N")]),
+                 &dst);
+    }
+
+    // Update the list of the crates of the search index
     let dst = cx.dst.join("search-index.js");
     let (mut all_indexes, mut krates) = try_err!(collect(&dst, &krate.name, "searchIndex"), &dst);
-    all_indexes.push(search_index);
+    all_indexes.push(search_index_entry);
     // Sort the indexes by crate so the file will be generated identically even
     // with rustdoc running in parallel.
     all_indexes.sort();
@@ -1102,16 +1168,54 @@ themePicker.onblur = handleThemeButtonsBlur;
     Ok(())
 }
 
-fn render_sources(dst: &Path, scx: &mut SharedContext,
-                  krate: clean::Crate) -> Result<clean::Crate, Error> {
+fn render_sources(root: &Path, scx: &mut SharedContext,
+                  krate: clean::Crate, span_map: SpanMap) -> Result<clean::Crate, Error> {
     info!("emitting source files");
-    let dst = dst.join("src").join(&krate.name);
+    let dst = root.join("src").join(&krate.name);
     try_err!(fs::create_dir_all(&dst), &dst);
     let mut folder = SourceCollector {
         dst,
         scx,
+        span_map,
     };
-    Ok(folder.fold_crate(krate))
+    let krate = folder.fold_crate(krate);
+
+    // The definitions that the sources link to, and the references to them, can be in files
+    // without any documented items, which are not rendered otherwise.
+    if folder.scx.include_sources {
+        let mut linked_files: FxHashSet<PathBuf> = folder.span_map.links.values()
+            .flat_map(|links| links.values())
+            .filter_map(|link| match *link {
+                LinkFromSrc::Local(ref file, _) => Some(file.clone()),
+                LinkFromSrc::External(..) => None,
+            })
+            .collect();
+        linked_files.extend(folder.span_map.references.iter()
+            .flat_map(|(file, references)| {
+                references.values().flatten().map(|&(ref ref_file, _)| ref_file.clone())
+                                             .chain(Some(file.clone()))
+            }));
+        for file in linked_files {
+            let filename = FileName::Real(file);
+            if let Err(e) = folder.emit_source(&filename) {
+                println!("warning: source code was requested to be rendered, \
+                          but processing `{}` had an error: {}", filename, e);
+            }
+        }
+    }
+
+    // The examples found in the repository link to their whole source.
+    let example_files: BTreeMap<String, String> = folder.scx.call_locations.values()
+        .flat_map(|calls| calls.iter())
+        .map(|(file, data)| (file.clone(), data.url.clone()))
+        .collect();
+    for (file, url) in example_files {
+        if let Err(e) = folder.emit_example_source(root, Path::new(&file), &url) {
+            println!("warning: the source of the example `{}` could not be rendered: {}",
+                     file, e);
+        }
+    }
+    Ok(krate)
 }
 
 /// Writes the entire contents of a string to a destination, not attempting to
@@ -1153,7 +1257,7 @@ fn write_minify_replacer<W: Write>(dst: &mut W,
 /// be passed to `f` if `keep_filename` is true, and ignored otherwise.
 // FIXME (#9639): The closure should deal with &[u8] instead of &str
 // FIXME (#9639): This is too conservative, rejecting non-UTF-8 paths
-fn clean_srcpath<F>(src_root: &Path, p: &Path, keep_filename: bool, mut f: F) where
+crate fn clean_srcpath<F>(src_root: &Path, p: &Path, keep_filename: bool, mut f: F) where
     F: FnMut(&str),
 {
     // make it relative, if possible
@@ -1252,23 +1356,18 @@ impl<'a> SourceCollector<'a> {
 
         let contents = fs::read_to_string(&p)?;
 
-        // Remove the utf-8 BOM if any
-        let contents = if contents.starts_with("\u{feff}") {
-            &contents[3..]
-        } else {
-            &contents[..]
-        };
-
         // Create the intermediate directories
         let mut cur = self.dst.clone();
         let mut root_path = String::from("../../");
         let mut href = String::new();
+        let mut location = vec!["src".to_string(), self.scx.layout.krate.clone()];
         clean_srcpath(&self.scx.src_root, &p, false, |component| {
             cur.push(component);
             fs::create_dir_all(&cur).unwrap();
             root_path.push_str("../");
             href.push_str(component);
             href.push('/');
+            location.push(component.to_string());
         });
         let mut fname = p.file_name()
                          .expect("source has no filename")
@@ -1277,10 +1376,40 @@ impl<'a> SourceCollector<'a> {
         cur.push(&fname);
         href.push_str(&fname.to_string_lossy());
 
-        let mut w = BufWriter::new(File::create(&cur)?);
+        self.write_source_page(p, &contents, &cur, &root_path, location)?;
+        self.scx.local_sources.insert(p.clone(), href);
+        Ok(())
+    }
+
+    /// Renders the source page of `p`, a file of an example found in the repository, at `url`
+    /// from `root`, the root of the documentation.
+    fn emit_example_source(&mut self, root: &Path, p: &Path, url: &str) -> io::Result<()> {
+        let contents = fs::read_to_string(p)?;
+        let mut location: Vec<String> = url.split('/').map(|s| s.to_string()).collect();
+        location.pop();
+        let root_path = "../".repeat(location.len());
+        let mut cur = root.to_path_buf();
+        cur.extend(&location);
+        fs::create_dir_all(&cur)?;
+        cur.push(url.rsplit('/').next().unwrap_or(url));
+        self.write_source_page(p, &contents, &cur, &root_path, location)
+    }
+
+    /// Writes the source page of `p` to `cur`, the page being at `location` from the root of the
+    /// documentation, which `root_path` leads back to.
+    fn write_source_page(&self, p: &Path, contents: &str, cur: &Path, root_path: &str,
+                         location: Vec<String>) -> io::Result<()> {
+        // Remove the utf-8 BOM if any
+        let contents = if contents.starts_with("\u{feff}") {
+            &contents[3..]
+        } else {
+            &contents[..]
+        };
+
+        let mut w = BufWriter::new(File::create(cur)?);
         let title = format!("{} -- source", cur.file_name().unwrap()
                                                .to_string_lossy());
-        let desc = format!("Source to the Rust file `{}`.", filename);
+        let desc = format!("Source to the Rust file `{}`.", p.display());
         let page = layout::Page {
             title: &title,
             css_class: "source",
@@ -1289,14 +1418,88 @@ impl<'a> SourceCollector<'a> {
             keywords: BASIC_KEYWORDS,
             resource_suffix: &self.scx.resource_suffix,
         };
+        let links = self.source_links(p, root_path, location);
+        let references = self.source_references(p, contents, root_path);
+        // The names of the definitions link to the list of their references.
+        let links: FxHashMap<_, _> = links.into_iter()
+            .chain(references.iter().map(|&(range, ref refs)| (range, format!("#{}", refs.id))))
+            .collect();
+        let references: Vec<_> = references.into_iter().map(|(_, refs)| refs).collect();
         layout::render(&mut w, &self.scx.layout,
-                       &page, &(""), &Source(contents),
+                       &page, &(""), &Source(contents, &links, &references),
                        self.scx.css_file_extension.is_some(),
                        &self.scx.themes)?;
         w.flush()?;
-        self.scx.local_sources.insert(p.clone(), href);
         Ok(())
     }
+
+    /// Returns the URLs that the identifiers in the source file `p` link to, by their byte range
+    /// in the file. The source page of `p` is at `location`, `root_path` being the way back to
+    /// the root of the documentation.
+    fn source_links(&self, p: &Path, root_path: &str,
+                    location: Vec<String>) -> FxHashMap<(u32, u32), String> {
+        let links = match self.span_map.links.get(p) {
+            Some(links) => links,
+            None => return FxHashMap::default(),
+        };
+        // `href` makes the links to the documentation relative to the current location.
+        CURRENT_LOCATION_KEY.with(|s| *s.borrow_mut() = location);
+        let links = links.iter().filter_map(|(&range, link)| {
+            let url = match *link {
+                LinkFromSrc::Local(ref file, line) => self.source_url(root_path, file, line),
+                LinkFromSrc::External(def_id, ref anchor) => {
+                    let mut url = href(def_id)?.0;
+                    if let Some(ref anchor) = *anchor {
+                        url.push('#');
+                        url.push_str(anchor);
+                    }
+                    url
+                }
+            };
+            Some((range, url))
+        }).collect();
+        CURRENT_LOCATION_KEY.with(|s| s.borrow_mut().clear());
+        links
+    }
+
+    /// Returns the references to the definitions of the source file `p`, whose contents are
+    /// `contents`, by the byte range of the names of the definitions, sorted by position.
+    fn source_references(&self, p: &Path, contents: &str,
+                         root_path: &str) -> Vec<((u32, u32), SourceReferences)> {
+        let references = match self.span_map.references.get(p) {
+            Some(references) => references,
+            None => return Vec::new(),
+        };
+        let mut references: Vec<_> = references.iter().filter_map(|(&(lo, hi), refs)| {
+            let name = contents.get(lo as usize..hi as usize)?;
+            let line = contents[..lo as usize].matches('\n').count() + 1;
+            let refs = refs.iter().map(|&(ref file, line)| {
+                let file_name = file.strip_prefix(&self.scx.src_root).unwrap_or(file);
+                (self.source_url(root_path, file, line),
+                 format!("{}:{}", file_name.display(), line))
+            }).collect();
+            Some(((lo, hi), SourceReferences {
+                id: format!("references-{}", lo),
+                name: name.to_string(),
+                line,
+                references: refs,
+            }))
+        }).collect();
+        references.sort_by_key(|&(range, _)| range);
+        references
+    }
+
+    /// Returns the URL of the line `line` of the source page of `file`, from `root_path`.
+    fn source_url(&self, root_path: &str, file: &Path, line: usize) -> String {
+        let mut url = format!("{}src/{}/", root_path, self.scx.layout.krate);
+        clean_srcpath(&self.scx.src_root, file, true, |component| {
+            url.push_str(component);
+            url.push('/');
+        });
+        url.pop();
+        url.push_str(&format!(".html#{}", line));
+        url
+    }
 }
 
 impl DocFolder for Cache {
@@ -1404,14 +1607,17 @@ impl DocFolder for Cache {
                             desc: plain_summary_line(item.doc_value()),
                             parent,
                             parent_idx: None,
-                            search_type: get_index_search_type(&item),
+                            search_type: get_index_search_type(&item,
+                                                               self.impl_self_type.as_ref()),
                         });
                     }
                 }
                 (Some(parent), None) if is_inherent_impl_item => {
                     // We have a parent, but we don't know where they're
                     // defined yet. Wait for later to index this item.
-                    self.orphan_impl_items.push((parent, item.clone()));
+                    let search_type = get_index_search_type(&item,
+                                                            self.impl_self_type.as_ref());
+                    self.orphan_impl_items.push((parent, item.clone(), search_type));
                 }
                 _ => {}
             }
@@ -1467,6 +1673,7 @@ impl DocFolder for Cache {
 
         // Maintain the parent stack
         let orig_parent_is_trait_impl = self.parent_is_trait_impl;
+        let orig_impl_self_type = self.impl_self_type.take();
         let parent_pushed = match item.inner {
             clean::TraitItem(..) | clean::EnumItem(..) | clean::ForeignTypeItem |
             clean::StructItem(..) | clean::UnionItem(..) => {
@@ -1476,6 +1683,7 @@ impl DocFolder for Cache {
             }
             clean::ImplItem(ref i) => {
                 self.parent_is_trait_impl = i.trait_.is_some();
+                self.impl_self_type = Some(i.for_.clone());
                 match i.for_ {
                     clean::ResolvedPath{ did, .. } => {
                         self.parent_stack.push(did);
@@ -1556,6 +1764,7 @@ impl DocFolder for Cache {
         if parent_pushed { self.parent_stack.pop().unwrap(); }
         self.stripped_mod = orig_stripped_mod;
         self.parent_is_trait_impl = orig_parent_is_trait_impl;
+        self.impl_self_type = orig_impl_self_type;
         ret
     }
 }
@@ -1596,7 +1805,8 @@ impl<'a> Cache {
                                 desc: plain_summary_line(item.doc_value()),
                                 parent: None,
                                 parent_idx: None,
-                                search_type: get_index_search_type(&item),
+                                search_type: get_index_search_type(&item,
+                                                                   self.impl_self_type.as_ref()),
                             });
             }
         }
@@ -2319,6 +2529,7 @@ fn document(w: &mut fmt::Formatter, cx: &Context, item: &clean::Item) -> fmt::Re
     }
     document_stability(w, cx, item, false)?;
     document_full(w, item, cx, "", false)?;
+    render_call_locations(w, cx, item, false)?;
     Ok(())
 }
 
@@ -2369,6 +2580,97 @@ fn document_full(w: &mut fmt::Formatter, item: &clean::Item,
     Ok(())
 }
 
+/// How many of the examples found in the repository are shown on the page of an item, the
+/// others being listed as links.
+const MAX_FULL_EXAMPLES: usize = 3;
+
+/// Renders the calls to `item` found in the examples of the crate, with `--with-examples`.
+fn render_call_locations(w: &mut fmt::Formatter, cx: &Context, item: &clean::Item,
+                         is_hidden: bool) -> fmt::Result {
+    struct Example<'a> {
+        file: &'a str,
+        data: &'a CallData,
+        enclosing_item: (u32, u32),
+        calls: Vec<&'a CallLocation>,
+    }
+
+    let call_locations = match cx.shared.call_locations.get(&item.def_id) {
+        Some(call_locations) => call_locations,
+        None => return Ok(()),
+    };
+    // An item with several calls is shown once, and the smaller it is the better an example it
+    // makes.
+    let mut examples: Vec<Example> = Vec::new();
+    for (file, data) in call_locations {
+        for call in &data.locations {
+            let found = examples.iter().position(|example| {
+                example.file == file && example.enclosing_item == call.enclosing_item
+            });
+            match found {
+                Some(i) => examples[i].calls.push(call),
+                None => examples.push(Example {
+                    file,
+                    data,
+                    enclosing_item: call.enclosing_item,
+                    calls: vec![call],
+                }),
+            }
+        }
+    }
+    examples.sort_by(|a, b| {
+        let key = |example: &Example| {
+            (example.enclosing_item.1 - example.enclosing_item.0, example.calls[0].call_lines)
+        };
+        key(a).cmp(&key(b)).then_with(|| a.data.display_name.cmp(&b.data.display_name))
+    });
+
+    let root_path = cx.root_path();
+    let call_href = |data: &CallData, call: &CallLocation| {
+        format!("{}{}#{}-{}", root_path, data.url, call.call_lines.0, call.call_lines.1)
+    };
+    write!(w, "<div class='docblock scraped-examples{}'>", if is_hidden { " hidden" } else { "" })?;
+    write!(w, "<h5 class='scraped-examples-title'>Examples found in repository</h5>")?;
+    let mut shown = 0;
+    let mut more_examples = Vec::new();
+    for example in &examples {
+        let contents = fs::read_to_string(example.file).ok();
+        let (lo, hi) = example.enclosing_item;
+        let code = contents.as_ref().and_then(|contents| {
+            contents.trim_left_matches('\u{feff}').get(lo as usize..hi as usize)
+        });
+        let code = match code {
+            Some(code) if shown < MAX_FULL_EXAMPLES => code,
+            _ => {
+                more_examples.push(example);
+                continue;
+            }
+        };
+        let links = example.calls.iter().map(|call| {
+            ((call.call_ident.0 - lo, call.call_ident.1 - lo), call_href(example.data, call))
+        }).collect();
+        write!(w, "<div class='scraped-example'>\
+                       <div class='scraped-example-title'>{} (<a href='{}'>line {}</a>)</div>\
+                       {}\
+                   </div>",
+               Escape(&example.data.display_name),
+               call_href(example.data, example.calls[0]),
+               example.calls[0].call_lines.0,
+               highlight::render_source_with_links(code, &links))?;
+        shown += 1;
+    }
+    if !more_examples.is_empty() {
+        write!(w, "<p class='scraped-examples-more'>More examples:</p><ul>")?;
+        for example in more_examples {
+            write!(w, "<li><a href='{}'>{} (line {})</a></li>",
+                   call_href(example.data, example.calls[0]),
+                   Escape(&example.data.display_name),
+                   example.calls[0].call_lines.0)?;
+        }
+        write!(w, "</ul>")?;
+    }
+    write!(w, "</div>")
+}
+
 fn document_stability(w: &mut fmt::Formatter, cx: &Context, item: &clean::Item,
                       is_hidden: bool) -> fmt::Result {
     let stabilities = short_stability(item, cx, true);
@@ -4041,6 +4343,7 @@ fn render_impl(w: &mut fmt::Formatter, cx: &Context, i: &Impl, link: AssocItemLi
                     document_stability(w, cx, item, is_hidden)?;
                     if show_def_docs {
                         document_full(w, item, cx, "", is_hidden)?;
+                        render_call_locations(w, cx, item, is_hidden)?;
                     }
                 }
             } else {
@@ -4676,7 +4979,7 @@ fn sidebar_foreign_type(fmt: &mut fmt::Formatter, it: &clean::Item) -> fmt::Resu
 
 impl<'a> fmt::Display for Source<'a> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let Source(s) = *self;
+        let Source(s, links, references) = *self;
         let lines = s.lines().count();
         let mut cols = 0;
         let mut tmp = lines;
@@ -4689,8 +4992,20 @@ impl<'a> fmt::Display for Source<'a> {
             write!(fmt, "<span id=\"{0}\">{0:1$}</span>\n", i, cols)?;
         }
         write!(fmt, "</pre>")?;
-        write!(fmt, "{}",
-               highlight::render_with_highlighting(s, None, None, None))?;
+        write!(fmt, "{}", highlight::render_source_with_links(s, links))?;
+        if !references.is_empty() {
+            write!(fmt, "<div class=\"src-references\">")?;
+            for refs in references {
+                write!(fmt, "<h3 id=\"{}\">References to <a href=\"#{}\"><code>{}</code></a>\
+                             </h3><ul>",
+                       refs.id, refs.line, Escape(&refs.name))?;
+                for &(ref url, ref location) in &refs.references {
+                    write!(fmt, "<li><a href=\"{}\">{}</a></li>", Escape(url), Escape(location))?;
+                }
+                write!(fmt, "</ul>")?;
+            }
+            write!(fmt, "</div>")?;
+        }
         Ok(())
     }
 }
@@ -4758,7 +5073,8 @@ fn make_item_keywords(it: &clean::Item) -> String {
     format!("{}, {}", BASIC_KEYWORDS, it.name.as_ref().unwrap())
 }
 
-fn get_index_search_type(item: &clean::Item) -> Option<IndexItemFunctionType> {
+fn get_index_search_type(item: &clean::Item,
+                         self_type: Option<&clean::Type>) -> Option<IndexItemFunctionType> {
     let decl = match item.inner {
         clean::FunctionItem(ref f) => &f.decl,
         clean::MethodItem(ref m) => &m.decl,
@@ -4766,21 +5082,87 @@ fn get_index_search_type(item: &clean::Item) -> Option<IndexItemFunctionType> {
         _ => return None
     };
 
-    let inputs = decl.inputs.values.iter().map(|arg| get_index_type(&arg.type_)).collect();
+    let mut generics = Vec::new();
+    let inputs = decl.inputs.values.iter()
+                                   .map(|arg| get_index_type(&arg.type_, self_type, &mut generics))
+                                   .collect();
     let output = match decl.output {
-        clean::FunctionRetTy::Return(ref return_type) => Some(get_index_type(return_type)),
+        clean::FunctionRetTy::Return(ref return_type) => {
+            Some(get_index_type(return_type, self_type, &mut generics))
+        }
         _ => None
     };
 
     Some(IndexItemFunctionType { inputs: inputs, output: output })
 }
 
-fn get_index_type(clean_type: &clean::Type) -> Type {
-    let t = Type {
-        name: get_index_type_name(clean_type, true).map(|s| s.to_ascii_lowercase()),
-        generics: get_generics(clean_type),
-    };
-    t
+/// Normalizes `clean_type` for the search index. `generics` holds the names of the generic
+/// parameters seen so far in the signature, in order, which gives their numbers.
+fn get_index_type(clean_type: &clean::Type,
+                  self_type: Option<&clean::Type>,
+                  generics: &mut Vec<String>) -> Type {
+    let named = |name: &str, args: Vec<Type>| Type::Named(name.to_ascii_lowercase(), args);
+    match *clean_type {
+        clean::ResolvedPath { ref path, is_generic: false, .. } => {
+            let segment = match path.segments.last() {
+                Some(segment) => segment,
+                None => return Type::Unknown,
+            };
+            let args = match segment.args {
+                clean::GenericArgs::AngleBracketed { ref types, ref bindings, .. } => {
+                    types.iter()
+                         .chain(bindings.iter().map(|binding| &binding.ty))
+                         .map(|ty| get_index_type(ty, self_type, generics))
+                         .collect()
+                }
+                clean::GenericArgs::Parenthesized { ref inputs, ref output } => {
+                    inputs.iter()
+                          .chain(output.iter())
+                          .map(|ty| get_index_type(ty, self_type, generics))
+                          .collect()
+                }
+            };
+            named(&segment.name, args)
+        }
+        clean::Generic(ref name) => {
+            match self_type {
+                Some(self_type) if name == "Self" => {
+                    return get_index_type(self_type, None, generics);
+                }
+                _ => {}
+            }
+            match generics.iter().position(|g| g == name) {
+                Some(index) => Type::Generic(index),
+                None => {
+                    generics.push(name.clone());
+                    Type::Generic(generics.len() - 1)
+                }
+            }
+        }
+        clean::Primitive(p) => named(p.as_str(), Vec::new()),
+        clean::Tuple(ref types) if types.is_empty() => named("unit", Vec::new()),
+        clean::Tuple(ref types) => {
+            let types = types.iter().map(|ty| get_index_type(ty, self_type, generics)).collect();
+            named("tuple", types)
+        }
+        clean::Slice(ref ty) => named("slice", vec![get_index_type(ty, self_type, generics)]),
+        clean::Array(ref ty, _) => named("array", vec![get_index_type(ty, self_type, generics)]),
+        clean::BareFunction(..) => named("fn", Vec::new()),
+        clean::Never => named("never", Vec::new()),
+        clean::Unique(ref ty) |
+        clean::RawPointer(_, ref ty) |
+        clean::BorrowedRef { type_: ref ty, .. } => get_index_type(ty, self_type, generics),
+        // `impl Trait` is searched for as the trait it implements.
+        clean::ImplTrait(ref bounds) => {
+            bounds.iter().filter_map(|bound| match *bound {
+                clean::GenericBound::TraitBound(ref poly_trait, _) => {
+                    Some(get_index_type(&poly_trait.trait_, self_type, generics))
+                }
+                clean::GenericBound::Outlives(_) => None,
+            }).next().unwrap_or(Type::Unknown)
+        }
+        clean::ResolvedPath { .. } | clean::QPath { .. } | clean::Infer => Type::Unknown,
+    }
 }
 
 /// Returns a list of all paths used in the type.
@@ -4843,39 +5225,6 @@ fn collect_paths_for_type(first_ty: clean::Type) -> Vec<String> {
     out
 }
 
-fn get_index_type_name(clean_type: &clean::Type, accept_generic: bool) -> Option<String> {
-    match *clean_type {
-        clean::ResolvedPath { ref path, .. } => {
-            let segments = &path.segments;
-            let path_segment = segments.into_iter().last().unwrap_or_else(|| panic!(
-                "get_index_type_name(clean_type: {:?}, accept_generic: {:?}) had length zero path",
-                clean_type, accept_generic
-            ));
-            Some(path_segment.name.clone())
-        }
-        clean::Generic(ref s) if accept_generic => Some(s.clone()),
-        clean::Primitive(ref p) => Some(format!("{:?}", p)),
-        clean::BorrowedRef { ref type_, .. } => get_index_type_name(type_, accept_generic),
-        // FIXME: add all from clean::Type.
-        _ => None
-    }
-}
-
-fn get_generics(clean_type: &clean::Type) -> Option<Vec<String>> {
-    clean_type.generics()
-              .and_then(|types| {
-                  let r = types.iter()
-                               .filter_map(|t| get_index_type_name(t, false))
-                               .map(|s| s.to_ascii_lowercase())
-                               .collect::<Vec<_>>();
-                  if r.is_empty() {
-                      None
-                  } else {
-                      Some(r)
-                  }
-              })
-}
-
 pub fn cache() -> Arc<Cache> {
     CACHE_KEY.with(|c| c.borrow().clone())
 }
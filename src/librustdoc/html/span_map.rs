@@ -0,0 +1,312 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The links from the identifiers in the source of the crate to their definitions, used to
+//! render "jump to definition" links in the source pages with `--generate-link-to-definition`.
+//!
+//! The links are collected from the HIR while the type context is still around: each segment
+//! of a path links to what it resolved to, and the name of a method call or of a type-relative
+//! path like `Vec::new` to the method that type checking picked. Identifiers that come from a
+//! macro expansion are not linked, as they are not in the source as such.
+//!
+//! The uses of each definition of the crate are recorded as well, by the name of the definition,
+//! so that the source pages can list the references to it.
+//!
+//! The paths of the items of other crates that are linked to are recorded like those of the
+//! items in signatures, so that their documentation can be found when rendering.
+
+use std::mem;
+use std::path::PathBuf;
+
+use rustc::hir::{self, intravisit, Node};
+use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
+use rustc::ty;
+use rustc::util::nodemap::FxHashMap;
+use syntax::ast;
+use syntax::ext::base::MacroKind;
+use syntax_pos::{BytePos, FileName, Span};
+use syntax_pos::hygiene::SyntaxContext;
+
+use clean::{self, inline};
+use core::DocContext;
+
+/// Where an identifier in the source links to.
+#[derive(Clone, Debug)]
+pub enum LinkFromSrc {
+    /// A definition in the crate, by the file and the line it starts on, which is linked to in
+    /// the source pages.
+    Local(PathBuf, usize),
+    /// An item of another crate, which is linked to in its documentation, with the anchor of
+    /// the item on the page of its parent for associated items and variants.
+    External(DefId, Option<String>),
+}
+
+/// The links and the references of the source files of the crate.
+#[derive(Default)]
+pub struct SpanMap {
+    /// The links of each file, by the byte range of the identifier in the file.
+    pub links: FxHashMap<PathBuf, FxHashMap<(u32, u32), LinkFromSrc>>,
+    /// The references to the definitions of each file, by the byte range of the name of the
+    /// definition, as the files and the lines they are on.
+    pub references: FxHashMap<PathBuf, FxHashMap<(u32, u32), Vec<(PathBuf, usize)>>>,
+}
+
+/// The keywords that the name of an item follows.
+const ITEM_KEYWORDS: &[&str] = &[
+    "const", "enum", "fn", "macro_rules", "mod", "mut", "static", "struct", "trait", "type",
+    "union",
+];
+
+/// Collects the links of all the identifiers in the crate.
+pub fn collect(cx: &DocContext) -> SpanMap {
+    let mut collector = SpanMapCollector {
+        cx,
+        tables: None,
+        span_map: SpanMap::default(),
+    };
+    intravisit::walk_crate(&mut collector, cx.tcx.hir.krate());
+    let mut span_map = collector.span_map;
+    for references in span_map.references.values_mut().flat_map(|refs| refs.values_mut()) {
+        references.sort();
+        references.dedup();
+    }
+    span_map
+}
+
+struct SpanMapCollector<'a, 'tcx: 'a, 'rcx: 'a, 'cstore: 'rcx> {
+    cx: &'a DocContext<'a, 'tcx, 'rcx, 'cstore>,
+    /// The type checking results of the body being visited, if any.
+    tables: Option<&'tcx ty::TypeckTables<'tcx>>,
+    span_map: SpanMap,
+}
+
+impl<'a, 'tcx, 'rcx, 'cstore> SpanMapCollector<'a, 'tcx, 'rcx, 'cstore> {
+    /// Links the identifier at `span` to the definition of `def`.
+    fn link(&mut self, span: Span, def: Def) {
+        if !is_in_source(span) {
+            return;
+        }
+        let tcx = self.cx.tcx;
+        let local_id = match def {
+            Def::Local(id) | Def::Upvar(id, ..) => Some(id),
+            _ => def.opt_def_id().and_then(|def_id| tcx.hir.as_local_node_id(def_id)),
+        };
+        let link = match local_id {
+            Some(id) => {
+                self.add_reference(span, id);
+                self.local_link(tcx.hir.span(id))
+            }
+            None if def.opt_def_id().is_some() => self.external_link(def),
+            None => None,
+        };
+        let link = match link {
+            Some(link) => link,
+            None => return,
+        };
+        if let Some((path, range)) = self.file_range(span) {
+            self.span_map.links.entry(path).or_default().insert(range, link);
+        }
+    }
+
+    /// Records the identifier at `span` as a reference to the definition `id` of the crate.
+    fn add_reference(&mut self, span: Span, id: ast::NodeId) {
+        let reference = match self.local_link(span) {
+            Some(LinkFromSrc::Local(path, line)) => (path, line),
+            _ => return,
+        };
+        let name = match self.name_span(id).and_then(|span| self.file_range(span)) {
+            Some(name) => name,
+            None => return,
+        };
+        let (path, range) = name;
+        self.span_map.references.entry(path).or_default()
+                                .entry(range).or_default()
+                                .push(reference);
+    }
+
+    /// Returns the span of the name of the definition `id`, if it is in the source.
+    fn name_span(&self, id: ast::NodeId) -> Option<Span> {
+        let hir = &self.cx.tcx.hir;
+        let span = match hir.find(id)? {
+            Node::Item(item) => self.item_name_span(item.span, item.name)?,
+            Node::ForeignItem(item) => self.item_name_span(item.span, item.name)?,
+            Node::TraitItem(item) => item.ident.span,
+            Node::ImplItem(item) => item.ident.span,
+            Node::Binding(&hir::Pat { node: hir::PatKind::Binding(_, _, ident, _), .. }) => {
+                ident.span
+            }
+            // The constructor of a tuple or unit struct is named by the struct.
+            Node::StructCtor(_) => return self.name_span(hir.get_parent(id)),
+            _ => return None,
+        };
+        if is_in_source(span) {
+            Some(span)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the span of `name` in the item at `span`, where it follows the keyword that
+    /// introduces the item, as the HIR doesn't keep the span of the names of items.
+    fn item_name_span(&self, span: Span, name: ast::Name) -> Option<Span> {
+        let snippet = self.cx.tcx.sess.source_map().span_to_snippet(span).ok()?;
+        let name = name.as_str();
+        let mut previous = "";
+        for word in snippet.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+            if word.is_empty() {
+                continue;
+            }
+            if word == &*name && ITEM_KEYWORDS.contains(&previous) {
+                let offset = (word.as_ptr() as usize - snippet.as_ptr() as usize) as u32;
+                let lo = span.lo() + BytePos(offset);
+                return Some(span.with_lo(lo).with_hi(lo + BytePos(word.len() as u32)));
+            }
+            previous = word;
+        }
+        None
+    }
+
+    /// Returns the file `span` is in and its byte range in the file.
+    fn file_range(&self, span: Span) -> Option<(PathBuf, (u32, u32))> {
+        let pos = self.cx.tcx.sess.source_map().lookup_byte_offset(span.lo());
+        match pos.sf.name {
+            FileName::Real(ref path) => {
+                Some((path.clone(), (pos.pos.0, pos.pos.0 + (span.hi().0 - span.lo().0))))
+            }
+            _ => None,
+        }
+    }
+
+    /// Links the name of the method call or type-relative path `id` to the method it resolved
+    /// to.
+    fn link_type_dependent(&mut self, span: Span, id: hir::HirId) {
+        let def = self.tables.and_then(|tables| tables.type_dependent_defs().get(id).cloned());
+        if let Some(def) = def {
+            self.link(span, def);
+        }
+    }
+
+    /// Links to the documentation of `def`, an item of another crate, recording the path of the
+    /// page it is on.
+    fn external_link(&self, def: Def) -> Option<LinkFromSrc> {
+        let tcx = self.cx.tcx;
+        match def {
+            Def::Method(def_id) | Def::AssociatedConst(def_id) | Def::AssociatedTy(def_id) => {
+                let item = tcx.associated_item(def_id);
+                let (parent, is_trait) = match item.container {
+                    ty::TraitContainer(trait_did) => {
+                        inline::record_extern_fqn(self.cx, trait_did, clean::TypeKind::Trait);
+                        (trait_did, true)
+                    }
+                    ty::ImplContainer(impl_did) => match tcx.type_of(impl_did).sty {
+                        ty::Adt(adt, _) => {
+                            let def = match adt.adt_kind() {
+                                ty::AdtKind::Struct => Def::Struct(adt.did),
+                                ty::AdtKind::Union => Def::Union(adt.did),
+                                ty::AdtKind::Enum => Def::Enum(adt.did),
+                            };
+                            (clean::register_def(self.cx, def), false)
+                        }
+                        _ => return None,
+                    },
+                };
+                let kind = match item.kind {
+                    ty::AssociatedKind::Method if is_trait && !item.defaultness.has_value() => {
+                        "tymethod"
+                    }
+                    ty::AssociatedKind::Method => "method",
+                    ty::AssociatedKind::Const => "associatedconstant",
+                    ty::AssociatedKind::Type => "associatedtype",
+                    ty::AssociatedKind::Existential => return None,
+                };
+                Some(LinkFromSrc::External(parent, Some(format!("{}.{}", kind, item.ident))))
+            }
+            Def::Variant(def_id) | Def::VariantCtor(def_id, _) => {
+                let parent = clean::register_def(self.cx, Def::Variant(def_id));
+                let anchor = format!("variant.{}", tcx.item_name(def_id));
+                Some(LinkFromSrc::External(parent, Some(anchor)))
+            }
+            Def::StructCtor(def_id, _) => {
+                let parent = tcx.parent_def_id(def_id)?;
+                Some(LinkFromSrc::External(clean::register_def(self.cx, Def::Struct(parent)), None))
+            }
+            // Registering a trait also inlines it, which is not needed to link to it.
+            Def::Trait(def_id) => {
+                inline::record_extern_fqn(self.cx, def_id, clean::TypeKind::Trait);
+                Some(LinkFromSrc::External(def_id, None))
+            }
+            Def::Macro(_, MacroKind::ProcMacroStub) => None,
+            Def::Fn(..) | Def::TyAlias(..) | Def::Enum(..) | Def::Struct(..) | Def::Union(..) |
+            Def::Mod(..) | Def::ForeignTy(..) | Def::Const(..) | Def::Static(..) |
+            Def::Macro(..) => {
+                Some(LinkFromSrc::External(clean::register_def(self.cx, def), None))
+            }
+            _ => None,
+        }
+    }
+
+    fn local_link(&self, span: Span) -> Option<LinkFromSrc> {
+        if !is_in_source(span) {
+            return None;
+        }
+        let loc = self.cx.tcx.sess.source_map().lookup_char_pos(span.lo());
+        match loc.file.name {
+            FileName::Real(ref path) => Some(LinkFromSrc::Local(path.clone(), loc.line)),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `span` points to code written in a source file, rather than to the expansion of a
+/// macro or a desugaring.
+crate fn is_in_source(span: Span) -> bool {
+    !span.is_dummy() && span.ctxt() == SyntaxContext::empty()
+}
+
+impl<'a, 'tcx, 'rcx, 'cstore> intravisit::Visitor<'tcx>
+    for SpanMapCollector<'a, 'tcx, 'rcx, 'cstore>
+{
+    fn nested_visit_map<'this>(&'this mut self) -> intravisit::NestedVisitorMap<'this, 'tcx> {
+        intravisit::NestedVisitorMap::All(&self.cx.tcx.hir)
+    }
+
+    fn visit_nested_body(&mut self, id: hir::BodyId) {
+        let old_tables = mem::replace(&mut self.tables, Some(self.cx.tcx.body_tables(id)));
+        let body = self.cx.tcx.hir.body(id);
+        self.visit_body(body);
+        self.tables = old_tables;
+    }
+
+    fn visit_path(&mut self, path: &'tcx hir::Path, _: hir::HirId) {
+        // Only the segments of the path that name something on their own, like modules, know
+        // what they resolved to; the last one resolved to the path's definition.
+        if let Some((last, segments)) = path.segments.split_last() {
+            for segment in segments {
+                if let Some(def) = segment.def {
+                    self.link(segment.ident.span, def);
+                }
+            }
+            self.link(last.ident.span, path.def);
+        }
+        intravisit::walk_path(self, path);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        match expr.node {
+            hir::ExprKind::MethodCall(ref segment, ..) |
+            hir::ExprKind::Path(hir::QPath::TypeRelative(_, ref segment)) => {
+                self.link_type_dependent(segment.ident.span, expr.hir_id);
+            }
+            _ => {}
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
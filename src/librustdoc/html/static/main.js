@@ -638,14 +638,35 @@
                 };
             }
 
+            // Types in the search index are `null` when they can't be searched for, `[n]` for
+            // the n-th generic parameter of the function and `[name, [generics]]` otherwise.
+            function typeName(ty) {
+                return ty && typeof ty[NAME] === "string" ? ty[NAME] : null;
+            }
+
+            function typeGenerics(ty) {
+                return ty && ty.length > GENERICS_DATA ? ty[GENERICS_DATA] : [];
+            }
+
+            // The names of the generics of a type, leaving out those which aren't named.
+            function genericNames(ty) {
+                var generics = typeGenerics(ty);
+                var names = [];
+                for (var x = 0; x < generics.length; ++x) {
+                    if (typeName(generics[x]) !== null) {
+                        names.push(typeName(generics[x]));
+                    }
+                }
+                return names;
+            }
+
             function checkGenerics(obj, val) {
                 // The names match, but we need to be sure that all generics kinda
                 // match as well.
                 var lev_distance = MAX_LEV_DISTANCE + 1;
                 if (val.generics.length > 0) {
-                    if (obj.length > GENERICS_DATA &&
-                          obj[GENERICS_DATA].length >= val.generics.length) {
-                        var elems = obj[GENERICS_DATA].slice(0);
+                    var elems = genericNames(obj);
+                    if (elems.length >= val.generics.length) {
                         var total = 0;
                         var done = 0;
                         // We need to find the type that matches the most to remove it in order
@@ -677,12 +698,16 @@
             // Check for type name and type generics (if any).
             function checkType(obj, val, literalSearch) {
                 var lev_distance = MAX_LEV_DISTANCE + 1;
-                if (obj[NAME] === val.name) {
+                var name = typeName(obj);
+                if (name === null) {
+                    return literalSearch === true ? false : lev_distance;
+                }
+                var generics = genericNames(obj);
+                if (name === val.name) {
                     if (literalSearch === true) {
                         if (val.generics && val.generics.length !== 0) {
-                            if (obj.length > GENERICS_DATA &&
-                                  obj[GENERICS_DATA].length >= val.generics.length) {
-                                var elems = obj[GENERICS_DATA].slice(0);
+                            if (generics.length >= val.generics.length) {
+                                var elems = generics.slice(0);
                                 var allFound = true;
                                 var x;
 
@@ -706,7 +731,7 @@
                     }
                     // If the type has generics but don't match, then it won't return at this point.
                     // Otherwise, `checkGenerics` will return 0 and it'll return.
-                    if (obj.length > GENERICS_DATA && obj[GENERICS_DATA].length !== 0) {
+                    if (generics.length !== 0) {
                         var tmp_lev = checkGenerics(obj, val);
                         if (tmp_lev <= MAX_LEV_DISTANCE) {
                             return tmp_lev;
@@ -717,23 +742,20 @@
                 }
                 // Names didn't match so let's check if one of the generic types could.
                 if (literalSearch === true) {
-                     if (obj.length > GENERICS_DATA && obj[GENERICS_DATA].length > 0) {
-                        for (var x = 0; x < obj[GENERICS_DATA].length; ++x) {
-                            if (obj[GENERICS_DATA][x] === val.name) {
-                                return true;
-                            }
+                    for (var x = 0; x < generics.length; ++x) {
+                        if (generics[x] === val.name) {
+                            return true;
                         }
                     }
                     return false;
                 }
-                var lev_distance = Math.min(levenshtein(obj[NAME], val.name),
-                                            lev_distance);
+                var lev_distance = Math.min(levenshtein(name, val.name), lev_distance);
                 if (lev_distance <= MAX_LEV_DISTANCE) {
                     lev_distance = Math.min(checkGenerics(obj, val), lev_distance);
-                } else if (obj.length > GENERICS_DATA && obj[GENERICS_DATA].length > 0) {
+                } else {
                     // We can check if the type we're looking for is inside the generics!
-                    for (var x = 0; x < obj[GENERICS_DATA].length; ++x) {
-                        lev_distance = Math.min(levenshtein(obj[GENERICS_DATA][x], val.name),
+                    for (var x = 0; x < generics.length; ++x) {
+                        lev_distance = Math.min(levenshtein(generics[x], val.name),
                                                 lev_distance);
                     }
                 }
@@ -841,6 +863,264 @@
                 return itemTypes[ty.ty] + ty.path + ty.name;
             }
 
+            // Parses a query like `&[T], usize -> Option<T>` into the types of the inputs and
+            // of the output, normalized like the types of the search index: references and
+            // pointers are left out, and slices, arrays and tuples are named after their
+            // primitive. Each type is an object with its `name` and its `generics`; a `*`
+            // stands for any type and has a `null` name, and a name of a single letter is a
+            // type variable, which stands for the same type wherever it appears.
+            // The output is `null` if the query doesn't give one, and the inputs are `null` if
+            // the query is `* -> ...`. Returns `null` if the query can't be parsed.
+            function parseSignature(query) {
+                var tokens = query.match(/'?[a-z_0-9]+|::|->|\S/g) || [];
+                var pos = 0;
+
+                function isIdent(token) {
+                    return token !== undefined && /^[a-z_0-9]+$/.test(token);
+                }
+
+                function parseType() {
+                    var token = tokens[pos++];
+                    if (token === "&") {
+                        if (tokens[pos] && tokens[pos].charAt(0) === "'") {
+                            pos += 1;
+                        }
+                        if (tokens[pos] === "mut") {
+                            pos += 1;
+                        }
+                        return parseType();
+                    } else if (token === "*") {
+                        if (tokens[pos] === "const" || tokens[pos] === "mut") {
+                            pos += 1;
+                            return parseType();
+                        }
+                        return {name: null, generics: []};
+                    } else if (token === "[") {
+                        var inner = parseType();
+                        if (inner === null) {
+                            return null;
+                        }
+                        var name = "slice";
+                        if (tokens[pos] === ";") {
+                            name = "array";
+                            while (pos < tokens.length && tokens[pos] !== "]") {
+                                pos += 1;
+                            }
+                        }
+                        if (tokens[pos++] !== "]") {
+                            return null;
+                        }
+                        return {name: name, generics: [inner]};
+                    } else if (token === "(") {
+                        var types = parseList(")");
+                        if (types === null) {
+                            return null;
+                        } else if (types.length === 0) {
+                            return {name: "unit", generics: []};
+                        } else if (types.length === 1) {
+                            return types[0];
+                        }
+                        return {name: "tuple", generics: types};
+                    } else if (token === "!") {
+                        return {name: "never", generics: []};
+                    } else if (token === "impl" || token === "dyn") {
+                        return parseType();
+                    } else if (isIdent(token) === false) {
+                        return null;
+                    }
+                    // Only the last segment of a path is in the search index.
+                    while (tokens[pos] === "::" && isIdent(tokens[pos + 1])) {
+                        token = tokens[pos + 1];
+                        pos += 2;
+                    }
+                    var generics = [];
+                    if (tokens[pos] === "<") {
+                        pos += 1;
+                        generics = parseList(">");
+                    } else if (tokens[pos] === "(") {
+                        // The arguments of the `Fn` traits, followed by their output.
+                        pos += 1;
+                        generics = parseList(")");
+                        if (generics !== null && tokens[pos] === "->") {
+                            pos += 1;
+                            generics.push(parseType());
+                        }
+                    }
+                    if (generics === null || generics.indexOf(null) !== -1) {
+                        return null;
+                    }
+                    return {name: token, generics: generics, isVar: /^[a-z]$/.test(token)};
+                }
+
+                // Parses comma-separated types up to `end`, leaving out the lifetimes and the
+                // names of associated type bindings like `Item =`.
+                function parseList(end) {
+                    var types = [];
+                    while (pos < tokens.length && tokens[pos] !== end) {
+                        if (tokens[pos].charAt(0) === "'") {
+                            pos += 1;
+                        } else {
+                            if (isIdent(tokens[pos]) && tokens[pos + 1] === "=") {
+                                pos += 2;
+                            }
+                            var ty = parseType();
+                            if (ty === null) {
+                                return null;
+                            }
+                            types.push(ty);
+                        }
+                        if (tokens[pos] === ",") {
+                            pos += 1;
+                        } else if (tokens[pos] !== end) {
+                            return null;
+                        }
+                    }
+                    pos += 1;
+                    return types;
+                }
+
+                var inputs = parseList("->");
+                var outputs = inputs === null ? null : parseList(undefined);
+                if (outputs === null || outputs.length > 1) {
+                    return null;
+                }
+                if (inputs.length === 1 && inputs[0].name === null) {
+                    inputs = null;
+                }
+                return {inputs: inputs, output: outputs.length === 0 ? null : outputs[0]};
+            }
+
+            // The key of a type of the query, to check that the generic parameters of the
+            // search index always stand for the same type.
+            function queryTypeKey(ty) {
+                return ty.name + "<" + ty.generics.map(queryTypeKey).join(",") + ">";
+            }
+
+            function copyEnv(env) {
+                var copy = {vars: {}, generics: {}};
+                for (var v in env.vars) {
+                    if (env.vars.hasOwnProperty(v)) {
+                        copy.vars[v] = env.vars[v];
+                    }
+                }
+                for (var g in env.generics) {
+                    if (env.generics.hasOwnProperty(g)) {
+                        copy.generics[g] = env.generics[g];
+                    }
+                }
+                return copy;
+            }
+
+            // Unifies the type `qty` of the query with the type `ity` of the search index,
+            // returning the distance between them or -1 if they don't match. The type variables
+            // of the query and the generic parameters of the function are bound in `env` to
+            // the types they stand for. A type variable matching a concrete type, or a generic
+            // parameter matching a concrete type of the query, adds 1 to the distance.
+            function unifyType(qty, ity, env) {
+                if (qty.name === null) {
+                    return 0;
+                } else if (!ity) {
+                    return -1;
+                }
+                if (qty.isVar === true) {
+                    var key = JSON.stringify(ity);
+                    if (env.vars.hasOwnProperty(qty.name)) {
+                        return env.vars[qty.name] === key ? 0 : -1;
+                    }
+                    env.vars[qty.name] = key;
+                    return typeof ity[NAME] === "number" ? 0 : 1;
+                }
+                if (typeof ity[NAME] === "number") {
+                    var qkey = queryTypeKey(qty);
+                    if (env.generics.hasOwnProperty(ity[NAME])) {
+                        return env.generics[ity[NAME]] === qkey ? 1 : -1;
+                    }
+                    env.generics[ity[NAME]] = qkey;
+                    return 1;
+                }
+                var generics = typeGenerics(ity);
+                if (qty.name !== ity[NAME] || qty.generics.length > generics.length) {
+                    return -1;
+                }
+                // The generics are matched in order, and those left out of the query match
+                // anything.
+                var lev = 0;
+                for (var i = 0; i < qty.generics.length; ++i) {
+                    var tmp = unifyType(qty.generics[i], generics[i], env);
+                    if (tmp === -1) {
+                        return -1;
+                    }
+                    lev += tmp;
+                }
+                return lev;
+            }
+
+            function unifyOutput(output, type, env) {
+                if (output === null) {
+                    // Functions returning nothing.
+                    return type.length > OUTPUT_DATA && typeName(type[OUTPUT_DATA]) !== "unit" ?
+                        -1 : 0;
+                }
+                return unifyType(output, type[OUTPUT_DATA], env);
+            }
+
+            // Calls `callback` with the distance and the bindings of each way the inputs of the
+            // query can be matched to distinct inputs of the function, in any order. Each input
+            // of the function left out of the query adds 1 to the distance.
+            function unifyInputs(inputs, type, callback) {
+                var fnInputs = type[INPUTS_DATA] || [];
+                if (inputs === null) {
+                    callback(0, {vars: {}, generics: {}});
+                    return;
+                } else if (inputs.length > fnInputs.length) {
+                    return;
+                }
+                var used = [];
+                function assign(i, lev, env) {
+                    if (i === inputs.length) {
+                        callback(lev + fnInputs.length - inputs.length, env);
+                        return;
+                    }
+                    for (var j = 0; j < fnInputs.length; ++j) {
+                        if (used[j] === true) {
+                            continue;
+                        }
+                        var newEnv = copyEnv(env);
+                        var tmp = unifyType(inputs[i], fnInputs[j], newEnv);
+                        if (tmp !== -1) {
+                            used[j] = true;
+                            assign(i + 1, lev + tmp, newEnv);
+                            used[j] = false;
+                        }
+                    }
+                }
+                assign(0, 0, {vars: {}, generics: {}});
+            }
+
+            // The distances between the signature of the query and the function type `type`
+            // of the search index, for the whole signature, the inputs only and the output
+            // only, each being -1 if it doesn't match.
+            function unifySignature(signature, type) {
+                var lev = {full: -1, inputs: -1, output: -1};
+                function best(current, tmp) {
+                    return tmp !== -1 && (current === -1 || tmp < current) ? tmp : current;
+                }
+
+                unifyInputs(signature.inputs, type, function(inputsLev, env) {
+                    if (signature.inputs !== null && signature.inputs.length > 0) {
+                        lev.inputs = best(lev.inputs, inputsLev);
+                    }
+                    var outputLev = unifyOutput(signature.output, type, env);
+                    if (outputLev !== -1) {
+                        lev.full = best(lev.full, inputsLev + outputLev);
+                    }
+                });
+                if (signature.output !== null && signature.output.name !== null) {
+                    lev.output = unifyOutput(signature.output, type, {vars: {}, generics: {}});
+                }
+                return lev;
+            }
+
             // quoted values mean literal search
             var nSearchWords = searchWords.length;
             if ((val.charAt(0) === "\"" || val.charAt(0) === "'") &&
@@ -891,67 +1171,47 @@
                 query.search = val;
             // searching by type
             } else if (val.search("->") > -1) {
-                var trimmer = function(s) { return s.trim(); };
-                var parts = val.split("->").map(trimmer);
-                var input = parts[0];
-                // sort inputs so that order does not matter
-                var inputs = input.split(",").map(trimmer).sort();
-                for (var i = 0; i < inputs.length; ++i) {
-                    inputs[i] = extractGenerics(inputs[i]);
-                }
-                var output = extractGenerics(parts[1]);
-
-                for (var i = 0; i < nSearchWords; ++i) {
-                    var type = searchIndex[i].type;
-                    var ty = searchIndex[i];
-                    if (!type) {
-                        continue;
-                    }
-                    var fullId = generateId(ty);
-
-                    // allow searching for void (no output) functions as well
-                    var typeOutput = type.length > OUTPUT_DATA ? type[OUTPUT_DATA].name : "";
-                    var returned = checkReturned(ty, output, true);
-                    if (output.name === "*" || returned === true) {
-                        var in_args = false;
-                        var module = false;
-
-                        if (input === "*") {
-                            module = true;
-                        } else {
-                            var allFound = true;
-                            for (var it = 0; allFound === true && it < inputs.length; it++) {
-                                allFound = checkType(type, inputs[it], true);
-                            }
-                            in_args = allFound;
+                var signature = parseSignature(val);
+                if (signature !== null) {
+                    for (var i = 0; i < nSearchWords; ++i) {
+                        var ty = searchIndex[i];
+                        if (!ty.type || typePassesFilter(typeFilter, ty.ty) === false) {
+                            continue;
                         }
-                        if (in_args === true) {
-                            results_in_args[fullId] = {
+                        var fullId = generateId(ty);
+                        var lev = unifySignature(signature, ty.type);
+
+                        if (lev.full !== -1) {
+                            results[fullId] = {
                                 id: i,
                                 index: -1,
+                                lev: lev.full,
                                 dontValidate: true,
                             };
                         }
-                        if (returned === true) {
-                            results_returned[fullId] = {
+                        if (lev.inputs !== -1) {
+                            results_in_args[fullId] = {
                                 id: i,
                                 index: -1,
+                                lev: lev.inputs,
                                 dontValidate: true,
                             };
                         }
-                        if (module === true) {
-                            results[fullId] = {
+                        if (lev.output !== -1) {
+                            results_returned[fullId] = {
                                 id: i,
                                 index: -1,
+                                lev: lev.output,
                                 dontValidate: true,
                             };
                         }
                     }
+                    // `* -> T` leaves the inputs out.
+                    query.inputs = signature.inputs ? signature.inputs.map(function(input) {
+                        return input.name;
+                    }) : ["*"];
+                    query.output = signature.output ? signature.output.name : "";
                 }
-                query.inputs = inputs.map(function(input) {
-                    return input.name;
-                });
-                query.output = output.name;
             } else {
                 query.inputs = [val];
                 query.output = val;
@@ -1402,7 +1662,8 @@
         }
 
         function execSearch(query, searchWords) {
-            var queries = query.raw.split(",");
+            // The commas of a type signature separate its inputs rather than queries.
+            var queries = query.raw.search("->") > -1 ? [query.raw] : query.raw.split(",");
             var results = {
                 'in_args': [],
                 'returned': [],
@@ -1502,9 +1763,125 @@
                 }
             }
 
+            var crates = searchedCrates(query.raw, allCrates());
+            if (loadShards(crates) === false) {
+                // The search is done again once the shards it needs are loaded.
+                addClass(document.getElementById('main'), 'hidden');
+                var search_c = document.getElementById('search');
+                removeClass(search_c, 'hidden');
+                search_c.innerHTML =
+                    '<h3 style="text-align: center;">Loading search results...</h3>';
+                return;
+            }
+
+            if (indexedCrates !== crates.join(",")) {
+                index = buildIndex(pickShards(crates));
+                indexedCrates = crates.join(",");
+            }
             showResults(execSearch(query, index));
         }
 
+        // The items of each crate are in a shard of the search index, which is only loaded
+        // once a search needs it, so that reading a page doesn't load the items of all the
+        // crates. The shard of a crate is null once it failed to load.
+        var shards = {};
+        var requestedShards = {};
+        // The crates the current `index` is built from, separated by commas.
+        var indexedCrates;
+
+        function allCrates() {
+            var crates = [];
+            for (var crate in rawSearchIndex) {
+                if (rawSearchIndex.hasOwnProperty(crate)) {
+                    crates.push(crate);
+                }
+            }
+            return crates;
+        }
+
+        /**
+         * The crates whose items a search for `raw` looks through: the crate each of its
+         * queries starts with, like `alloc` for `alloc::vec::Vec`, or all of `crates` as soon
+         * as one of the queries doesn't start with the name of a crate.
+         */
+        function searchedCrates(raw, crates) {
+            if (raw.search("->") > -1) {
+                return crates;
+            }
+            var queries = raw.split(",");
+            var searched = [];
+            for (var i = 0; i < queries.length; ++i) {
+                var query = getQuery(queries[i].trim()).query;
+                if (query.length === 0) {
+                    continue;
+                }
+                var path = query.split("::");
+                var first = path[0].trim().toLowerCase();
+                var crate = null;
+                for (var j = 0; j < crates.length && path.length > 1; ++j) {
+                    if (crates[j].toLowerCase() === first) {
+                        crate = crates[j];
+                        break;
+                    }
+                }
+                if (crate === null) {
+                    return crates;
+                }
+                if (searched.indexOf(crate) === -1) {
+                    searched.push(crate);
+                }
+            }
+            searched.sort();
+            return searched.length > 0 ? searched : crates;
+        }
+
+        // Returns the loaded shards of `crates`, by crate.
+        function pickShards(crates) {
+            var picked = {};
+            for (var i = 0; i < crates.length; ++i) {
+                if (shards[crates[i]]) {
+                    picked[crates[i]] = shards[crates[i]];
+                }
+            }
+            return picked;
+        }
+
+        // Starts loading the shards of `crates` that aren't loaded yet, and returns whether
+        // they all are.
+        function loadShards(crates) {
+            var loaded = true;
+            // Called by the shards once they're loaded.
+            window.addSearchShard = function(crate, shard) {
+                shards[crate] = shard;
+                currentResults = null;
+                search();
+            };
+            function shardFailed(crate) {
+                return function() {
+                    // A crate whose shard is missing is left out of the search.
+                    shards[crate] = null;
+                    currentResults = null;
+                    search();
+                };
+            }
+            for (var i = 0; i < crates.length; ++i) {
+                var crate = crates[i];
+                if (shards.hasOwnProperty(crate)) {
+                    continue;
+                }
+                loaded = false;
+                if (requestedShards[crate] === true) {
+                    continue;
+                }
+                requestedShards[crate] = true;
+                var script = document.createElement('script');
+                script.src = rootPath + 'search-index/' + crate + '.js';
+                script.onerror = shardFailed(crate);
+                document.head.appendChild(script);
+            }
+            return loaded;
+        }
+
         function buildIndex(rawSearchIndex) {
             searchIndex = [];
             var searchWords = [];
@@ -1521,16 +1898,23 @@
                     type: null,
                 });
 
-                // an array of [(Number) item type,
-                //              (String) name,
-                //              (String) full path or empty string for previous path,
-                //              (String) description,
-                //              (Number | null) the parent path index to `paths`]
-                //              (Object | null) the type of the function (if any)
-                var items = rawSearchIndex[crate].items;
+                // the items are stored by column:
+                // (String) the item type of each item, as a letter from 'A'
+                var types = rawSearchIndex[crate].t;
+                // an array of (String) the name of each item
+                var names = rawSearchIndex[crate].n;
+                // an array of [(Number) index of the first item with the path,
+                //              (String) full path]
+                var itemPaths = rawSearchIndex[crate].q;
+                // an array of (String) the description of each item
+                var descs = rawSearchIndex[crate].d;
+                // an array of (Number) the parent path index to `paths` plus one, or zero
+                var parents = rawSearchIndex[crate].i;
+                // an array of (Object | null) the type of the function (if any)
+                var fnTypes = rawSearchIndex[crate].f;
                 // an array of [(Number) item type,
                 //              (String) name]
-                var paths = rawSearchIndex[crate].paths;
+                var paths = rawSearchIndex[crate].p;
 
                 // convert `paths` into an object form
                 var len = paths.length;
@@ -1545,13 +1929,17 @@
                 // operation that is cached for the life of the page state so that
                 // all other search operations have access to this cached data for
                 // faster analysis operations
-                var len = items.length;
+                var len = names.length;
                 var lastPath = "";
+                var pathPos = 0;
                 for (var i = 0; i < len; ++i) {
-                    var rawRow = items[i];
-                    var row = {crate: crate, ty: rawRow[0], name: rawRow[1],
-                               path: rawRow[2] || lastPath, desc: rawRow[3],
-                               parent: paths[rawRow[4]], type: rawRow[5]};
+                    if (pathPos < itemPaths.length && itemPaths[pathPos][0] === i) {
+                        lastPath = itemPaths[pathPos][1];
+                        pathPos += 1;
+                    }
+                    var row = {crate: crate, ty: types.charCodeAt(i) - 65, name: names[i],
+                               path: lastPath, desc: descs[i],
+                               parent: paths[parents[i] - 1], type: fnTypes[i]};
                     searchIndex.push(row);
                     if (typeof row.name === "string") {
                         var word = row.name.toLowerCase();
@@ -1559,7 +1947,6 @@
                     } else {
                         searchWords.push("");
                     }
-                    lastPath = row.path;
                 }
             }
             return searchWords;
@@ -1649,7 +2036,6 @@
             search();
         }
 
-        index = buildIndex(rawSearchIndex);
         startSearch();
 
         // Draw a convenient sidebar of known crates if we have a listing
@@ -0,0 +1,619 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The JSON backend, used with `--output-format json`.
+//!
+//! Instead of HTML pages, a single `<crate>.json` file is written to the output directory,
+//! describing the cleaned crate after all passes have run. The document is an object with the
+//! following fields:
+//!
+//! * `format_version`: the version of this schema, `FORMAT_VERSION`. It is bumped whenever a
+//!   field is removed or changes meaning.
+//! * `crate`: the `name` and `version` of the crate, and the id of its `root` module.
+//! * `index`: every documented item of the crate, by id. Items removed by a pass (for example
+//!   private items) are not included.
+//! * `paths`: the full path and `kind` of every item that can be named, local or external, by
+//!   id. This is where ids of items outside of `index` can be looked up.
+//! * `external_crates`: the `name` and `html_root_url` of each crate, by crate id.
+//!
+//! Ids are strings of the form `"<crate id>:<item index>"`. They are only stable within one
+//! document. Each item has an `id`, `crate_id`, `name`, `span`, `visibility`, `docs`, `links`,
+//! `attrs`, `deprecation`, `stability`, `kind` and `inner` field, where `kind` is the same name
+//! the HTML backend uses for the item's type and `inner` holds the fields specific to it.
+//! Nested items, like fields, variants and the items of traits and impls, are referred to by
+//! id. Impls, including the auto trait and blanket impls synthesized by rustdoc, are listed in
+//! the `impls` of the type they are for and in the `implementors` of their trait.
+//!
+//! Types, generic arguments, bounds and variant kinds are objects with a `kind` naming the
+//! variant and the variant's contents in `inner`. The full schema is described in the unstable
+//! features chapter of the rustdoc book.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+use rustc::hir;
+use rustc::hir::def_id::{CrateNum, DefId};
+use rustc::middle::stability;
+use rustc::util::nodemap::FxHashMap;
+use serialize::json::{Json, ToJson};
+use syntax::ext::base::MacroKind;
+use syntax::print::pprust;
+
+use clean::{self, AttributesExt};
+use config::RenderOptions;
+use doctree;
+use html::item_type::ItemType;
+use html::render::{Error, RenderInfo};
+
+/// The version of the JSON schema, written as `format_version`.
+pub const FORMAT_VERSION: u32 = 1;
+
+struct JsonRenderer {
+    /// Every documented item, by id.
+    index: BTreeMap<String, Json>,
+    /// The path and kind of every item that can be named, by id.
+    paths: BTreeMap<String, Json>,
+    /// The ids of the impls of each type.
+    impls: FxHashMap<DefId, Vec<String>>,
+    /// The ids of the impls of each trait.
+    implementors: FxHashMap<DefId, Vec<String>>,
+    /// The items documenting primitive types in this crate.
+    primitives: FxHashMap<clean::PrimitiveType, DefId>,
+    /// The path of the module being walked.
+    module_path: Vec<String>,
+}
+
+/// Writes `krate` to `<output>/<crate name>.json`.
+pub fn run(krate: clean::Crate,
+           options: RenderOptions,
+           renderinfo: RenderInfo) -> Result<(), Error> {
+    let json = document(&krate, &options, &renderinfo);
+    let dst = options.output.join(format!("{}.json", krate.name));
+    fs::create_dir_all(&options.output).map_err(|e| Error::new(e, &options.output))?;
+    let mut file = BufWriter::new(File::create(&dst).map_err(|e| Error::new(e, &dst))?);
+    write!(file, "{}", json).map_err(|e| Error::new(e, &dst))?;
+    file.flush().map_err(|e| Error::new(e, &dst))
+}
+
+/// Builds the JSON document describing `krate`.
+pub fn document(krate: &clean::Crate,
+                options: &RenderOptions,
+                renderinfo: &RenderInfo) -> Json {
+    let mut renderer = JsonRenderer {
+        index: BTreeMap::new(),
+        paths: BTreeMap::new(),
+        impls: FxHashMap::default(),
+        implementors: FxHashMap::default(),
+        primitives: krate.primitives.iter().map(|&(did, prim, _)| (prim, did)).collect(),
+        module_path: vec![krate.name.clone()],
+    };
+    let root = match krate.module {
+        Some(ref module) => {
+            let path = vec![krate.name.clone()];
+            let entry = path_entry(module.def_id.krate, path, ItemType::Module);
+            renderer.paths.insert(id(module.def_id), entry);
+            renderer.item(module)
+        }
+        None => None,
+    };
+    renderer.link_impls();
+
+    for (&did, &(ref path, kind)) in &renderinfo.external_paths {
+        renderer.paths.entry(id(did)).or_insert_with(|| {
+            path_entry(did.krate, path.clone(), ItemType::from(kind))
+        });
+    }
+
+    let mut external_crates = BTreeMap::new();
+    for &(cnum, ref e) in &krate.externs {
+        let html_root_url = options.extern_html_root_urls.get(&e.name).cloned().or_else(|| {
+            e.attrs.lists("doc")
+                .filter(|a| a.check_name("html_root_url"))
+                .filter_map(|a| a.value_str())
+                .map(|url| url.to_string())
+                .next()
+        });
+        external_crates.insert(cnum.as_u32().to_string(), object(vec![
+            ("name", e.name.to_json()),
+            ("html_root_url", html_root_url.to_json()),
+        ]));
+    }
+
+    object(vec![
+        ("format_version", FORMAT_VERSION.to_json()),
+        ("crate", object(vec![
+            ("name", krate.name.to_json()),
+            ("version", krate.version.to_json()),
+            ("root", root.to_json()),
+        ])),
+        ("index", Json::Object(renderer.index)),
+        ("paths", Json::Object(renderer.paths)),
+        ("external_crates", Json::Object(external_crates)),
+    ])
+}
+
+impl JsonRenderer {
+    /// Adds `item` and the items nested in it to the index, returning its id. Stripped items
+    /// are skipped.
+    fn item(&mut self, item: &clean::Item) -> Option<String> {
+        if item.is_stripped() {
+            return None;
+        }
+        let item_id = id(item.def_id);
+        let kind = item.type_();
+        let inner = self.inner(item, &item_id);
+        let deprecation = item.deprecation.as_ref().map(|depr| object(vec![
+            ("since", non_empty(&depr.since)),
+            ("note", non_empty(&depr.note)),
+        ]));
+        let stability = item.stability.as_ref().map(|stab| object(vec![
+            ("level", match stab.level {
+                stability::Stable => "stable",
+                stability::Unstable => "unstable",
+            }.to_json()),
+            ("feature", non_empty(&stab.feature)),
+            ("since", non_empty(&stab.since)),
+            ("issue", stab.issue.to_json()),
+        ]));
+        let links = item.attrs.links.iter().map(|&(ref text, did, ref fragment)| object(vec![
+            ("text", text.to_json()),
+            ("id", did.map(id).to_json()),
+            ("fragment", fragment.to_json()),
+        ])).collect();
+
+        self.index.insert(item_id.clone(), object(vec![
+            ("id", item_id.to_json()),
+            ("crate_id", item.def_id.krate.as_u32().to_json()),
+            ("name", item.name.to_json()),
+            ("span", span(&item.source)),
+            ("visibility", visibility(&item.visibility)),
+            ("docs", item.collapsed_doc_value().to_json()),
+            ("links", Json::Array(links)),
+            ("attrs", Json::Array(item.attrs.other_attrs.iter()
+                .map(|attr| pprust::attribute_to_string(attr).to_json())
+                .collect())),
+            ("deprecation", deprecation.to_json()),
+            ("stability", stability.to_json()),
+            ("kind", kind.css_class().to_json()),
+            ("inner", inner),
+        ]));
+        Some(item_id)
+    }
+
+    /// Adds the items of a module to the index, recording their paths.
+    fn module_items(&mut self, items: &[clean::Item]) -> Json {
+        let mut ids = vec![];
+        for item in items {
+            if item.is_stripped() {
+                continue;
+            }
+            if let Some(ref name) = item.name {
+                let kind = item.type_();
+                match kind {
+                    ItemType::Import | ItemType::ExternCrate | ItemType::Impl => {}
+                    _ => {
+                        let mut path = self.module_path.clone();
+                        path.push(name.clone());
+                        let entry = path_entry(item.def_id.krate, path, kind);
+                        self.paths.entry(id(item.def_id)).or_insert(entry);
+                    }
+                }
+            }
+            ids.extend(self.item(item).map(Json::String));
+        }
+        Json::Array(ids)
+    }
+
+    fn items(&mut self, items: &[clean::Item]) -> Json {
+        Json::Array(items.iter().filter_map(|item| self.item(item)).map(Json::String).collect())
+    }
+
+    fn inner(&mut self, item: &clean::Item, item_id: &str) -> Json {
+        match item.inner {
+            clean::ModuleItem(ref m) => {
+                if !m.is_crate {
+                    self.module_path.push(item.name.clone().unwrap_or_default());
+                }
+                let items = self.module_items(&m.items);
+                if !m.is_crate {
+                    self.module_path.pop();
+                }
+                object(vec![
+                    ("is_crate", m.is_crate.to_json()),
+                    ("items", items),
+                ])
+            }
+            clean::ExternCrateItem(ref name, ref original) => object(vec![
+                ("name", name.to_json()),
+                ("original_name", original.to_json()),
+            ]),
+            clean::ImportItem(ref import) => {
+                let (name, source, glob) = match *import {
+                    clean::Import::Simple(ref name, ref source) => (Some(name), source, false),
+                    clean::Import::Glob(ref source) => (None, source, true),
+                };
+                object(vec![
+                    ("source", path_string(&source.path).to_json()),
+                    ("name", name.map(|name| name.to_json()).to_json()),
+                    ("id", source.did.map(id).to_json()),
+                    ("glob", glob.to_json()),
+                ])
+            }
+            clean::StructItem(clean::Struct {
+                struct_type, ref generics, ref fields, fields_stripped,
+            }) |
+            clean::UnionItem(clean::Union {
+                struct_type, ref generics, ref fields, fields_stripped,
+            }) => object(vec![
+                ("struct_type", struct_type_name(struct_type).to_json()),
+                ("generics", self::generics(generics)),
+                ("fields", self.items(fields)),
+                ("fields_stripped", fields_stripped.to_json()),
+                ("impls", Json::Array(vec![])),
+            ]),
+            clean::EnumItem(ref e) => object(vec![
+                ("generics", generics(&e.generics)),
+                ("variants", self.items(&e.variants.raw)),
+                ("variants_stripped", e.variants_stripped.to_json()),
+                ("impls", Json::Array(vec![])),
+            ]),
+            clean::VariantItem(ref variant) => {
+                let kind = match variant.kind {
+                    clean::VariantKind::CLike => tagged("plain", Json::Null),
+                    clean::VariantKind::Tuple(ref tys) => tagged("tuple", types(tys)),
+                    clean::VariantKind::Struct(ref s) => tagged("struct", object(vec![
+                        ("fields", self.items(&s.fields)),
+                        ("fields_stripped", s.fields_stripped.to_json()),
+                    ])),
+                };
+                object(vec![("variant_kind", kind)])
+            }
+            clean::StructFieldItem(ref ty) => object(vec![("type", type_(ty))]),
+            clean::FunctionItem(clean::Function { ref decl, ref generics, header }) |
+            clean::ForeignFunctionItem(clean::Function { ref decl, ref generics, header }) |
+            clean::MethodItem(clean::Method { ref decl, ref generics, header }) |
+            clean::TyMethodItem(clean::TyMethod { ref decl, ref generics, header }) => {
+                let has_body = match item.inner {
+                    clean::TyMethodItem(..) | clean::ForeignFunctionItem(..) => false,
+                    _ => true,
+                };
+                object(vec![
+                    ("decl", fn_decl(decl)),
+                    ("generics", self::generics(generics)),
+                    ("header", object(vec![
+                        ("is_const", (header.constness == hir::Constness::Const).to_json()),
+                        ("is_unsafe", (header.unsafety == hir::Unsafety::Unsafe).to_json()),
+                        ("is_async", (header.asyncness == hir::IsAsync::Async).to_json()),
+                        ("abi", header.abi.name().to_json()),
+                    ])),
+                    ("has_body", has_body.to_json()),
+                ])
+            }
+            clean::TypedefItem(ref t, _) => object(vec![
+                ("type", type_(&t.type_)),
+                ("generics", generics(&t.generics)),
+            ]),
+            clean::ExistentialItem(ref e, _) => object(vec![
+                ("bounds", bounds(&e.bounds)),
+                ("generics", generics(&e.generics)),
+            ]),
+            clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => object(vec![
+                ("type", type_(&s.type_)),
+                ("mutable", (s.mutability == clean::Mutable).to_json()),
+                ("expr", s.expr.to_json()),
+            ]),
+            clean::ConstantItem(ref c) => object(vec![
+                ("type", type_(&c.type_)),
+                ("expr", c.expr.to_json()),
+            ]),
+            clean::TraitItem(ref t) => object(vec![
+                ("is_auto", t.is_auto.to_json()),
+                ("is_unsafe", (t.unsafety == hir::Unsafety::Unsafe).to_json()),
+                ("items", self.items(&t.items)),
+                ("generics", generics(&t.generics)),
+                ("bounds", bounds(&t.bounds)),
+                ("implementors", Json::Array(vec![])),
+            ]),
+            clean::ImplItem(ref i) => {
+                if let Some(did) = self.impl_target(&i.for_) {
+                    self.impls.entry(did).or_default().push(item_id.to_string());
+                }
+                if let Some(clean::ResolvedPath { did, .. }) = i.trait_ {
+                    self.implementors.entry(did).or_default().push(item_id.to_string());
+                }
+                let mut provided_trait_methods: Vec<_> =
+                    i.provided_trait_methods.iter().cloned().collect();
+                provided_trait_methods.sort();
+                object(vec![
+                    ("is_unsafe", (i.unsafety == hir::Unsafety::Unsafe).to_json()),
+                    ("generics", generics(&i.generics)),
+                    ("provided_trait_methods", provided_trait_methods.to_json()),
+                    ("trait", i.trait_.as_ref().map(type_).to_json()),
+                    ("for", type_(&i.for_)),
+                    ("items", self.items(&i.items)),
+                    ("negative", (i.polarity == Some(clean::ImplPolarity::Negative)).to_json()),
+                    ("synthetic", i.synthetic.to_json()),
+                    ("blanket_impl", i.blanket_impl.as_ref().map(type_).to_json()),
+                ])
+            }
+            clean::MacroItem(ref m) => object(vec![
+                ("source", m.source.to_json()),
+                ("imported_from", m.imported_from.to_json()),
+            ]),
+            clean::ProcMacroItem(ref m) => object(vec![
+                ("macro_kind", match m.kind {
+                    MacroKind::Bang => "bang",
+                    MacroKind::Attr => "attr",
+                    MacroKind::Derive => "derive",
+                    MacroKind::ProcMacroStub => "stub",
+                }.to_json()),
+                ("helpers", m.helpers.to_json()),
+            ]),
+            clean::PrimitiveItem(prim) => object(vec![
+                ("primitive", prim.as_str().to_json()),
+                ("impls", Json::Array(vec![])),
+            ]),
+            clean::AssociatedConstItem(ref ty, ref default) => object(vec![
+                ("type", type_(ty)),
+                ("default", default.to_json()),
+            ]),
+            clean::AssociatedTypeItem(ref bounds, ref default) => object(vec![
+                ("bounds", self::bounds(bounds)),
+                ("default", default.as_ref().map(type_).to_json()),
+            ]),
+            clean::ForeignTypeItem => object(vec![("impls", Json::Array(vec![]))]),
+            clean::KeywordItem(ref keyword) => object(vec![("keyword", keyword.to_json())]),
+            clean::StrippedItem(..) => Json::Null,
+        }
+    }
+
+    /// The item that impls for `ty` are documented on, if any.
+    fn impl_target(&self, ty: &clean::Type) -> Option<DefId> {
+        match *ty {
+            clean::ResolvedPath { did, .. } => Some(did),
+            _ => ty.primitive_type().and_then(|prim| self.primitives.get(&prim).cloned()),
+        }
+    }
+
+    /// Fills in the `impls` and `implementors` lists of the items in the index, now that all
+    /// impls have been seen.
+    fn link_impls(&mut self) {
+        let lists = self.impls.iter().map(|(did, ids)| (did, ids, "impls"))
+            .chain(self.implementors.iter().map(|(did, ids)| (did, ids, "implementors")));
+        for (&did, ids, field) in lists {
+            let inner = self.index.get_mut(&id(did)).and_then(|item| match *item {
+                Json::Object(ref mut item) => item.get_mut("inner"),
+                _ => None,
+            });
+            if let Some(&mut Json::Object(ref mut inner)) = inner {
+                if let Some(&mut Json::Array(ref mut list)) = inner.get_mut(field) {
+                    list.extend(ids.iter().map(|id| id.to_json()));
+                }
+            }
+        }
+    }
+}
+
+fn id(did: DefId) -> String {
+    format!("{}:{}", did.krate.as_u32(), did.index.as_raw_u32())
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(name, value)| (name.to_string(), value)).collect())
+}
+
+fn tagged(kind: &str, inner: Json) -> Json {
+    object(vec![("kind", kind.to_json()), ("inner", inner)])
+}
+
+fn non_empty(s: &str) -> Json {
+    if s.is_empty() { Json::Null } else { s.to_json() }
+}
+
+fn path_entry(krate: CrateNum, path: Vec<String>, kind: ItemType) -> Json {
+    object(vec![
+        ("crate_id", krate.as_u32().to_json()),
+        ("path", path.to_json()),
+        ("kind", kind.css_class().to_json()),
+    ])
+}
+
+fn path_string(path: &clean::Path) -> String {
+    let segments: Vec<_> = path.segments.iter().map(|s| &s.name[..]).collect();
+    let joined = segments.join("::");
+    if path.global { format!("::{}", joined) } else { joined }
+}
+
+fn span(span: &clean::Span) -> Json {
+    if span.loline == 0 {
+        return Json::Null;
+    }
+    object(vec![
+        ("filename", span.filename.to_string().to_json()),
+        ("begin", vec![span.loline, span.locol].to_json()),
+        ("end", vec![span.hiline, span.hicol].to_json()),
+    ])
+}
+
+fn visibility(vis: &Option<clean::Visibility>) -> Json {
+    match *vis {
+        Some(clean::Public) => "public".to_json(),
+        Some(clean::Inherited) | None => "default".to_json(),
+        Some(clean::Visibility::Crate) => "crate".to_json(),
+        Some(clean::Visibility::Restricted(did, ref path)) => object(vec![
+            ("restricted", object(vec![
+                ("parent", id(did).to_json()),
+                ("path", path_string(path).to_json()),
+            ])),
+        ]),
+    }
+}
+
+fn struct_type_name(struct_type: doctree::StructType) -> &'static str {
+    match struct_type {
+        doctree::Plain => "plain",
+        doctree::Tuple => "tuple",
+        doctree::Unit => "unit",
+    }
+}
+
+fn types(tys: &[clean::Type]) -> Json {
+    Json::Array(tys.iter().map(type_).collect())
+}
+
+fn type_(ty: &clean::Type) -> Json {
+    match *ty {
+        clean::ResolvedPath { ref path, ref typarams, did, is_generic } => {
+            tagged("resolved_path", object(vec![
+                ("name", path_string(path).to_json()),
+                ("id", id(did).to_json()),
+                ("args", path.segments.last().map(|s| generic_args(&s.args)).to_json()),
+                ("param_names", typarams.as_ref().map(|b| bounds(b)).to_json()),
+                ("is_generic", is_generic.to_json()),
+            ]))
+        }
+        clean::Generic(ref name) => tagged("generic", name.to_json()),
+        clean::Primitive(prim) => tagged("primitive", prim.as_str().to_json()),
+        clean::BareFunction(ref f) => tagged("function_pointer", object(vec![
+            ("is_unsafe", (f.unsafety == hir::Unsafety::Unsafe).to_json()),
+            ("generic_params", generic_params(&f.generic_params)),
+            ("decl", fn_decl(&f.decl)),
+            ("abi", f.abi.name().to_json()),
+        ])),
+        clean::Tuple(ref tys) => tagged("tuple", types(tys)),
+        clean::Slice(ref ty) => tagged("slice", type_(ty)),
+        clean::Array(ref ty, ref len) => tagged("array", object(vec![
+            ("type", type_(ty)),
+            ("len", len.to_json()),
+        ])),
+        clean::Never => tagged("never", Json::Null),
+        clean::Unique(ref ty) => tagged("unique", type_(ty)),
+        clean::RawPointer(mutability, ref ty) => tagged("raw_pointer", object(vec![
+            ("mutable", (mutability == clean::Mutable).to_json()),
+            ("type", type_(ty)),
+        ])),
+        clean::BorrowedRef { ref lifetime, mutability, ref type_ } => {
+            tagged("borrowed_ref", object(vec![
+                ("lifetime", lifetime.as_ref().map(|lt| lt.get_ref().to_json()).to_json()),
+                ("mutable", (mutability == clean::Mutable).to_json()),
+                ("type", self::type_(type_)),
+            ]))
+        }
+        clean::QPath { ref name, ref self_type, ref trait_ } => {
+            tagged("qualified_path", object(vec![
+                ("name", name.to_json()),
+                ("self_type", type_(self_type)),
+                ("trait", type_(trait_)),
+            ]))
+        }
+        clean::Infer => tagged("infer", Json::Null),
+        clean::ImplTrait(ref b) => tagged("impl_trait", bounds(b)),
+    }
+}
+
+fn generic_args(args: &clean::GenericArgs) -> Json {
+    match *args {
+        clean::GenericArgs::AngleBracketed { ref lifetimes, ref types, ref bindings } => {
+            tagged("angle_bracketed", object(vec![
+                ("lifetimes", Json::Array(lifetimes.iter()
+                    .map(|lt| lt.get_ref().to_json())
+                    .collect())),
+                ("types", self::types(types)),
+                ("bindings", Json::Array(bindings.iter().map(|binding| object(vec![
+                    ("name", binding.name.to_json()),
+                    ("type", type_(&binding.ty)),
+                ])).collect())),
+            ]))
+        }
+        clean::GenericArgs::Parenthesized { ref inputs, ref output } => {
+            tagged("parenthesized", object(vec![
+                ("inputs", types(inputs)),
+                ("output", output.as_ref().map(type_).to_json()),
+            ]))
+        }
+    }
+}
+
+fn bounds(bounds: &[clean::GenericBound]) -> Json {
+    Json::Array(bounds.iter().map(|bound| match *bound {
+        clean::GenericBound::TraitBound(ref poly, modifier) => {
+            tagged("trait_bound", object(vec![
+                ("trait", type_(&poly.trait_)),
+                ("generic_params", generic_params(&poly.generic_params)),
+                ("modifier", match modifier {
+                    hir::TraitBoundModifier::None => "none",
+                    hir::TraitBoundModifier::Maybe => "maybe",
+                }.to_json()),
+            ]))
+        }
+        clean::GenericBound::Outlives(ref lt) => tagged("outlives", lt.get_ref().to_json()),
+    }).collect())
+}
+
+fn generic_params(params: &[clean::GenericParamDef]) -> Json {
+    Json::Array(params.iter().map(|param| {
+        let kind = match param.kind {
+            clean::GenericParamDefKind::Lifetime => tagged("lifetime", Json::Null),
+            clean::GenericParamDefKind::Type { ref bounds, ref default, ref synthetic, .. } => {
+                tagged("type", object(vec![
+                    ("bounds", self::bounds(bounds)),
+                    ("default", default.as_ref().map(type_).to_json()),
+                    ("synthetic", synthetic.is_some().to_json()),
+                ]))
+            }
+        };
+        object(vec![
+            ("name", param.name.to_json()),
+            ("kind", kind),
+        ])
+    }).collect())
+}
+
+fn generics(generics: &clean::Generics) -> Json {
+    let where_predicates = generics.where_predicates.iter().map(|pred| match *pred {
+        clean::WherePredicate::BoundPredicate { ref ty, ref bounds } => {
+            tagged("bound_predicate", object(vec![
+                ("type", type_(ty)),
+                ("bounds", self::bounds(bounds)),
+            ]))
+        }
+        clean::WherePredicate::RegionPredicate { ref lifetime, ref bounds } => {
+            tagged("region_predicate", object(vec![
+                ("lifetime", lifetime.get_ref().to_json()),
+                ("bounds", self::bounds(bounds)),
+            ]))
+        }
+        clean::WherePredicate::EqPredicate { ref lhs, ref rhs } => {
+            tagged("eq_predicate", object(vec![
+                ("lhs", type_(lhs)),
+                ("rhs", type_(rhs)),
+            ]))
+        }
+    }).collect();
+    object(vec![
+        ("params", generic_params(&generics.params)),
+        ("where_predicates", Json::Array(where_predicates)),
+    ])
+}
+
+fn fn_decl(decl: &clean::FnDecl) -> Json {
+    let inputs = decl.inputs.values.iter().map(|arg| {
+        Json::Array(vec![arg.name.to_json(), type_(&arg.type_)])
+    }).collect();
+    object(vec![
+        ("inputs", Json::Array(inputs)),
+        ("output", match decl.output {
+            clean::Return(ref ty) => type_(ty),
+            clean::DefaultReturn => Json::Null,
+        }),
+        ("variadic", decl.variadic.to_json()),
+    ])
+}
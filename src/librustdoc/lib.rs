@@ -65,11 +65,14 @@ use rustc::session::config::{ErrorOutputType, RustcOptGroup};
 #[macro_use]
 mod externalfiles;
 
+mod api_diff;
 mod clean;
 mod config;
 mod core;
+mod coverage;
 mod doctree;
 mod fold;
+mod json;
 pub mod html {
     crate mod highlight;
     crate mod escape;
@@ -78,11 +81,13 @@ pub mod html {
     crate mod layout;
     pub mod markdown;
     crate mod render;
+    crate mod span_map;
     crate mod static_files;
     crate mod toc;
 }
 mod markdown;
 mod passes;
+mod scrape_examples;
 mod visit_ast;
 mod visit_lib;
 mod test;
@@ -142,7 +147,7 @@ fn opts() -> Vec<RustcOptGroup> {
                      "[rust]")
         }),
         stable("w", |o| {
-            o.optopt("w", "output-format", "the output type to write", "[html]")
+            o.optopt("w", "output-format", "the output type to write", "[html|json]")
         }),
         stable("o", |o| o.optopt("o", "output", "where to place the output", "PATH")),
         stable("crate-name", |o| {
@@ -338,6 +343,75 @@ fn opts() -> Vec<RustcOptGroup> {
                        "enable-index-page",
                        "To enable generation of the index page")
         }),
+        unstable("api-diff", |o| {
+            o.optopt("",
+                     "api-diff",
+                     "compare the public API of the crate to a previous version, given as the \
+                      output of `--output-format json`, instead of generating documentation",
+                     "PATH")
+        }),
+        unstable("show-coverage", |o| {
+            o.optflag("",
+                      "show-coverage",
+                      "calculate the percentage of public items with documentation and code \
+                       examples, instead of generating documentation")
+        }),
+        unstable("merge-doctests", |o| {
+            o.optflag("",
+                      "merge-doctests",
+                      "when running doctests, compile those without a `fn main`, crate \
+                       attributes or special code block attributes into a single executable")
+        }),
+        unstable("persist-doctests", |o| {
+            o.optopt("",
+                     "persist-doctests",
+                     "directory in which to keep the compiled doctest executables, instead of \
+                      deleting them after running them",
+                     "PATH")
+        }),
+        unstable("doctest-timeout", |o| {
+            o.optopt("",
+                     "doctest-timeout",
+                     "number of seconds after which a running doctest is killed and fails",
+                     "SECS")
+        }),
+        unstable("doctest-filter", |o| {
+            o.optmulti("",
+                       "doctest-filter",
+                       "only run the doctests of the items with this path, or inside them",
+                       "PATH")
+        }),
+        unstable("generate-link-to-definition", |o| {
+            o.optflag("",
+                      "generate-link-to-definition",
+                      "make the paths, method calls and type names in the source pages link to \
+                       their definitions")
+        }),
+        unstable("scrape-examples-output-path", |o| {
+            o.optopt("",
+                     "scrape-examples-output-path",
+                     "write the calls to the functions and methods of the target crates found in \
+                      the crate to a file, instead of generating documentation",
+                     "PATH")
+        }),
+        unstable("scrape-examples-target-crate", |o| {
+            o.optmulti("",
+                       "scrape-examples-target-crate",
+                       "crate whose items the calls are scraped for",
+                       "NAME")
+        }),
+        unstable("scrape-tests", |o| {
+            o.optflag("",
+                      "scrape-tests",
+                      "compile the crate as a test when scraping it, to scrape the calls in its \
+                       `#[test]` functions")
+        }),
+        unstable("with-examples", |o| {
+            o.optmulti("",
+                       "with-examples",
+                       "show the calls scraped from an example on the pages of the items called",
+                       "PATH")
+        }),
     ]
 }
 
@@ -387,8 +461,31 @@ fn main_args(args: &[String]) -> isize {
         info!("going to format");
         let (error_format, treat_err_as_bug, ui_testing) = diag_opts;
         let diag = core::new_handler(error_format, None, treat_err_as_bug, ui_testing);
-        html::render::run(krate, renderopts, passes.into_iter().collect(), renderinfo, &diag)
-            .expect("failed to generate documentation");
+        if renderopts.show_coverage {
+            coverage::run(&krate, &renderopts);
+            return 0;
+        }
+        if let Some(ref path) = renderopts.scrape_examples_output_path {
+            if let Err(e) = scrape_examples::write(path, &renderinfo.scraped_calls) {
+                diag.struct_err(&e).emit();
+                return 1;
+            }
+            return 0;
+        }
+        if let Some(ref old) = renderopts.api_diff {
+            return api_diff::run(&krate, &renderopts, &renderinfo, old, &diag);
+        }
+        match renderopts.output_format {
+            config::OutputFormat::Html => {
+                html::render::run(krate, renderopts, passes.into_iter().collect(), renderinfo,
+                                  &diag)
+                    .expect("failed to generate documentation");
+            }
+            config::OutputFormat::Json => {
+                json::run(krate, renderopts, renderinfo)
+                    .expect("failed to generate documentation");
+            }
+        }
         0
     })
 }
@@ -0,0 +1,106 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use clean::*;
+
+use rustc::lint as lint;
+
+use core::DocContext;
+use fold::DocFolder;
+use html::markdown::{markdown_problems, MarkdownProblem};
+
+use passes::Pass;
+use passes::collect_intra_doc_links::{note_markdown_range, span_of_attrs, span_of_markdown_range};
+
+pub const CHECK_DOC_MARKDOWN: Pass =
+    Pass::early("check-doc-markdown", check_doc_markdown,
+                "lints broken markdown, bare URLs and invalid HTML in the documentation");
+
+struct MarkdownLinter<'a, 'tcx: 'a, 'rcx: 'a, 'cstore: 'rcx> {
+    cx: &'a DocContext<'a, 'tcx, 'rcx, 'cstore>,
+}
+
+pub fn check_doc_markdown(krate: Crate, cx: &DocContext) -> Crate {
+    let mut coll = MarkdownLinter { cx };
+
+    coll.fold_crate(krate)
+}
+
+impl<'a, 'tcx, 'rcx, 'cstore> DocFolder for MarkdownLinter<'a, 'tcx, 'rcx, 'cstore> {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        let cx = self.cx;
+        // The documentation of other crates is linted when they are documented.
+        let node_id = match cx.as_local_node_id(item.def_id) {
+            Some(node_id) => node_id,
+            None => return self.fold_item_recur(item),
+        };
+        let dox = item.attrs.collapsed_doc_value().unwrap_or_else(String::new);
+
+        for (problem, range) in markdown_problems(&dox) {
+            let (lint, msg, label, what, help) = match problem {
+                MarkdownProblem::UnclosedHtmlTag(name) => {
+                    (lint::builtin::INVALID_HTML_TAGS,
+                     format!("unclosed HTML tag `{}`", name),
+                     "this tag is never closed",
+                     "the tag",
+                     "if this is meant as text rather than HTML, put it in backticks or escape \
+                      the `<` as `\\<`".to_owned())
+                }
+                MarkdownProblem::UnopenedHtmlTag(name) => {
+                    (lint::builtin::INVALID_HTML_TAGS,
+                     format!("unopened HTML tag `{}`", name),
+                     "this tag is never opened",
+                     "the tag",
+                     "if this is meant as text rather than HTML, put it in backticks or escape \
+                      the `<` as `\\<`".to_owned())
+                }
+                MarkdownProblem::BareUrl(url) => {
+                    (lint::builtin::BARE_URLS,
+                     "this URL is not a hyperlink".to_owned(),
+                     "not a hyperlink",
+                     "the URL",
+                     format!("use an automatic link instead: `<{}>`", url))
+                }
+                MarkdownProblem::UnclosedCodeBlock => {
+                    (lint::builtin::UNCLOSED_CODE_BLOCKS,
+                     "this code block is never closed".to_owned(),
+                     "this fence is never closed",
+                     "the fence",
+                     "the rest of the documentation is part of the code block, add a closing \
+                      fence where it ends".to_owned())
+                }
+                MarkdownProblem::BrokenFootnoteReference(label) => {
+                    (lint::builtin::BROKEN_FOOTNOTE_REFERENCES,
+                     format!("the footnote `[^{}]` is not defined", label),
+                     "undefined footnote",
+                     "the footnote reference",
+                     format!("define it on a line starting with `[^{}]:`", label))
+                }
+            };
+
+            let span = span_of_markdown_range(cx, &dox, &range, &item.attrs);
+            let mut diag = cx.tcx.struct_span_lint_node(lint,
+                                                        node_id,
+                                                        span.unwrap_or_else(|| {
+                                                            span_of_attrs(&item.attrs)
+                                                        }),
+                                                        &msg);
+            if let Some(span) = span {
+                diag.span_label(span, label);
+            } else {
+                note_markdown_range(&mut diag, &dox, &range, what);
+            }
+            diag.help(&help);
+            diag.emit();
+        }
+
+        self.fold_item_recur(item)
+    }
+}
@@ -19,6 +19,7 @@ use syntax::ast::{self, Ident, NodeId};
 use syntax::feature_gate::UnstableFeatures;
 use syntax::symbol::Symbol;
 use syntax_pos::{self, DUMMY_SP};
+use errors::DiagnosticBuilder;
 
 use std::ops::Range;
 
@@ -460,6 +461,65 @@ pub fn span_of_attrs(attrs: &Attributes) -> syntax_pos::Span {
     start.to(end)
 }
 
+/// Returns the span in the source of the `range` of `dox`, the collapsed documentation of
+/// `attrs`, or `None` if the documentation doesn't map line by line to the doc comments.
+pub fn span_of_markdown_range(
+    cx: &DocContext,
+    dox: &str,
+    range: &Range<usize>,
+    attrs: &Attributes,
+) -> Option<syntax_pos::Span> {
+    let sp = span_of_attrs(attrs);
+    let code_dox = sp.to_src(cx);
+
+    let doc_comment_padding = 3;
+    if dox.lines().count() != code_dox.lines().count() {
+        return None;
+    }
+
+    // blah blah blah\nblah\nblah [blah] blah blah\nblah blah
+    //                       ^    ~~~~~~
+    //                       |    range
+    //                       last_new_line_offset
+    let line_offset = dox[..range.start].lines().count();
+    // The span starts in the `///`, so we don't have to account for the leading whitespace
+    let code_dox_len = if line_offset <= 1 {
+        doc_comment_padding
+    } else {
+        // The first `///`
+        doc_comment_padding +
+            // Each subsequent leading whitespace and `///`
+            code_dox.lines().skip(1).take(line_offset - 1).fold(0, |sum, line| {
+                sum + doc_comment_padding + line.len() - line.trim_start().len()
+            })
+    };
+
+    // Extract the specific span
+    Some(sp.from_inner_byte_pos(
+        range.start + code_dox_len,
+        range.end + code_dox_len,
+    ))
+}
+
+/// Adds a note to `diag` showing the line of `dox` with the `range`, marked with `^`s, for when
+/// the range has no span of its own.
+pub fn note_markdown_range(diag: &mut DiagnosticBuilder, dox: &str, range: &Range<usize>,
+                           what: &str) {
+    let last_new_line_offset = dox[..range.start].rfind('\n').map_or(0, |n| n + 1);
+    let line = dox[last_new_line_offset..].lines().next().unwrap_or("");
+
+    // Print the line containing the `range` and manually mark it with '^'s
+    diag.note(&format!(
+        "{what} appears in this line:\n\n{line}\n\
+         {indicator: <before$}{indicator:^<found$}",
+        what=what,
+        line=line,
+        indicator="",
+        before=range.start - last_new_line_offset,
+        found=range.len(),
+    ));
+}
+
 fn resolution_failure(
     cx: &DocContext,
     attrs: &Attributes,
@@ -470,36 +530,9 @@ fn resolution_failure(
     let sp = span_of_attrs(attrs);
     let msg = format!("`[{}]` cannot be resolved, ignoring it...", path_str);
 
-    let code_dox = sp.to_src(cx);
-
-    let doc_comment_padding = 3;
     let mut diag = if let Some(link_range) = link_range {
-        // blah blah blah\nblah\nblah [blah] blah blah\nblah blah
-        //                       ^    ~~~~~~
-        //                       |    link_range
-        //                       last_new_line_offset
-
         let mut diag;
-        if dox.lines().count() == code_dox.lines().count() {
-            let line_offset = dox[..link_range.start].lines().count();
-            // The span starts in the `///`, so we don't have to account for the leading whitespace
-            let code_dox_len = if line_offset <= 1 {
-                doc_comment_padding
-            } else {
-                // The first `///`
-                doc_comment_padding +
-                    // Each subsequent leading whitespace and `///`
-                    code_dox.lines().skip(1).take(line_offset - 1).fold(0, |sum, line| {
-                        sum + doc_comment_padding + line.len() - line.trim_start().len()
-                    })
-            };
-
-            // Extract the specific span
-            let sp = sp.from_inner_byte_pos(
-                link_range.start + code_dox_len,
-                link_range.end + code_dox_len,
-            );
-
+        if let Some(sp) = span_of_markdown_range(cx, dox, &link_range, attrs) {
             diag = cx.tcx.struct_span_lint_node(lint::builtin::INTRA_DOC_LINK_RESOLUTION_FAILURE,
                                                 NodeId::from_u32(0),
                                                 sp,
@@ -510,19 +543,7 @@ fn resolution_failure(
                                                 NodeId::from_u32(0),
                                                 sp,
                                                 &msg);
-
-            let last_new_line_offset = dox[..link_range.start].rfind('\n').map_or(0, |n| n + 1);
-            let line = dox[last_new_line_offset..].lines().next().unwrap_or("");
-
-            // Print the line containing the `link_range` and manually mark it with '^'s
-            diag.note(&format!(
-                "the link appears in this line:\n\n{line}\n\
-                 {indicator: <before$}{indicator:^<found$}",
-                line=line,
-                indicator="",
-                before=link_range.start - last_new_line_offset,
-                found=link_range.len(),
-            ));
+            note_markdown_range(&mut diag, dox, &link_range, "the link");
         }
         diag
     } else {
@@ -55,6 +55,9 @@ pub use self::private_items_doc_tests::CHECK_PRIVATE_ITEMS_DOC_TESTS;
 mod collect_trait_impls;
 pub use self::collect_trait_impls::COLLECT_TRAIT_IMPLS;
 
+mod check_doc_markdown;
+pub use self::check_doc_markdown::CHECK_DOC_MARKDOWN;
+
 /// Represents a single pass.
 #[derive(Copy, Clone)]
 pub enum Pass {
@@ -146,6 +149,7 @@ pub const PASSES: &'static [Pass] = &[
     PROPAGATE_DOC_CFG,
     COLLECT_INTRA_DOC_LINKS,
     COLLECT_TRAIT_IMPLS,
+    CHECK_DOC_MARKDOWN,
 ];
 
 /// The list of passes run by default.
@@ -155,6 +159,7 @@ pub const DEFAULT_PASSES: &'static [&'static str] = &[
     "strip-hidden",
     "strip-private",
     "collect-intra-doc-links",
+    "check-doc-markdown",
     "collapse-docs",
     "unindent-comments",
     "propagate-doc-cfg",
@@ -166,6 +171,7 @@ pub const DEFAULT_PRIVATE_PASSES: &'static [&'static str] = &[
     "check-private-items-doc-tests",
     "strip-priv-imports",
     "collect-intra-doc-links",
+    "check-doc-markdown",
     "collapse-docs",
     "unindent-comments",
     "propagate-doc-cfg",
@@ -0,0 +1,241 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The examples found in the repository of a crate, scraped from the crates using it with
+//! `--scrape-examples-output-path` and rendered on the pages of its items with
+//! `--with-examples`.
+//!
+//! Scraping runs on an example, or a test, like documenting it would, and then looks in its
+//! bodies for the calls that type checking resolved to a function or a method of one of the
+//! `--scrape-examples-target-crate` crates. Each call is written down with the item it is in,
+//! which is the code shown as the example, by the definition path of the function called, so
+//! that documenting the target crate can find the calls to its items. The `#[test]` functions
+//! of a test only exist, and are only scraped, when it is compiled as a test with
+//! `--scrape-tests`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use rustc::hir::{self, intravisit};
+use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
+use rustc::ty::{self, TyCtxt};
+use rustc::util::nodemap::FxHashMap;
+use serialize::json;
+use syntax_pos::{FileName, Span};
+
+use core::DocContext;
+use html::render::clean_srcpath;
+use html::span_map::is_in_source;
+
+/// A call found in an example.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct CallLocation {
+    /// The byte range in the file of the name of the function or method called.
+    pub call_ident: (u32, u32),
+    /// The first and last lines of the call.
+    pub call_lines: (usize, usize),
+    /// The byte range in the file of the item the call is in.
+    pub enclosing_item: (u32, u32),
+}
+
+/// The calls to an item found in a file of an example.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct CallData {
+    pub locations: Vec<CallLocation>,
+    /// The URL of the source page of the file, from the root of the documentation.
+    pub url: String,
+    /// The path of the file from the root of the example, to show.
+    pub display_name: String,
+}
+
+/// The calls to an item, by the path of the file they were found in.
+pub type FnCallLocations = BTreeMap<String, CallData>;
+
+/// The calls to the items of the target crates, by the definition path of the item called.
+pub type AllCallLocations = BTreeMap<String, FnCallLocations>;
+
+/// The key of `def_id` in `AllCallLocations`, the same in the crate that defines it and in the
+/// crates using it.
+fn def_path_key(tcx: TyCtxt, def_id: DefId) -> String {
+    format!("{}{}", tcx.crate_name(def_id.krate), tcx.def_path(def_id).to_string_no_crate())
+}
+
+/// Finds the calls to the items of `target_crates` in the crate being documented.
+pub fn collect(cx: &DocContext, target_crates: &[String]) -> AllCallLocations {
+    // The files are named from the directory of the root of the crate, like its source pages.
+    let src_root = cx.sess().local_crate_source_file.as_ref()
+                            .and_then(|file| file.parent())
+                            .map(Path::to_path_buf)
+                            .unwrap_or_default();
+    let mut finder = FindCalls {
+        cx,
+        target_crates,
+        src_root,
+        tables: None,
+        enclosing_item: None,
+        calls: AllCallLocations::new(),
+    };
+    intravisit::walk_crate(&mut finder, cx.tcx.hir.krate());
+    finder.calls
+}
+
+/// Writes the calls scraped from an example to `path`, for `--with-examples`.
+pub fn write(path: &Path, calls: &AllCallLocations) -> Result<(), String> {
+    let encoded = json::encode(calls).map_err(|e| e.to_string())?;
+    fs::write(path, encoded).map_err(|e| format!("failed to write `{}`: {}", path.display(), e))
+}
+
+/// Reads the calls scraped from the examples at `paths`, and returns those to the items of the
+/// crate being documented.
+pub fn load(cx: &DocContext,
+            paths: &[PathBuf]) -> Result<FxHashMap<DefId, FnCallLocations>, String> {
+    let mut all_calls = AllCallLocations::new();
+    for path in paths {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read `{}`: {}", path.display(), e))?;
+        let calls: AllCallLocations = json::decode(&contents)
+            .map_err(|e| format!("failed to parse `{}`: {}", path.display(), e))?;
+        for (key, files) in calls {
+            all_calls.entry(key).or_default().extend(files);
+        }
+    }
+
+    let tcx = cx.tcx;
+    let krate = tcx.hir.krate();
+    let ids = krate.items.keys().cloned()
+        .chain(krate.trait_items.keys().map(|id| id.node_id))
+        .chain(krate.impl_items.keys().map(|id| id.node_id));
+    Ok(ids.filter_map(|id| {
+        let def_id = tcx.hir.local_def_id(id);
+        all_calls.remove(&def_path_key(tcx, def_id)).map(|calls| (def_id, calls))
+    }).collect())
+}
+
+struct FindCalls<'a, 'tcx: 'a, 'rcx: 'a, 'cstore: 'rcx> {
+    cx: &'a DocContext<'a, 'tcx, 'rcx, 'cstore>,
+    target_crates: &'a [String],
+    src_root: PathBuf,
+    /// The type checking results of the body being visited, if any.
+    tables: Option<&'tcx ty::TypeckTables<'tcx>>,
+    /// The span of the item whose body is being visited; closures are part of the item they
+    /// are in.
+    enclosing_item: Option<Span>,
+    calls: AllCallLocations,
+}
+
+impl<'a, 'tcx, 'rcx, 'cstore> FindCalls<'a, 'tcx, 'rcx, 'cstore> {
+    /// Records a call to `def`, whose name is at `ident_span`, if it is a function or a method
+    /// of a target crate.
+    fn record(&mut self, call_span: Span, ident_span: Span, def: Def) {
+        let tcx = self.cx.tcx;
+        let def_id = match def {
+            Def::Fn(def_id) | Def::Method(def_id) => def_id,
+            _ => return,
+        };
+        let crate_name = tcx.crate_name(def_id.krate).to_string();
+        if def_id.is_local() || !self.target_crates.contains(&crate_name) {
+            return;
+        }
+        let enclosing_item = match self.enclosing_item {
+            Some(span) => span,
+            None => return,
+        };
+        if [call_span, ident_span, enclosing_item].iter().any(|&span| !is_in_source(span)) {
+            return;
+        }
+
+        let source_map = tcx.sess.source_map();
+        let file = source_map.lookup_byte_offset(enclosing_item.lo()).sf;
+        let path = match file.name {
+            FileName::Real(ref path) => path.clone(),
+            _ => return,
+        };
+        if !file.contains(call_span.lo()) || !file.contains(enclosing_item.hi()) {
+            return;
+        }
+        let offset = |span: Span| {
+            (span.lo().0 - file.start_pos.0, span.hi().0 - file.start_pos.0)
+        };
+        let location = CallLocation {
+            call_ident: offset(ident_span),
+            call_lines: (source_map.lookup_char_pos(call_span.lo()).line,
+                         source_map.lookup_char_pos(call_span.hi()).line),
+            enclosing_item: offset(enclosing_item),
+        };
+
+        let local_crate = tcx.crate_name(hir::def_id::LOCAL_CRATE);
+        let src_root = &self.src_root;
+        let entry = self.calls.entry(def_path_key(tcx, def_id))
+                              .or_default()
+                              .entry(path.display().to_string())
+                              .or_insert_with(|| {
+            let mut url = format!("src/{}/", local_crate);
+            let mut display_name = String::new();
+            clean_srcpath(src_root, &path, true, |component| {
+                if !display_name.is_empty() {
+                    display_name.push('/');
+                }
+                display_name.push_str(component);
+            });
+            url.push_str(&display_name);
+            url.push_str(".html");
+            CallData { locations: Vec::new(), url, display_name }
+        });
+        entry.locations.push(location);
+    }
+}
+
+impl<'a, 'tcx, 'rcx, 'cstore> intravisit::Visitor<'tcx> for FindCalls<'a, 'tcx, 'rcx, 'cstore> {
+    fn nested_visit_map<'this>(&'this mut self) -> intravisit::NestedVisitorMap<'this, 'tcx> {
+        intravisit::NestedVisitorMap::All(&self.cx.tcx.hir)
+    }
+
+    fn visit_nested_body(&mut self, id: hir::BodyId) {
+        let tcx = self.cx.tcx;
+        let old_tables = mem::replace(&mut self.tables, Some(tcx.body_tables(id)));
+        let old_enclosing_item = self.enclosing_item;
+        if old_enclosing_item.is_none() {
+            self.enclosing_item = Some(tcx.hir.span(tcx.hir.body_owner(id)));
+        }
+        self.visit_body(tcx.hir.body(id));
+        self.tables = old_tables;
+        self.enclosing_item = old_enclosing_item;
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        if let Some(tables) = self.tables {
+            match expr.node {
+                hir::ExprKind::Call(ref f, _) => {
+                    if let hir::ExprKind::Path(ref qpath) = f.node {
+                        let ident_span = match *qpath {
+                            hir::QPath::Resolved(_, ref path) => {
+                                path.segments.last().map(|segment| segment.ident.span)
+                            }
+                            hir::QPath::TypeRelative(_, ref segment) => Some(segment.ident.span),
+                        };
+                        if let Some(ident_span) = ident_span {
+                            self.record(expr.span, ident_span, tables.qpath_def(qpath, f.hir_id));
+                        }
+                    }
+                }
+                hir::ExprKind::MethodCall(ref segment, ..) => {
+                    if let Some(&def) = tables.type_dependent_defs().get(expr.hir_id) {
+                        self.record(expr.span, segment.ident.span, def);
+                    }
+                }
+                _ => {}
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
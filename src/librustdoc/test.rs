@@ -10,12 +10,15 @@
 
 use std::env;
 use std::ffi::OsString;
+use std::fs;
 use std::io::prelude::*;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::panic::{self, AssertUnwindSafe};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
 use std::str;
+use std::thread;
+use std::time::{Duration, Instant};
 use rustc_data_structures::sync::Lrc;
 use std::sync::{Arc, Mutex};
 
@@ -26,8 +29,9 @@ use rustc::hir::intravisit;
 use rustc::session::{self, CompileIncomplete, config};
 use rustc::session::config::{OutputType, OutputTypes, Externs, CodegenOptions};
 use rustc::session::search_paths::{SearchPaths, PathKind};
+use rustc::util::nodemap::FxHashMap;
 use rustc_metadata::dynamic_lib::DynamicLibrary;
-use tempfile::Builder as TempFileBuilder;
+use tempfile::{Builder as TempFileBuilder, TempDir};
 use rustc_driver::{self, driver, target_features, Compilation};
 use rustc_driver::driver::phase_2_configure_and_expand;
 use rustc_metadata::cstore::CStore;
@@ -127,7 +131,11 @@ pub fn run(mut options: Options) -> isize {
             Some(source_map),
             None,
             options.linker,
-            options.edition
+            options.edition,
+            options.merge_doctests,
+            options.persist_doctests,
+            options.doctest_timeout,
+            options.doctest_filters,
         );
 
         {
@@ -191,10 +199,100 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
             cg: CodegenOptions, externs: Externs,
             should_panic: bool, no_run: bool, as_test_harness: bool,
             compile_fail: bool, mut error_codes: Vec<String>, opts: &TestOptions,
-            maybe_sysroot: Option<PathBuf>, linker: Option<PathBuf>, edition: Edition) {
+            maybe_sysroot: Option<PathBuf>, linker: Option<PathBuf>, edition: Edition,
+            persist_dir: Option<PathBuf>, timeout: Option<Duration>) {
     // the test harness wants its own `main` & top level functions, so
     // never wrap the test in `fn main() { ... }`
     let (test, line_offset) = make_test(test, Some(cratename), as_test_harness, opts);
+
+    // Shuffle around a few input and output handles here. We're going to pass
+    // an explicit handle into rustc to collect output messages, but we also
+    // want to catch the error message that rustc prints when it fails.
+    //
+    // We take our thread-local stderr (likely set by the test runner) and replace
+    // it with a sink that is also passed to rustc itself. When this function
+    // returns the output of the sink is copied onto the output of our own thread.
+    //
+    // The basic idea is to not use a default Handler for rustc, and then also
+    // not print things by default to the actual stderr.
+    struct Bomb(Arc<Mutex<Vec<u8>>>, Box<dyn Write+Send>);
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            let _ = self.1.write_all(&self.0.lock().unwrap());
+        }
+    }
+    let data = Arc::new(Mutex::new(Vec::new()));
+
+    let old = io::set_panic(Some(box Sink(data.clone())));
+    let _bomb = Bomb(data.clone(), old.unwrap_or(box io::stdout()));
+
+    let (libdir, outdir, compile_result) = compile_test(test, filename, line, line_offset,
+                                                        cfgs, libs, cg, externs, no_run,
+                                                        as_test_harness, &data, maybe_sysroot,
+                                                        linker, edition, persist_dir);
+
+    match (compile_result, compile_fail) {
+        (Ok(()), true) => {
+            panic!("test compiled while it wasn't supposed to")
+        }
+        (Ok(()), false) => {}
+        (Err(()), true) => {
+            if error_codes.len() > 0 {
+                let out = String::from_utf8(data.lock().unwrap().to_vec()).unwrap();
+                error_codes.retain(|err| !out.contains(err));
+            }
+        }
+        (Err(()), false) => {
+            panic!("couldn't compile the test")
+        }
+    }
+
+    if error_codes.len() > 0 {
+        panic!("Some expected error codes were not found: {:?}", error_codes);
+    }
+
+    if no_run { return }
+
+    run_executable(outdir.path(), libdir, &[], should_panic, timeout);
+}
+
+struct Sink(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Sink {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        Write::write(&mut *self.0.lock().unwrap(), data)
+    }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+/// The directory a doctest is compiled into.
+enum OutDir {
+    /// A temporary directory, deleted once the test has run.
+    Temp(TempDir),
+    /// A directory in the one given with `--persist-doctests`, which is kept.
+    Persist(PathBuf),
+}
+
+impl OutDir {
+    fn path(&self) -> &Path {
+        match *self {
+            OutDir::Temp(ref dir) => dir.path(),
+            OutDir::Persist(ref path) => path,
+        }
+    }
+}
+
+/// Compiles `test` into an executable called `rust_out`, writing the diagnostics to `data`.
+/// The executable is put in `persist_dir` if given, and in a temporary directory otherwise.
+/// Returns the target's library directory, the directory containing the executable and
+/// whether the compilation succeeded.
+fn compile_test(test: String, filename: &FileName, line: usize, line_offset: usize,
+                cfgs: Vec<String>, libs: SearchPaths,
+                cg: CodegenOptions, externs: Externs,
+                no_run: bool, as_test_harness: bool, data: &Arc<Mutex<Vec<u8>>>,
+                maybe_sysroot: Option<PathBuf>, linker: Option<PathBuf>, edition: Edition,
+                persist_dir: Option<PathBuf>)
+                -> (PathBuf, OutDir, Result<(), ()>) {
     // FIXME(#44940): if doctests ever support path remapping, then this filename
     // needs to be the result of SourceMap::span_to_unmapped_path
     let input = config::Input::Str {
@@ -223,34 +321,6 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
         ..config::Options::default()
     };
 
-    // Shuffle around a few input and output handles here. We're going to pass
-    // an explicit handle into rustc to collect output messages, but we also
-    // want to catch the error message that rustc prints when it fails.
-    //
-    // We take our thread-local stderr (likely set by the test runner) and replace
-    // it with a sink that is also passed to rustc itself. When this function
-    // returns the output of the sink is copied onto the output of our own thread.
-    //
-    // The basic idea is to not use a default Handler for rustc, and then also
-    // not print things by default to the actual stderr.
-    struct Sink(Arc<Mutex<Vec<u8>>>);
-    impl Write for Sink {
-        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
-            Write::write(&mut *self.0.lock().unwrap(), data)
-        }
-        fn flush(&mut self) -> io::Result<()> { Ok(()) }
-    }
-    struct Bomb(Arc<Mutex<Vec<u8>>>, Box<dyn Write+Send>);
-    impl Drop for Bomb {
-        fn drop(&mut self) {
-            let _ = self.1.write_all(&self.0.lock().unwrap());
-        }
-    }
-    let data = Arc::new(Mutex::new(Vec::new()));
-
-    let old = io::set_panic(Some(box Sink(data.clone())));
-    let _bomb = Bomb(data.clone(), old.unwrap_or(box io::stdout()));
-
     let (libdir, outdir, compile_result) = driver::spawn_thread_pool(sessopts, |sessopts| {
         let source_map = Lrc::new(SourceMap::new_doctest(
             sessopts.file_path_mapping(), filename.clone(), line as isize - line_offset as isize
@@ -270,9 +340,18 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
         let cstore = CStore::new(codegen_backend.metadata_loader());
         rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
 
-        let outdir = Mutex::new(
-            TempFileBuilder::new().prefix("rustdoctest").tempdir().expect("rustdoc needs a tempdir")
-        );
+        let outdir = Mutex::new(match persist_dir {
+            Some(dir) => {
+                if let Err(e) = fs::create_dir_all(&dir) {
+                    panic!("couldn't create directory {} for the doctest: {}", dir.display(), e);
+                }
+                OutDir::Persist(dir)
+            }
+            None => OutDir::Temp(
+                TempFileBuilder::new().prefix("rustdoctest").tempdir()
+                                      .expect("rustdoc needs a tempdir")
+            ),
+        });
         let libdir = sess.target_filesearch(PathKind::All).get_lib_path();
         let mut control = driver::CompileController::basic();
 
@@ -308,35 +387,21 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
         (libdir, outdir, compile_result)
     });
 
-    match (compile_result, compile_fail) {
-        (Ok(()), true) => {
-            panic!("test compiled while it wasn't supposed to")
-        }
-        (Ok(()), false) => {}
-        (Err(()), true) => {
-            if error_codes.len() > 0 {
-                let out = String::from_utf8(data.lock().unwrap().to_vec()).unwrap();
-                error_codes.retain(|err| !out.contains(err));
-            }
-        }
-        (Err(()), false) => {
-            panic!("couldn't compile the test")
-        }
-    }
-
-    if error_codes.len() > 0 {
-        panic!("Some expected error codes were not found: {:?}", error_codes);
-    }
-
-    if no_run { return }
+    (libdir, outdir.into_inner().unwrap(), compile_result)
+}
 
+/// Runs the `rust_out` executable in `outdir` with `args`, and checks that it fails if and only
+/// if the test should panic, and that it finishes within `timeout` if given.
+fn run_executable(outdir: &Path, libdir: PathBuf, args: &[String], should_panic: bool,
+                  timeout: Option<Duration>) {
     // Run the code!
     //
     // We're careful to prepend the *target* dylib search path to the child's
     // environment to ensure that the target loads the right libraries at
     // runtime. It would be a sad day if the *host* libraries were loaded as a
     // mistake.
-    let mut cmd = Command::new(&outdir.lock().unwrap().path().join("rust_out"));
+    let mut cmd = Command::new(&outdir.join("rust_out"));
+    cmd.args(args);
     let var = DynamicLibrary::envvar();
     let newpath = {
         let path = env::var_os(var).unwrap_or(OsString::new());
@@ -346,12 +411,20 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
     };
     cmd.env(var, &newpath);
 
-    match cmd.output() {
+    let output = match timeout {
+        Some(timeout) => output_with_timeout(cmd, timeout),
+        None => cmd.output().map(Some),
+    };
+    match output {
         Err(e) => panic!("couldn't run the test: {}{}", e,
                         if e.kind() == io::ErrorKind::PermissionDenied {
                             " - maybe your tempdir is mounted with noexec?"
                         } else { "" }),
-        Ok(out) => {
+        Ok(None) => {
+            panic!("test executable timed out after {} seconds",
+                   timeout.map_or(0, |timeout| timeout.as_secs()))
+        }
+        Ok(Some(out)) => {
             if should_panic && out.status.success() {
                 panic!("test executable succeeded when it should have failed");
             } else if !should_panic && !out.status.success() {
@@ -363,6 +436,144 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
     }
 }
 
+/// Runs `cmd` and collects its output like `Command::output`, but kills it and returns `None`
+/// if it is still running after `timeout`.
+fn output_with_timeout(mut cmd: Command, timeout: Duration) -> io::Result<Option<Output>> {
+    fn read_all<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    }
+
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    // The output is read on other threads, so that the child never blocks on a full pipe.
+    let stdout = read_all(child.stdout.take());
+    let stderr = read_all(child.stderr.take());
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if start.elapsed() >= timeout {
+            // The child may have exited in the meantime, in which case there is nothing to kill.
+            let _ = child.kill();
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    Ok(Some(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    }))
+}
+
+/// The doctests that are compiled together into a single executable with `--merge-doctests`,
+/// shared by the test functions of all of them.
+///
+/// The executable is compiled by the first of these tests to run, after all the tests have been
+/// collected, and runs the test whose index it is given as its argument. If the tests do not
+/// compile together, each of them is compiled on its own instead, which reports the errors of
+/// the tests that fail to compile.
+struct MergedDoctests {
+    /// The code of each test, which becomes the body of its `main` function.
+    bodies: Vec<String>,
+    /// Once the executable has been compiled, the target's library directory and the directory
+    /// containing the executable, or `None` if the tests did not compile together.
+    compiled: Option<Option<(PathBuf, OutDir)>>,
+}
+
+/// Returns the code of the doctest `s` to compile together with the other doctests, if it has
+/// neither crate attributes nor `extern crate` items, nor a `fn main` of its own.
+fn merged_test_body(s: &str) -> Option<String> {
+    let (crate_attrs, everything_else, _) = partition_source(s);
+    if !crate_attrs.trim().is_empty() {
+        return None;
+    }
+    let everything_else = everything_else.trim();
+    let (has_main, _) = check_for_main_and_extern_crate(everything_else.to_string(), None);
+    if has_main {
+        None
+    } else {
+        Some(everything_else.to_string())
+    }
+}
+
+/// Makes the file of the merged doctests: each test is the `main` function of a module named
+/// after its index, and the executable's `main` calls the one it is given the index of.
+fn make_merged_test(bodies: &[String], cratename: &str, opts: &TestOptions) -> String {
+    let mut prog = String::new();
+
+    if opts.attrs.is_empty() && !opts.display_warnings {
+        prog.push_str("#![allow(unused)]\n");
+    }
+    for attr in &opts.attrs {
+        prog.push_str(&format!("#![{}]\n", attr));
+    }
+    // The modules of the tests import the crate from the crate root.
+    if !opts.no_crate_inject && cratename != "std" &&
+        bodies.iter().any(|body| body.contains(cratename)) {
+        prog.push_str(&format!("extern crate {};\n", cratename));
+    }
+
+    for (index, body) in bodies.iter().enumerate() {
+        prog.push_str(&format!("mod __doctest_{} {{\n", index));
+        prog.push_str("#[allow(unused_imports)]\nuse super::*;\n");
+        prog.push_str("pub fn main() {\n");
+        prog.push_str(body);
+        prog.push_str("\n}\n}\n");
+    }
+
+    prog.push_str("fn main() {\n");
+    prog.push_str("    let index = ::std::env::args().nth(1).and_then(|arg| arg.parse().ok());\n");
+    prog.push_str("    match index {\n");
+    for index in 0..bodies.len() {
+        prog.push_str(&format!("        Some({0}) => __doctest_{0}::main(),\n", index));
+    }
+    prog.push_str("        _ => panic!(\"expected the index of a doctest\"),\n");
+    prog.push_str("    }\n}");
+
+    info!("merged test program: {}", prog);
+
+    prog
+}
+
+/// Compiles the merged doctests if no test has done so yet, into `persist_dir` if given.
+/// Returns the target's library directory and the directory containing the executable if they
+/// compiled.
+fn compile_merged_tests(merged: &Mutex<MergedDoctests>, cratename: &str,
+                        cfgs: Vec<String>, libs: SearchPaths,
+                        cg: CodegenOptions, externs: Externs, opts: &TestOptions,
+                        maybe_sysroot: Option<PathBuf>, linker: Option<PathBuf>,
+                        edition: Edition, persist_dir: Option<PathBuf>)
+                        -> Option<(PathBuf, PathBuf)> {
+    // The lock is held while compiling, so that the other merged tests wait for the executable.
+    let mut merged = merged.lock().unwrap();
+    if merged.compiled.is_none() {
+        let test = make_merged_test(&merged.bodies, cratename, opts);
+        let filename = FileName::Custom("merged doctests".to_owned());
+        // The errors are reported when the tests are compiled on their own.
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let (libdir, outdir, compile_result) = compile_test(test, &filename, 0, 0, cfgs, libs,
+                                                            cg, externs, false, false, &data,
+                                                            maybe_sysroot, linker, edition,
+                                                            persist_dir);
+        if compile_result.is_err() {
+            debug!("the merged doctests did not compile: {}",
+                   String::from_utf8_lossy(&data.lock().unwrap()));
+        }
+        merged.compiled = Some(compile_result.ok().map(|()| (libdir, outdir)));
+    }
+    merged.compiled.as_ref().unwrap().as_ref().map(|&(ref libdir, ref outdir)| {
+        (libdir.clone(), outdir.path().to_path_buf())
+    })
+}
+
 /// Makes the test file. Also returns the number of lines before the code begins
 pub fn make_test(s: &str,
                  cratename: Option<&str>,
@@ -395,14 +606,46 @@ pub fn make_test(s: &str,
 
     // Uses libsyntax to parse the doctest and find if there's a main fn and the extern
     // crate already is included.
-    let (already_has_main, already_has_extern_crate) = crate::syntax::with_globals(|| {
+    let source = crates + &everything_else;
+    let (already_has_main, already_has_extern_crate) =
+        check_for_main_and_extern_crate(source, cratename);
+
+    // Don't inject `extern crate std` because it's already injected by the
+    // compiler.
+    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
+        if let Some(cratename) = cratename {
+            // Make sure its actually used if not included.
+            if s.contains(cratename) {
+                prog.push_str(&format!("extern crate {};\n", cratename));
+                line_offset += 1;
+            }
+        }
+    }
+
+    if dont_insert_main || already_has_main {
+        prog.push_str(everything_else);
+    } else {
+        prog.push_str("fn main() {\n");
+        line_offset += 1;
+        prog.push_str(everything_else);
+        prog.push_str("\n}");
+    }
+
+    info!("final test program: {}", prog);
+
+    (prog, line_offset)
+}
+
+/// Parses `source` to find if it has a `fn main`, and whether it has an `extern crate` item
+/// for `cratename`, which is always the case if `cratename` is `None`.
+fn check_for_main_and_extern_crate(source: String, cratename: Option<&str>) -> (bool, bool) {
+    crate::syntax::with_globals(|| {
         use crate::syntax::{ast, parse::{self, ParseSess}, source_map::FilePathMapping};
         use crate::syntax_pos::FileName;
         use errors::emitter::EmitterWriter;
         use errors::Handler;
 
         let filename = FileName::Anon;
-        let source = crates + &everything_else;
 
         // any errors in parsing should also appear when the doctest is compiled for real, so just
         // send all the errors that libsyntax emits directly into a Sink instead of stderr
@@ -464,32 +707,7 @@ pub fn make_test(s: &str,
         }
 
         (found_main, found_extern_crate)
-    });
-
-    // Don't inject `extern crate std` because it's already injected by the
-    // compiler.
-    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
-        if let Some(cratename) = cratename {
-            // Make sure its actually used if not included.
-            if s.contains(cratename) {
-                prog.push_str(&format!("extern crate {};\n", cratename));
-                line_offset += 1;
-            }
-        }
-    }
-
-    if dont_insert_main || already_has_main {
-        prog.push_str(everything_else);
-    } else {
-        prog.push_str("fn main() {\n");
-        line_offset += 1;
-        prog.push_str(everything_else);
-        prog.push_str("\n}");
-    }
-
-    info!("final test program: {}", prog);
-
-    (prog, line_offset)
+    })
 }
 
 // FIXME(aburka): use a real parser to deal with multiline attributes
@@ -555,6 +773,9 @@ pub struct Collector {
     //
     // the `names` vector of that test will be `["Title", "Subtitle"]`.
     names: Vec<String>,
+    /// The number of tests found so far for each file and path in `names`, which numbers the
+    /// tests of the same item in their names.
+    test_indices: FxHashMap<String, usize>,
 
     cfgs: Vec<String>,
     libs: SearchPaths,
@@ -569,16 +790,27 @@ pub struct Collector {
     filename: Option<PathBuf>,
     linker: Option<PathBuf>,
     edition: Edition,
+    /// The doctests compiled into a single executable, with `--merge-doctests`.
+    merged: Option<Arc<Mutex<MergedDoctests>>>,
+    /// The directory to keep the compiled tests in, with `--persist-doctests`.
+    persist_doctests: Option<PathBuf>,
+    /// How long each test may run, with `--doctest-timeout`.
+    timeout: Option<Duration>,
+    /// The paths of the items whose tests are run, with `--doctest-filter`.
+    filters: Vec<String>,
 }
 
 impl Collector {
     pub fn new(cratename: String, cfgs: Vec<String>, libs: SearchPaths, cg: CodegenOptions,
                externs: Externs, use_headers: bool, opts: TestOptions,
                maybe_sysroot: Option<PathBuf>, source_map: Option<Lrc<SourceMap>>,
-               filename: Option<PathBuf>, linker: Option<PathBuf>, edition: Edition) -> Collector {
+               filename: Option<PathBuf>, linker: Option<PathBuf>, edition: Edition,
+               merge_doctests: bool, persist_doctests: Option<PathBuf>,
+               timeout: Option<Duration>, filters: Vec<String>) -> Collector {
         Collector {
             tests: Vec::new(),
             names: Vec::new(),
+            test_indices: FxHashMap::default(),
             cfgs,
             libs,
             cg,
@@ -592,11 +824,55 @@ impl Collector {
             filename,
             linker,
             edition,
+            merged: if merge_doctests {
+                Some(Arc::new(Mutex::new(MergedDoctests { bodies: Vec::new(), compiled: None })))
+            } else {
+                None
+            },
+            persist_doctests,
+            timeout,
+            filters,
+        }
+    }
+
+    /// Adds `test` to the merged doctests if it can be compiled with them, returning them and
+    /// the index of the test.
+    fn merge_test(&self, test: &str, config: &LangString)
+                  -> Option<(Arc<Mutex<MergedDoctests>>, usize)> {
+        let merged = self.merged.as_ref()?;
+        if config.compile_fail || config.no_run || config.test_harness || config.ignore ||
+            config.allow_fail || !config.error_codes.is_empty() ||
+            config.edition.map_or(false, |edition| edition != self.edition) {
+            return None;
         }
+        let body = merged_test_body(test)?;
+        let mut tests = merged.lock().unwrap();
+        tests.bodies.push(body);
+        Some((merged.clone(), tests.bodies.len() - 1))
+    }
+
+    /// Names the next test of the current item, at `line`, after the item's path and the index of
+    /// the test among those of the item, which, unlike its line, doesn't change when the file is
+    /// edited. The line is still given after them, to find the test in the file.
+    fn generate_name(&mut self, filename: &FileName, line: usize) -> String {
+        let prefix = format!("{} - {}", filename, self.names.join("::"));
+        let index = self.test_indices.entry(prefix.clone()).or_insert(0);
+        let name = format!("{} ({}) (line {})", prefix, index, line);
+        *index += 1;
+        name
     }
 
-    fn generate_name(&self, line: usize, filename: &FileName) -> String {
-        format!("{} - {} (line {})", filename, self.names.join("::"), line)
+    /// Whether the tests of the current item are run, which is the case if no `--doctest-filter`
+    /// was given, or if the item is, or is inside, one of the items given.
+    fn matches_filters(&self) -> bool {
+        if self.filters.is_empty() {
+            return true;
+        }
+        let path = self.names.join("::");
+        self.filters.iter().any(|filter| {
+            path == *filter ||
+                (path.starts_with(filter.as_str()) && path[filter.len()..].starts_with("::"))
+        })
     }
 
     pub fn set_position(&mut self, position: Span) {
@@ -625,7 +901,12 @@ impl Collector {
 impl Tester for Collector {
     fn add_test(&mut self, test: String, config: LangString, line: usize) {
         let filename = self.get_filename();
-        let name = self.generate_name(line, &filename);
+        // The test is named even if it's filtered out, so that it doesn't change the names of
+        // the other tests of the item.
+        let name = self.generate_name(&filename, line);
+        if !self.matches_filters() {
+            return;
+        }
         let cfgs = self.cfgs.clone();
         let libs = self.libs.clone();
         let cg = self.cg.clone();
@@ -635,6 +916,11 @@ impl Tester for Collector {
         let maybe_sysroot = self.maybe_sysroot.clone();
         let linker = self.linker.clone();
         let edition = config.edition.unwrap_or(self.edition);
+        let merged = self.merge_test(&test, &config);
+        let persist_dir = self.persist_doctests.as_ref().map(|dir| dir.join(dir_name(&name)));
+        let merged_persist_dir = self.persist_doctests.as_ref()
+                                                      .map(|dir| dir.join("merged_doctests"));
+        let timeout = self.timeout;
         debug!("Creating test {}: {}", name, test);
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
@@ -651,6 +937,27 @@ impl Tester for Collector {
                     rustc_driver::in_named_rustc_thread(name, move || with_globals(move || {
                         io::set_panic(panic);
                         io::set_print(print);
+                        if let Some((merged, index)) = merged {
+                            let compiled = compile_merged_tests(&merged,
+                                                                &cratename,
+                                                                cfgs.clone(),
+                                                                libs.clone(),
+                                                                cg.clone(),
+                                                                externs.clone(),
+                                                                &opts,
+                                                                maybe_sysroot.clone(),
+                                                                linker.clone(),
+                                                                edition,
+                                                                merged_persist_dir);
+                            if let Some((libdir, outdir)) = compiled {
+                                run_executable(&outdir,
+                                               libdir,
+                                               &[index.to_string()],
+                                               config.should_panic,
+                                               timeout);
+                                return;
+                            }
+                        }
                         run_test(&test,
                                  &cratename,
                                  &filename,
@@ -667,7 +974,9 @@ impl Tester for Collector {
                                  &opts,
                                  maybe_sysroot,
                                  linker,
-                                 edition)
+                                 edition,
+                                 persist_dir,
+                                 timeout)
                     }))
                 } {
                     Ok(()) => (),
@@ -726,6 +1035,17 @@ impl Tester for Collector {
     }
 }
 
+/// The name of the directory a test named `name` is kept in with `--persist-doctests`, made of
+/// the words of its name separated by underscores. The line of the test is left out, so that the
+/// directory stays the same when the file is edited.
+fn dir_name(name: &str) -> String {
+    let name = name.rsplitn(2, " (line ").last().unwrap_or(name);
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
 struct HirCollector<'a, 'hir: 'a> {
     sess: &'a session::Session,
     collector: &'a mut Collector,
@@ -828,7 +1148,7 @@ impl<'a, 'hir> intravisit::Visitor<'hir> for HirCollector<'a, 'hir> {
 
 #[cfg(test)]
 mod tests {
-    use super::{TestOptions, make_test};
+    use super::{TestOptions, make_test, make_merged_test, merged_test_body};
 
     #[test]
     fn make_test_basic() {
@@ -1106,4 +1426,51 @@ assert_eq!(asdf::foo, 4);
         let output = make_test(input, Some("asdf"), false, &opts);
         assert_eq!(output, (expected, 3));
     }
+
+    #[test]
+    fn merged_test_body_special() {
+        // tests with crate attributes, `extern crate` items or a `fn main` are not merged
+        assert_eq!(merged_test_body("#![feature(sure)]\nassert!(true);"), None);
+        assert_eq!(merged_test_body("extern crate asdf;\nassert!(true);"), None);
+        assert_eq!(merged_test_body("fn main() {\n    assert!(true);\n}"), None);
+        assert_eq!(merged_test_body("\nuse asdf::qwop;\nassert!(true);\n"),
+                   Some("use asdf::qwop;\nassert!(true);".to_string()));
+    }
+
+    #[test]
+    fn make_merged_test_basic() {
+        let opts = TestOptions::default();
+        let bodies = vec![
+            "assert_eq!(2+2, 4);".to_string(),
+            "use asdf::qwop;\nassert_eq!(qwop(), 4);".to_string(),
+        ];
+        let expected =
+"#![allow(unused)]
+extern crate asdf;
+mod __doctest_0 {
+#[allow(unused_imports)]
+use super::*;
+pub fn main() {
+assert_eq!(2+2, 4);
+}
+}
+mod __doctest_1 {
+#[allow(unused_imports)]
+use super::*;
+pub fn main() {
+use asdf::qwop;
+assert_eq!(qwop(), 4);
+}
+}
+fn main() {
+    let index = ::std::env::args().nth(1).and_then(|arg| arg.parse().ok());
+    match index {
+        Some(0) => __doctest_0::main(),
+        Some(1) => __doctest_1::main(),
+        _ => panic!(\"expected the index of a doctest\"),
+    }
+}".to_string();
+        let output = make_merged_test(&bodies, "asdf", &opts);
+        assert_eq!(output, expected);
+    }
 }
@@ -311,6 +311,7 @@
 #![feature(panic_info_message)]
 #![feature(non_exhaustive)]
 #![feature(alloc_layout_extra)]
+#![cfg_attr(not(stage0), feature(must_not_suspend))]
 
 #![default_lib_allocator]
 
@@ -151,6 +151,7 @@ unsafe impl<T: ?Sized + Send> Sync for Mutex<T> { }
 /// [`try_lock`]: struct.Mutex.html#method.try_lock
 /// [`Mutex`]: struct.Mutex.html
 #[must_use = "if unused the Mutex will immediately unlock"]
+#[cfg_attr(not(stage0), must_not_suspend = "holding a MutexGuard across a `yield` can deadlock")]
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct MutexGuard<'a, T: ?Sized + 'a> {
     // funny underscores due to how Deref/DerefMut currently work (they
@@ -116,6 +116,8 @@ unsafe impl<'a, T: ?Sized + Sync> Sync for RwLockReadGuard<'a, T> {}
 /// [`try_write`]: struct.RwLock.html#method.try_write
 /// [`RwLock`]: struct.RwLock.html
 #[must_use = "if unused the RwLock will immediately unlock"]
+#[cfg_attr(not(stage0),
+           must_not_suspend = "holding a RwLockWriteGuard across a `yield` can deadlock")]
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct RwLockWriteGuard<'a, T: ?Sized + 'a> {
     __lock: &'a RwLock<T>,
@@ -495,6 +495,22 @@ declare_features! (
 
     // `extern crate self as foo;` puts local crate root into extern prelude under name `foo`.
     (active, extern_crate_self, "1.31.0", Some(54658), None),
+
+    // Allows `if`, `match`, `&&` and `||` in constants and `const fn`.
+    (active, const_if_match, "1.32.0", None, None),
+
+    // Allows `loop`, `while` and `while let` in constants and `const fn`.
+    (active, const_loop, "1.32.0", None, None),
+
+    // Allows `box` in constants and `const fn`, as long as the allocation is freed
+    // before evaluation ends or only ends up in the value of an immutable static.
+    (active, const_heap, "1.32.0", None, None),
+
+    // Allows `#[must_not_suspend]` on types whose values should not be held across a `yield`.
+    (active, must_not_suspend, "1.32.0", None, None),
+
+    // Allows `#[deny_panics]` on functions that must not be able to panic.
+    (active, deny_panics, "1.32.0", None, None),
 );
 
 declare_features! (
@@ -818,6 +834,18 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                           "non exhaustive is an experimental feature",
                                           cfg_fn!(non_exhaustive))),
 
+    ("must_not_suspend", Whitelisted, Gated(Stability::Unstable,
+                                            "must_not_suspend",
+                                            "the `#[must_not_suspend]` attribute \
+                                             is an experimental feature",
+                                            cfg_fn!(must_not_suspend))),
+
+    ("deny_panics", Whitelisted, Gated(Stability::Unstable,
+                                       "deny_panics",
+                                       "the `#[deny_panics]` attribute \
+                                        is an experimental feature",
+                                       cfg_fn!(deny_panics))),
+
     // RFC #1268
     ("marker", Normal, Gated(Stability::Unstable,
                              "marker_trait_attr",
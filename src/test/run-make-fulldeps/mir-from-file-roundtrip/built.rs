@@ -0,0 +1,18 @@
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn sum_to(n: u32) -> u32 {
+    let mut i = n;
+    let mut total = 0;
+    while i > 0 {
+        total += double(i);
+        i -= 1;
+    }
+    total
+}
+
+fn main() {
+    assert_eq!(sum_to(0), 0);
+    assert_eq!(sum_to(4), 20);
+}
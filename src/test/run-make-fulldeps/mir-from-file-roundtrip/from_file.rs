@@ -0,0 +1,15 @@
+#![feature(rustc_attrs)]
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+#[rustc_mir(from_file = "sum_to.mir")]
+fn sum_to(_n: u32) -> u32 {
+    unreachable!()
+}
+
+fn main() {
+    assert_eq!(sum_to(0), 0);
+    assert_eq!(sum_to(4), 20);
+}
@@ -0,0 +1,53 @@
+#![crate_name = "foo"]
+#![feature(non_exhaustive)]
+
+use std::rc::Rc;
+
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Point {
+    // Renaming arguments does not change the signature.
+    pub fn new(a: i32, b: i32) -> Point {
+        Point { x: a, y: b, z: 0 }
+    }
+}
+
+pub struct Config {
+    pub verbose: bool,
+    pub color: bool,
+    _private: (),
+}
+
+pub enum Shape {
+    Circle,
+    Square,
+    Triangle,
+}
+
+#[non_exhaustive]
+pub enum Error {
+    Io,
+    Parse,
+}
+
+pub trait Draw {
+    fn draw(&self);
+    fn size(&self) -> u32;
+    fn name(&self) -> String {
+        String::new()
+    }
+}
+
+pub struct Handle {
+    _inner: Rc<u32>,
+}
+
+pub fn area(_shape: &Shape, _scale: f64) -> f64 {
+    0.0
+}
+
+pub fn added() {}
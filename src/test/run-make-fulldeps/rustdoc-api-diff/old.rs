@@ -0,0 +1,42 @@
+#![crate_name = "foo"]
+#![feature(non_exhaustive)]
+
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+}
+
+pub struct Config {
+    pub verbose: bool,
+    _private: (),
+}
+
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+#[non_exhaustive]
+pub enum Error {
+    Io,
+}
+
+pub trait Draw {
+    fn draw(&self);
+}
+
+pub struct Handle {
+    _inner: u32,
+}
+
+pub fn area(_shape: &Shape) -> f64 {
+    0.0
+}
+
+pub fn removed() {}
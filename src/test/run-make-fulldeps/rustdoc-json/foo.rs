@@ -0,0 +1,23 @@
+#![crate_name = "foo"]
+
+use std::fmt::Debug;
+
+pub mod inner {
+    /// A wrapper around a value.
+    pub struct Wrapper<T> {
+        pub value: T,
+    }
+}
+
+/// Something that can describe itself.
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl<T> Describe for inner::Wrapper<T> where T: Debug {
+    fn describe(&self) -> String {
+        format!("{:?}", self.value)
+    }
+}
+
+fn hidden() {}
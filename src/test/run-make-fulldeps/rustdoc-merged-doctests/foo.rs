@@ -0,0 +1,29 @@
+/// ```
+/// assert_eq!(foo::add_one(1), 2);
+/// ```
+pub fn add_one(x: i32) -> i32 {
+    x + 1
+}
+
+/// ```should_panic
+/// foo::fail();
+/// ```
+pub fn fail() {
+    panic!("oh no");
+}
+
+/// ```
+/// fn main() {
+///     assert_eq!(foo::add_one(2), 3);
+/// }
+/// ```
+pub struct OwnMain;
+
+/// ```
+/// let _ = foo::OwnMain;
+/// ```
+///
+/// ```ignore
+/// this is not compiled with the other tests
+/// ```
+pub struct Merged;
@@ -0,0 +1,8 @@
+extern crate foo;
+
+fn main() {
+    let mut counter = foo::Counter::new();
+    counter.incr();
+    let two = foo::add_one(1);
+    assert_eq!(two, 2);
+}
@@ -0,0 +1,26 @@
+#![crate_name = "foo"]
+
+/// Adds one to `x`.
+pub fn add_one(x: u32) -> u32 {
+    x + 1
+}
+
+/// Counts things.
+pub struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    /// Starts counting from zero.
+    pub fn new() -> Counter {
+        Counter { count: 0 }
+    }
+
+    /// Counts one more thing.
+    pub fn incr(&mut self) {
+        self.count += 1;
+    }
+}
+
+/// Never called from the example.
+pub fn unused() {}
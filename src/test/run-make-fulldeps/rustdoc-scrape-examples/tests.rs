@@ -0,0 +1,8 @@
+extern crate foo;
+
+#[test]
+fn counts_to_one() {
+    let mut counter = foo::Counter::new();
+    counter.incr();
+    assert_eq!(foo::add_one(0), 1);
+}
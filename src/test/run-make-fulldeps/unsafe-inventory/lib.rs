@@ -0,0 +1,28 @@
+#![crate_type = "lib"]
+
+static mut COUNTER: u32 = 0;
+
+pub struct Handle(*mut u8);
+
+unsafe impl Send for Handle {}
+
+pub fn read(handle: &Handle) -> u8 {
+    // SAFETY: a `Handle` always points to a live byte.
+    unsafe { *handle.0 }
+}
+
+pub unsafe fn write(handle: &Handle, value: u8) {
+    *handle.0 = value;
+    unsafe { COUNTER += 1 }
+}
+
+pub fn write_twice(handle: &Handle, value: u8) {
+    unsafe {
+        write(handle, value);
+        write(handle, value);
+    }
+}
+
+pub fn safe(value: u8) -> u8 {
+    value
+}
@@ -0,0 +1,30 @@
+#![crate_type = "rlib"]
+
+pub struct Used(pub u32);
+
+impl Used {
+    pub fn method(&self) -> u32 {
+        internal()
+    }
+
+    pub fn unused_method(&self) {}
+}
+
+pub trait Trait {
+    fn trait_method(&self);
+}
+
+impl Trait for Used {
+    fn trait_method(&self) {}
+}
+
+pub fn used() {}
+
+pub fn unused() {}
+
+pub fn internal() -> u32 {
+    1
+}
+
+#[allow(dead_code)]
+pub fn allowed() {}
@@ -0,0 +1,10 @@
+extern crate lib;
+
+use lib::Trait;
+
+fn main() {
+    lib::used();
+    let used = lib::Used(0);
+    used.method();
+    used.trait_method();
+}
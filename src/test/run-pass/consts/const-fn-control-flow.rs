@@ -0,0 +1,49 @@
+// run-pass
+// Test branches and loops in constants and `const fn`.
+
+#![feature(const_fn, const_let, const_if_match, const_loop)]
+
+const fn fib(n: u32) -> u32 {
+    let mut a = 0;
+    let mut b = 1;
+    let mut i = 0;
+    while i < n {
+        let next = a + b;
+        a = b;
+        b = next;
+        i += 1;
+    }
+    a
+}
+
+const fn digit_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        _ => None,
+    }
+}
+
+const fn first_set_bit(x: u32) -> u32 {
+    let mut bit = 0;
+    loop {
+        if bit == 32 || x & (1 << bit) != 0 {
+            return bit;
+        }
+        bit += 1;
+    }
+}
+
+const FIB: u32 = fib(10);
+const DIGIT: Option<u8> = digit_value(b'c');
+const NOT_A_DIGIT: Option<u8> = digit_value(b'z');
+const BIT: u32 = first_set_bit(0b1000);
+const SHORT_CIRCUIT: bool = BIT > 2 && FIB < 100;
+
+fn main() {
+    assert_eq!(FIB, 55);
+    assert_eq!(DIGIT, Some(12));
+    assert_eq!(NOT_A_DIGIT, None);
+    assert_eq!(BIT, 3);
+    assert!(SHORT_CIRCUIT);
+}
@@ -0,0 +1,16 @@
+// run-pass
+// Test heap allocations during constant evaluation.
+
+#![feature(box_syntax, const_let, const_heap)]
+
+const UNBOXED: i32 = {
+    let b = box 42;
+    *b
+};
+
+static BOXED: Box<[u8; 3]> = box [1, 2, 3];
+
+fn main() {
+    assert_eq!(UNBOXED, 42);
+    assert_eq!(*BOXED, [1, 2, 3]);
+}
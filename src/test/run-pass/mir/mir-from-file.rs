@@ -0,0 +1,17 @@
+#![feature(rustc_attrs)]
+
+// The MIR of `sum_to` is read from a file instead of being built from its body.
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+#[rustc_mir(from_file = "auxiliary/mir-from-file.mir")]
+fn sum_to(_n: u32) -> u32 {
+    unreachable!()
+}
+
+fn main() {
+    assert_eq!(sum_to(0), 0);
+    assert_eq!(sum_to(4), 20);
+}
@@ -0,0 +1,11 @@
+// The items of the crates other than the one a query starts with aren't searched.
+
+// should-fail
+
+const QUERY = 'alloc::vec::Vec';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'std::vec', 'name': 'Vec' },
+    ],
+};
@@ -0,0 +1,9 @@
+// A query starting with the name of a crate only looks through the items of that crate.
+
+const QUERY = 'alloc::vec::Vec';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'alloc::vec', 'name': 'Vec' },
+    ],
+};
@@ -0,0 +1,8 @@
+const QUERY = '* -> vec';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'std::vec::Vec', 'name': 'new' },
+        { 'path': 'std::vec::Vec', 'name': 'with_capacity' },
+    ],
+};
@@ -0,0 +1,7 @@
+const QUERY = '&str, usize -> String';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'std::str', 'name': 'repeat' },
+    ],
+};
@@ -0,0 +1,9 @@
+// The functions taking a `String` come before the generic ones.
+const QUERY = 'String -> usize';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'std::string::String', 'name': 'len' },
+        { 'path': 'std::mem', 'name': 'size_of_val' },
+    ],
+};
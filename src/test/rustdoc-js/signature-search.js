@@ -0,0 +1,10 @@
+const QUERY = 'Vec<T> -> Option<T>';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'std::vec::Vec', 'name': 'pop' },
+    ],
+    'returned': [
+        { 'path': 'std::vec::Vec', 'name': 'pop' },
+    ],
+};
@@ -0,0 +1,23 @@
+// The lints about the Markdown of documentation point to the problem in the doc comment:
+//
+// * `invalid_html_tags`, for HTML tags which are never closed, or never opened,
+// * `bare_urls`, for URLs which aren't links,
+// * `unclosed_code_blocks`, for code blocks whose fence is never closed,
+// * `broken_footnote_references`, for references to footnotes which aren't defined.
+//
+// `invalid_html_tags` is allowed by default, the others warn.
+
+#![deny(invalid_html_tags, bare_urls, unclosed_code_blocks, broken_footnote_references)]
+
+/// Details are at https://example.com/docs.
+pub fn bare_url() {}
+
+/// Returns a Vec<u8> of the bytes.
+pub fn unclosed_tag() -> Vec<u8> { Vec::new() }
+
+/// Works like this[^note].
+pub fn missing_footnote() {}
+
+/// ```
+/// let x = 1;
+pub fn unclosed_code_block() {}
@@ -0,0 +1,27 @@
+// Only the doctests of the items given with `--doctest-filter`, and of the items inside them, are
+// run. The doctests of an item are numbered in their names.
+
+// compile-flags:--test -Z unstable-options --doctest-filter Foo
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// compile-pass
+
+/// ```
+/// assert!(true);
+/// ```
+///
+/// ```
+/// assert!(true);
+/// ```
+pub struct Foo;
+
+impl Foo {
+    /// ```
+    /// assert!(true);
+    /// ```
+    pub fn bar() {}
+}
+
+/// ```
+/// assert!(false);
+/// ```
+pub struct FooBar;
@@ -0,0 +1,11 @@
+// A doctest still running after the `--doctest-timeout` is killed, and fails.
+
+// compile-flags:--test -Z unstable-options --doctest-timeout 1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// failure-status: 101
+// rustc-env:RUST_BACKTRACE=0
+
+/// ```
+/// loop {}
+/// ```
+pub struct Forever;
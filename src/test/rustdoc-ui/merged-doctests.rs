@@ -0,0 +1,30 @@
+// Doctests without a `fn main` or crate attributes are compiled into a single executable, the
+// others on their own.
+
+// compile-flags:--test -Z unstable-options --merge-doctests
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// compile-pass
+
+/// ```
+/// let x = 2 + 2;
+/// assert_eq!(x, 4);
+/// ```
+pub struct Merged;
+
+/// ```should_panic
+/// panic!("oh no");
+/// ```
+pub struct ShouldPanic;
+
+/// ```
+/// fn main() {
+///     assert!(true);
+/// }
+/// ```
+pub struct OwnMain;
+
+/// ```
+/// #![allow(dead_code)]
+/// struct Unused;
+/// ```
+pub struct CrateAttrs;
@@ -0,0 +1,7 @@
+// compile-pass
+// compile-flags:-Z unstable-options --show-coverage --output-format json
+
+/// A documented function.
+pub fn documented() {}
+
+pub fn undocumented() {}
@@ -0,0 +1,45 @@
+// compile-pass
+// compile-flags:-Z unstable-options --show-coverage
+
+//! The crate root.
+
+/// A documented function.
+///
+/// ```
+/// let x = 1;
+/// ```
+pub fn documented() {}
+
+pub fn undocumented() {}
+
+/// A point.
+pub struct Point {
+    /// The x coordinate.
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    /// Makes a point at the origin.
+    pub fn new() -> Point {
+        Point { x: 0, y: 0 }
+    }
+}
+
+// The items of trait impls are not counted.
+impl Clone for Point {
+    fn clone(&self) -> Point {
+        Point { x: self.x, y: self.y }
+    }
+}
+
+pub mod inner {
+    pub trait Shape {
+        /// The area of the shape.
+        ///
+        /// ```
+        /// assert!(true);
+        /// ```
+        fn area(&self) -> f64;
+    }
+}
@@ -16,7 +16,7 @@ pub mod str {
 
     #[lang = "str_alloc"]
     impl str {
-        // @has search-index.js foo
+        // @has search-index/issue_23511.js foo
         pub fn foo(&self) {}
     }
 }
@@ -0,0 +1,48 @@
+// compile-flags: -Z unstable-options --generate-link-to-definition
+
+#![crate_name = "foo"]
+
+// @has src/foo/link-to-definition.rs.html
+// @has src/foo/link-to-definition/helpers.rs.html
+
+#[path = "link-to-definition/helpers.rs"]
+mod helpers;
+
+pub struct Bar;
+
+impl Bar {
+    pub fn new() -> Bar {
+        Bar
+    }
+
+    pub fn get(&self) -> u32 {
+        1
+    }
+}
+
+// @has src/foo/link-to-definition.rs.html
+// @has - '//a[@href="../../src/foo/link-to-definition.rs.html#11"]' 'Bar'
+// @has - '//a[@href="../../src/foo/link-to-definition.rs.html#14"]' 'new'
+// @has - '//a[@href="../../src/foo/link-to-definition.rs.html#18"]' 'get'
+// @has - '//a[@href="../../src/foo/link-to-definition.rs.html#31"]' 'bar'
+// @has - '//a[@href="../../src/foo/link-to-definition/helpers.rs.html#1"]' 'double'
+// @has - '//a[@href="https://doc.rust-lang.org/nightly/alloc/string/struct.String.html"]' 'String'
+pub fn foo(x: u32) -> String {
+    let bar = Bar::new();
+    let y = helpers::double(x) + bar.get();
+    String::from(if y > 2 { "big" } else { "small" })
+}
+
+// The names of the definitions link to the list of their references.
+// @has src/foo/link-to-definition.rs.html
+// @has - '//a[@href="#references-255"]' 'Bar'
+// @has - '//h3[@id="references-255"]' 'References to Bar'
+// @has - '//h3[a[@href="#11"]]/following-sibling::ul[1]/li/a' 'link-to-definition.rs:13'
+// @has - '//h3[a[@href="#11"]]/following-sibling::ul[1]/li/a' 'link-to-definition.rs:15'
+// @has - '//h3[a[@href="#11"]]/following-sibling::ul[1]/li/a' 'link-to-definition.rs:31'
+// @has - '//h3[a[@href="#31"]]' 'References to bar'
+// @has - '//h3[a[@href="#31"]]/following-sibling::ul[1]/li/a' 'link-to-definition.rs:32'
+// @has - '//li/a[@href="../../src/foo/link-to-definition.rs.html#32"]' 'link-to-definition.rs:32'
+// @has src/foo/link-to-definition/helpers.rs.html
+// @has - '//h3[a[@href="#1"]]' 'References to double'
+// @has - '//a[@href="../../../src/foo/link-to-definition.rs.html#32"]' 'link-to-definition.rs:32'
@@ -0,0 +1,13 @@
+#![crate_name = "foo"]
+
+// The crate is listed in `search-index.js`, and its items are in its own shard of the index.
+
+// @has search-index.js 'searchIndex["foo"]'
+// @!has - 'bar_method'
+// @has search-index/foo.js 'addSearchShard("foo"'
+// @has - 'bar_method'
+pub struct Bar;
+
+impl Bar {
+    pub fn bar_method() {}
+}
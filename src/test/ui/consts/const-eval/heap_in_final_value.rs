@@ -0,0 +1,13 @@
+#![feature(box_syntax, const_heap)]
+
+use std::sync::atomic::AtomicUsize;
+
+static mut S: Box<i32> = box 5; //~ ERROR could not evaluate static initializer
+
+const C: Box<i32> = box 5; //~ ERROR any use of this value will cause an error
+
+// `Box` is `Freeze` whatever it points to, the heap memory is not.
+static ATOMIC: Box<AtomicUsize> = box AtomicUsize::new(0);
+//~^ ERROR could not evaluate static initializer
+
+fn main() {}
@@ -0,0 +1,11 @@
+// Heap memory has to be freed before the end of constant evaluation, unless it is part of
+// the final value.
+
+#![feature(box_syntax, const_let, const_heap)]
+
+use std::mem::ManuallyDrop;
+
+const LEAK: i32 = { let _b = ManuallyDrop::new(box 5); 5 };
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
@@ -0,0 +1,13 @@
+// compile-flags: -Z const-eval-step-limit=1000
+
+#![feature(const_let, const_if_match, const_loop)]
+
+fn main() {
+    let _ = [(); {
+        let mut n = 0usize;
+        while n < 100_000 { //~ ERROR evaluation of constant value failed
+            n += 1;
+        }
+        n
+    }];
+}
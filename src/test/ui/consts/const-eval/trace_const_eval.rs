@@ -0,0 +1,8 @@
+// compile-pass
+// compile-flags: -Z trace-const-eval=FOO
+
+const FOO: i32 = 5;
+
+fn main() {
+    let _ = FOO;
+}
@@ -0,0 +1,19 @@
+// compile-pass
+// compile-flags: -Z trace-const-eval=SUM
+// normalize-stderr-test "(?m)^ +(bb\d|_\d).*\n" -> ""
+
+// The trace shows the frames entered and left while evaluating a constant.
+
+const fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+const fn sum3(a: i32, b: i32, c: i32) -> i32 {
+    add(add(a, b), c)
+}
+
+const SUM: i32 = sum3(1, 2, 3);
+
+fn main() {
+    let _ = SUM;
+}
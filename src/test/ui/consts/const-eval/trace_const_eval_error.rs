@@ -0,0 +1,13 @@
+// compile-flags: -Z trace-const-eval=QUOTIENT
+// normalize-stderr-test "(?m)^ +(bb\d|_\d).*\n" -> ""
+
+// The trace of a failing evaluation ends with the frames the error happened in.
+
+const fn div(a: i32, b: i32) -> i32 {
+    a / b
+}
+
+const QUOTIENT: i32 = div(1, 0);
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
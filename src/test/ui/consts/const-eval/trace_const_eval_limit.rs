@@ -0,0 +1,15 @@
+// compile-pass
+// compile-flags: -Z trace-const-eval=SUM -Z trace-const-eval-limit=1
+// normalize-stderr-test "(?m)^( +)bb\d.*" -> "$1<step>"
+
+// Only the first `-Z trace-const-eval-limit` steps are printed.
+
+const fn sum3(a: i32, b: i32, c: i32) -> i32 {
+    a + b + c
+}
+
+const SUM: i32 = sum3(1, 2, 3);
+
+fn main() {
+    let _ = SUM;
+}
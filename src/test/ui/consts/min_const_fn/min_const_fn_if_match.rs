@@ -0,0 +1,24 @@
+// compile-pass
+
+#![feature(const_if_match)]
+
+const fn abs(x: i32) -> i32 {
+    if x < 0 { -x } else { x }
+}
+
+const fn is_some(x: Option<u8>) -> bool {
+    match x {
+        Some(_) => true,
+        None => false,
+    }
+}
+
+const fn both(a: bool, b: bool) -> bool {
+    a && b
+}
+
+const ABS: i32 = abs(-3);
+const IS_SOME: bool = is_some(Some(1));
+const BOTH: bool = both(IS_SOME, ABS == 3);
+
+fn main() {}
@@ -0,0 +1,64 @@
+// compile-flags: -C overflow-checks=on
+
+#![feature(deny_panics)]
+
+#[deny_panics]
+fn add(a: u8, b: u8) -> u8 { //~ ERROR `#[deny_panics]` function `add` can panic
+    a + b
+}
+
+#[deny_panics]
+fn first(xs: &[u32]) -> u32 { //~ ERROR `#[deny_panics]` function `first` can panic
+    get(xs, 0)
+}
+
+fn get(xs: &[u32], i: usize) -> u32 {
+    xs[i]
+}
+
+#[deny_panics]
+fn low_bits(a: u8) -> u8 {
+    a & 0x0f
+}
+
+#[deny_panics]
+fn checked(a: u8) -> u8 {
+    low_bits(a)
+}
+
+#[deny_panics]
+fn unwrap_value(x: Option<u32>) -> u32 { //~ ERROR function `unwrap_value` can panic
+    x.unwrap()
+}
+
+#[deny_panics]
+fn value_or_zero(x: Option<u32>) -> u32 {
+    x.unwrap_or(0)
+}
+
+extern "C" {
+    fn abs(x: i32) -> i32;
+    #[deny_panics]
+    fn labs(x: i64) -> i64;
+}
+
+#[deny_panics]
+fn call_abs(x: i32) -> i32 { //~ ERROR `#[deny_panics]` function `call_abs` can panic
+    unsafe { abs(x) }
+}
+
+// Foreign functions marked `#[deny_panics]` are trusted not to panic.
+#[deny_panics]
+fn call_labs(x: i64) -> i64 {
+    unsafe { labs(x) }
+}
+
+fn main() {
+    add(1, 2);
+    first(&[1]);
+    checked(3);
+    unwrap_value(Some(4));
+    value_or_zero(None);
+    call_abs(-5);
+    call_labs(-6);
+}
@@ -0,0 +1,8 @@
+// Test use of `box` in constants without the feature gate.
+
+#![feature(box_syntax)]
+#![allow(const_err)]
+
+const X: i32 = *(box 5); //~ ERROR allocations are not allowed in constants
+
+fn main() {}
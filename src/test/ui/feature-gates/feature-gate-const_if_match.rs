@@ -0,0 +1,9 @@
+// Test use of `if` in constants without the feature gate,
+// in a crate that allows loops.
+
+#![feature(const_let, const_loop)]
+
+const X: i32 = if true { 1 } else { 2 };
+//~^ ERROR `if`, `match`, `&&` and `||` in constants are unstable
+
+fn main() {}
@@ -0,0 +1,13 @@
+// Test use of loops in constants without the feature gate,
+// in a crate that allows branches.
+
+#![feature(const_let, const_if_match)]
+
+const X: i32 = {
+    let mut n = 0;
+    while n < 10 { n += 1; }
+    //~^ ERROR loops in constants are unstable
+    n
+};
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[deny_panics] //~ ERROR the `#[deny_panics]` attribute is an experimental feature
+fn f() {}
+
+fn main() {}
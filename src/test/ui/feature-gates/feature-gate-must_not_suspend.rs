@@ -0,0 +1,4 @@
+#[must_not_suspend = "not across a yield"] //~ ERROR the `#[must_not_suspend]` attribute is
+struct Guard;
+
+fn main() {}
@@ -0,0 +1,28 @@
+#![feature(generators)]
+#![deny(must_not_suspend)]
+
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+fn main() {
+    let cell = RefCell::new(0);
+    let mutex = Mutex::new(0);
+
+    let _ = || {
+        let mut borrow = cell.borrow_mut(); //~ ERROR `std::cell::RefMut` held across a `yield`
+        yield;
+        *borrow += 1;
+    };
+
+    let _ = || {
+        let guard = mutex.lock().unwrap(); //~ ERROR `std::sync::MutexGuard` held across a `yield`
+        yield;
+        drop(guard);
+    };
+
+    let _ = || {
+        *cell.borrow_mut() += 1;
+        yield;
+        drop(mutex.lock().unwrap());
+    };
+}
@@ -0,0 +1,28 @@
+#![deny(truncating_casts, sign_changing_casts, float_to_int_casts, lossless_casts)]
+
+fn main() {
+    let a: u64 = 1;
+    let b: i32 = -1;
+    let c: f64 = 1.5;
+    let d: u8 = 1;
+    let e: char = 'a';
+    let f: usize = 1;
+
+    let _ = a as u32; //~ ERROR truncating cast: `u64` as `u32`
+    let _ = b as u64; //~ ERROR sign-changing cast: `i32` as `u64`
+    let _ = c as u8; //~ ERROR float-to-integer cast: `f64` as `u8`
+    let _ = d as i8; //~ ERROR sign-changing cast: `u8` as `i8`
+    let _ = a as usize; //~ ERROR truncating cast: `u64` as `usize`
+    let _ = f as u32; //~ ERROR truncating cast: `usize` as `u32`
+    let _ = e as u8; //~ ERROR truncating cast: `char` as `u8`
+    let _ = d as u32; //~ ERROR lossless cast: `u8` as `u32`
+    let _ = d as i16; //~ ERROR lossless cast: `u8` as `i16`
+    let _ = d as usize; //~ ERROR lossless cast: `u8` as `usize`
+    let _ = d as char; //~ ERROR lossless cast: `u8` as `char`
+    let _ = e as u32; //~ ERROR lossless cast: `char` as `u32`
+
+    // Lossless, but there is no `From` impl for these.
+    let _ = f as u64;
+    let _ = e as i32;
+    let _ = a as f64;
+}
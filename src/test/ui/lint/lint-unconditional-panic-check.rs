@@ -0,0 +1,20 @@
+// compile-pass
+// compile-flags: --emit=metadata -Z mir-opt-level=3
+
+// The lint runs during analysis, so it is reported without codegen, and it looks at the MIR of
+// the functions called before any call in it is inlined.
+
+#[inline(always)]
+fn div(a: u32, b: u32) -> u32 {
+    a / b
+}
+
+fn div_one_by(b: u32) -> u32 {
+    div(1, b)
+}
+
+fn main() {
+    div(1, 0); //~ WARN this call to `div` will panic at runtime
+    // Only seen to panic if `div` is inlined into `div_one_by`.
+    div_one_by(0);
+}
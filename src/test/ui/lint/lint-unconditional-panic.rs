@@ -0,0 +1,29 @@
+// compile-pass
+// compile-flags: -C overflow-checks=on
+
+fn get(arr: [u8; 3], i: usize) -> u8 {
+    arr[i]
+}
+
+fn checked_get(arr: [u8; 3], i: usize) -> u8 {
+    if i < 3 { arr[i] } else { 0 }
+}
+
+fn div(a: u32, b: u32) -> u32 {
+    a / b
+}
+
+fn add(a: u8, b: u8) -> u8 {
+    a + b
+}
+
+fn main() {
+    let arr = [1, 2, 3];
+    get(arr, 1);
+    get(arr, 5); //~ WARN this call to `get` will panic at runtime
+    checked_get(arr, 5);
+    div(1, 0); //~ WARN this call to `div` will panic at runtime
+    div(1, 2);
+    add(200, 100); //~ WARN this call to `add` will panic at runtime
+    add(100, 100);
+}
@@ -0,0 +1,37 @@
+#![deny(unconditional_recursion)]
+#![allow(dead_code)]
+
+use std::fmt;
+
+struct Celsius(f64);
+
+impl fmt::Display for Celsius {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { //~ ERROR cannot return without recursing
+        write!(f, "{}", self)
+    }
+}
+
+struct Name(String);
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { //~ ERROR cannot return without recursing
+        f.write_str(&self.to_string())
+    }
+}
+
+// Formatting a field, or the value with another trait, is fine.
+struct Meters(f64);
+
+impl fmt::Display for Meters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} m", self.0)
+    }
+}
+
+impl fmt::Debug for Meters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Meters({})", self)
+    }
+}
+
+fn main() {}
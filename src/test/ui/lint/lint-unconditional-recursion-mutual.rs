@@ -0,0 +1,30 @@
+#![deny(unconditional_recursion)]
+#![allow(dead_code)]
+
+fn even(n: u32) -> bool { //~ ERROR function cannot return without recursing
+    !odd(n)
+}
+
+fn odd(n: u32) -> bool {
+    even(n + 1)
+}
+
+// Bounded mutual recursion is fine.
+fn ping(n: u32) -> u32 {
+    if n == 0 { 0 } else { pong(n - 1) }
+}
+
+fn pong(n: u32) -> u32 {
+    ping(n)
+}
+
+struct Meters(u32);
+
+// `PartialEq::ne` calls `eq` by default.
+impl PartialEq for Meters {
+    fn eq(&self, other: &Meters) -> bool { //~ ERROR function cannot return without recursing
+        !self.ne(other)
+    }
+}
+
+fn main() {}
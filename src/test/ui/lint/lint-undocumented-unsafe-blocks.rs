@@ -0,0 +1,48 @@
+#![deny(undocumented_unsafe_blocks)]
+
+unsafe fn f() {}
+
+fn documented() {
+    // SAFETY: `f` has no preconditions.
+    unsafe { f() }
+
+    // SAFETY: `f` has no preconditions,
+    // and it is fine to call it twice.
+    unsafe {
+        f();
+        f();
+    }
+
+    let _x = /* SAFETY: `f` has no preconditions. */ unsafe { f() };
+
+    /*
+     * SAFETY: `f` has no preconditions.
+     */
+    unsafe { f() }
+}
+
+fn undocumented() {
+    unsafe { f() } //~ ERROR `unsafe` block without a `// SAFETY:` comment
+
+    // Calls `f`.
+    let _x = unsafe { //~ ERROR `unsafe` block without a `// SAFETY:` comment
+        f()
+    };
+
+    let mut x = 0;
+    let r = &mut x;
+    // SAFETY: this comment is about the line below, not the block.
+    *r = 1;
+    unsafe { f() } //~ ERROR `unsafe` block without a `// SAFETY:` comment
+}
+
+#[allow(undocumented_unsafe_blocks)]
+fn allowed() {
+    unsafe { f() }
+}
+
+fn main() {
+    documented();
+    undocumented();
+    allowed();
+}
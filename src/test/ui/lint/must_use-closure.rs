@@ -0,0 +1,21 @@
+#![deny(unused_must_use, unused_nested_must_use)]
+
+fn res() -> Result<(), ()> {
+    Ok(())
+}
+
+fn each<T, R, F: FnMut(&T) -> R>(items: &[T], mut f: F) {
+    for item in items {
+        f(item);
+    }
+}
+
+fn main() {
+    let v = [1, 2];
+    each(&v, |_| res()); //~ ERROR unused `std::result::Result` returned from a closure
+    each(&v, |_| Some(res())); //~ ERROR unused `std::result::Result` in `std::option::Option`
+    v.iter().for_each(|_| { res(); }); //~ ERROR unused `std::result::Result` that must be used
+
+    each(&v, |_| 1);
+    let _n = v.iter().map(|_| res()).count();
+}
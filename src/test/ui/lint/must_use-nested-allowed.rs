@@ -0,0 +1,21 @@
+// compile-pass
+
+#![deny(unused_must_use)]
+
+use std::collections::HashMap;
+
+fn res() -> Result<(), ()> {
+    Ok(())
+}
+
+fn main() {
+    // The value replaced by `insert` may be dropped, even if it must be used on its own.
+    let mut map = HashMap::new();
+    map.insert(1, Ok::<(), ()>(()));
+    map.insert(2, Ok(()));
+
+    // Only `Option` and `Box` are looked through, and only by `unused_nested_must_use`.
+    Some(res());
+    Box::new(res());
+    vec![res()];
+}
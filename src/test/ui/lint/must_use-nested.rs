@@ -0,0 +1,22 @@
+#![deny(unused_must_use, let_underscore_must_use, unused_nested_must_use)]
+
+fn res() -> Result<(), ()> {
+    Ok(())
+}
+
+fn wrap<T>(t: T) -> Option<T> {
+    Some(t)
+}
+
+fn main() {
+    wrap(res()); //~ ERROR unused `std::result::Result` in `std::option::Option` that must be used
+    (1, res()); //~ ERROR unused `std::result::Result` in tuple element 1 that must be used
+    [res()]; //~ ERROR unused `std::result::Result` in array that must be used
+    Some(1).map(|_| res()); //~ ERROR unused `std::result::Result` in `std::option::Option`
+    let _ = res(); //~ ERROR unused `std::result::Result` that must be used
+    let _ = Box::new(res()); //~ ERROR unused `std::result::Result` in `std::boxed::Box`
+
+    wrap(1);
+    let _ = wrap(1);
+    let _unused = res();
+}
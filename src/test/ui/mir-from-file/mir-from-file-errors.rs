@@ -0,0 +1,23 @@
+#![feature(rustc_attrs)]
+
+// Malformed MIR read from a file is reported as an error instead of crashing the compiler.
+
+#[rustc_mir(from_file = "auxiliary/deref-non-pointer.mir")] //~ ERROR cannot dereference
+fn deref_non_pointer(_x: u32) -> u32 { 0 }
+
+#[rustc_mir(from_file = "auxiliary/undeclared-index.mir")] //~ ERROR is not declared
+fn undeclared_index(_x: [u32; 2]) -> u32 { 0 }
+
+#[rustc_mir(from_file = "auxiliary/local-out-of-range.mir")] //~ ERROR is out of range
+fn local_out_of_range() -> u32 { 0 }
+
+#[rustc_mir(from_file = "auxiliary/mismatched-types.mir")] //~ ERROR is assigned a value
+fn mismatched_types(_x: u32) -> bool { false }
+
+#[rustc_mir(from_file = "auxiliary/inline-asm.mir")] //~ ERROR inline assembly
+fn inline_asm() {}
+
+#[rustc_mir(from_file = "auxiliary/set-discriminant.mir")] //~ ERROR setting the discriminant
+fn set_discriminant(_x: u32) {}
+
+fn main() {}
@@ -0,0 +1,54 @@
+#![feature(nll)]
+
+fn consume(_: Vec<i32>) {}
+
+fn use_after_move() {
+    let v = vec![1, 2, 3];
+    consume(v);
+    consume(v); //~ ERROR use of moved value: `v`
+}
+
+fn use_after_for_loop() {
+    let v = vec![1, 2, 3];
+    for x in v {
+        println!("{}", x);
+    }
+    println!("{:?}", v); //~ ERROR borrow of moved value: `v`
+}
+
+// `v` is moved by the explicit call, not by the loop.
+fn use_after_into_iter_loop() {
+    let v = vec![1, 2, 3];
+    for x in v.into_iter() {
+        println!("{}", x);
+    }
+    println!("{:?}", v); //~ ERROR borrow of moved value: `v`
+}
+
+struct Config {
+    name: Option<String>,
+}
+
+impl Config {
+    fn name_len(&self) -> usize {
+        self.name.unwrap().len() //~ ERROR cannot move out of borrowed content
+    }
+}
+
+struct Registry {
+    items: Vec<u32>,
+    next: u32,
+}
+
+impl Registry {
+    fn next_id(&mut self) -> u32 {
+        self.next += 1;
+        self.next
+    }
+
+    fn register(&mut self) {
+        self.items.push(self.next_id()); //~ ERROR cannot borrow `*self` as mutable more than once
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+#![crate_type="lib"]
+
+pub mod ext {
+    pub trait Ext {
+        fn ext_method(&self) {}
+    }
+
+    impl Ext for u8 {}
+}
+
+mod private {
+    pub trait Private {
+        fn private_method(&self) {}
+    }
+
+    impl Private for u8 {}
+}
@@ -0,0 +1,5 @@
+#![crate_type="lib"]
+
+pub extern crate trait_import_inner as inner;
+
+pub use inner::ext::Ext as Extension;
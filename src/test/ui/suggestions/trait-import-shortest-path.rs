@@ -0,0 +1,11 @@
+// aux-build:trait-import-inner.rs
+// aux-build:trait-import-outer.rs
+
+extern crate trait_import_outer;
+
+fn main() {
+    1u8.ext_method();
+    //~^ ERROR no method named `ext_method` found for type `u8` in the current scope
+    1u8.private_method();
+    //~^ ERROR no method named `private_method` found for type `u8` in the current scope
+}
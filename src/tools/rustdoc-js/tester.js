@@ -229,6 +229,21 @@ function main(argv) {
     }
     searchIndex.pop();
     searchIndex = loadContent(searchIndex.join("\n") + '\nexports.searchIndex = searchIndex;');
+    // The items of each crate are in its shard of the search index.
+    var shards = {};
+    var crates = [];
+    for (var crate in searchIndex.searchIndex) {
+        if (!searchIndex.searchIndex.hasOwnProperty(crate)) {
+            continue;
+        }
+        crates.push(crate);
+        var shard = loadContent('var N = null;var shard;' +
+                                'function addSearchShard(crate, data) { shard = data; }\n' +
+                                readFile("build/" + toolchain + "/doc/search-index/" + crate +
+                                         ".js") +
+                                '\nexports.shard = shard;');
+        shards[crate] = shard.shard;
+    }
     finalJS = "";
 
     var arraysToLoad = ["itemTypes"];
@@ -238,9 +253,11 @@ function main(argv) {
                            "levenshtein_row2"];
     // execQuery first parameter is built in getQuery (which takes in the search input).
     // execQuery last parameter is built in buildIndex.
-    // buildIndex requires the hashmap from search-index.
+    // buildIndex requires the hashmap from the shards of search-index that searchedCrates
+    // picks for the query, as main.js only loads those.
     var functionsToLoad = ["buildHrefAndPath", "pathSplitter", "levenshtein", "validateResult",
-                           "getQuery", "buildIndex", "execQuery", "execSearch"];
+                           "getQuery", "searchedCrates", "buildIndex", "execQuery",
+                           "execSearch"];
 
     finalJS += 'window = { "currentCrate": "std" };\n';
     finalJS += 'var rootPath = "../";\n';
@@ -250,7 +267,8 @@ function main(argv) {
     finalJS += loadThings(functionsToLoad, 'function', extractFunction, mainJs);
 
     var loaded = loadContent(finalJS);
-    var index = loaded.buildIndex(searchIndex.searchIndex);
+    // The index of the crates searched last, which are all of them for most queries.
+    var indexedCrates, index;
 
     var errors = 0;
 
@@ -262,6 +280,15 @@ function main(argv) {
         const ignore_order = loadedFile.ignore_order;
         const exact_check = loadedFile.exact_check;
         const should_fail = loadedFile.should_fail;
+        var searched = loaded.searchedCrates(query, crates);
+        if (indexedCrates !== searched.join(",")) {
+            var picked = {};
+            for (var i = 0; i < searched.length; ++i) {
+                picked[searched[i]] = shards[searched[i]];
+            }
+            index = loaded.buildIndex(picked);
+            indexedCrates = searched.join(",");
+        }
         var results = loaded.execSearch(loaded.getQuery(query), index);
         process.stdout.write('Checking "' + file + '" ... ');
         var error_text = [];